@@ -1,3 +1,5 @@
+-- Migration 1 : schéma initial (idempotent pour les bases créées avant les migrations versionnées)
+
 -- Table des fichiers avec toutes les métadonnées
 CREATE TABLE IF NOT EXISTS files (
     id INTEGER PRIMARY KEY,
@@ -51,14 +53,10 @@ CREATE VIRTUAL TABLE IF NOT EXISTS fts_content USING fts5(
 
 -- Index pour optimiser les performances de recherche
 CREATE INDEX IF NOT EXISTS idx_files_name ON files(name COLLATE NOCASE);
-CREATE INDEX IF NOT EXISTS idx_files_is_dir ON files(is_dir);
-CREATE INDEX IF NOT EXISTS idx_files_file_type ON files(file_type);
 CREATE INDEX IF NOT EXISTS idx_files_size ON files(size);
 CREATE INDEX IF NOT EXISTS idx_files_last_modified ON files(last_modified);
 CREATE INDEX IF NOT EXISTS idx_files_created_at ON files(created_at);
 CREATE INDEX IF NOT EXISTS idx_files_accessed_at ON files(accessed_at);
-CREATE INDEX IF NOT EXISTS idx_files_path ON files(path);
-CREATE INDEX IF NOT EXISTS idx_files_is_hidden ON files(is_hidden);
 CREATE INDEX IF NOT EXISTS idx_files_is_readonly ON files(is_readonly);
 CREATE INDEX IF NOT EXISTS idx_files_is_system ON files(is_system);
 CREATE INDEX IF NOT EXISTS idx_files_is_executable ON files(is_executable);
@@ -76,7 +74,6 @@ CREATE INDEX IF NOT EXISTS idx_files_is_encrypted ON files(is_encrypted);
 CREATE INDEX IF NOT EXISTS idx_files_type_size ON files(file_type, size);
 CREATE INDEX IF NOT EXISTS idx_files_dir_modified ON files(is_dir, last_modified);
 CREATE INDEX IF NOT EXISTS idx_files_indexed_type ON files(is_indexed, file_type);
-CREATE INDEX IF NOT EXISTS idx_files_path_name ON files(path, name);
 
 -- Pour les filtres complexes
 CREATE INDEX IF NOT EXISTS idx_files_flags ON files(is_hidden, is_system, is_readonly);
@@ -93,7 +90,7 @@ INSERT OR IGNORE INTO types (name) VALUES
     ('gz'), ('rs'), ('py'), ('java'), ('cpp'), ('c'), ('go'), ('php'), ('rb'),
     ('swift'), ('kt'), ('exe'), ('dll'), ('so'), ('dylib');
 
--- Nettoyage d’index redondants (sécurisé si exécuté plusieurs fois)
+-- Index redondants créés par les anciennes versions
 DROP INDEX IF EXISTS idx_files_path;
 DROP INDEX IF EXISTS idx_files_path_name;
 DROP INDEX IF EXISTS idx_files_is_dir;
@@ -101,9 +98,3 @@ DROP INDEX IF EXISTS idx_files_file_type;
 DROP INDEX IF EXISTS idx_files_is_hidden;
 
 ANALYZE;
-
-PRAGMA journal_mode = WAL;
-PRAGMA synchronous = NORMAL;
-PRAGMA cache_size = 10000;
-PRAGMA temp_store = MEMORY;
-PRAGMA mmap_size = 268435456;
\ No newline at end of file
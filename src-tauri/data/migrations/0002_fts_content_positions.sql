@@ -0,0 +1,14 @@
+-- Migration 2 : fts_content avec detail complet (positions nécessaires à snippet() et highlight())
+-- La table est reconstruite, son contenu recopié à l'identique
+CREATE VIRTUAL TABLE fts_content_migration USING fts5(
+    content,
+    file_id UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '3 4'
+);
+
+INSERT INTO fts_content_migration (content, file_id) SELECT content, file_id FROM fts_content;
+
+DROP TABLE fts_content;
+
+ALTER TABLE fts_content_migration RENAME TO fts_content;
@@ -0,0 +1,41 @@
+-- Migration 3 : règles d'inclusion/exclusion (globales ou par racine) et réglages persistés
+
+CREATE TABLE IF NOT EXISTS path_rules (
+    id INTEGER PRIMARY KEY,
+    pattern TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('include', 'exclude')),
+    -- NULL : règle globale, sinon chemin de la table paths
+    root TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_path_rules_root ON path_rules(root);
+
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- Exclusions par défaut : dossiers système et fichiers temporaires
+-- (les dossiers de build ne sont plus exclus d'office, voir respect_ignore_files)
+INSERT INTO path_rules (pattern, kind, root) VALUES
+    ('/proc/**', 'exclude', NULL),
+    ('/sys/**', 'exclude', NULL),
+    ('/System/**', 'exclude', NULL),
+    ('/private/**', 'exclude', NULL),
+    ('**/Windows/System32', 'exclude', NULL),
+    ('**/AppData/Local/Temp', 'exclude', NULL),
+    ('$RECYCLE.BIN', 'exclude', NULL),
+    ('System Volume Information', 'exclude', NULL),
+    ('.Trashes', 'exclude', NULL),
+    ('.fseventsd', 'exclude', NULL),
+    ('.TemporaryItems', 'exclude', NULL),
+    ('node_modules', 'exclude', NULL),
+    ('.git', 'exclude', NULL),
+    ('.vscode', 'exclude', NULL),
+    ('.idea', 'exclude', NULL),
+    ('*.tmp', 'exclude', NULL),
+    ('*.temp', 'exclude', NULL),
+    ('~$*', 'exclude', NULL),
+    ('.DS_Store', 'exclude', NULL);
+
+INSERT OR IGNORE INTO settings (key, value) VALUES ('respect_ignore_files', 'false');
@@ -0,0 +1,27 @@
+-- Migration 4 : index trigramme sur le nom et le chemin, pour les recherches par sous-chaîne
+-- (LIKE '%motif%' ne peut pas utiliser idx_files_name). Table à contenu externe, tenue à jour par triggers.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS fts_names USING fts5(
+    name,
+    path,
+    content = 'files',
+    content_rowid = 'id',
+    tokenize = 'trigram case_sensitive 0'
+);
+
+CREATE TRIGGER IF NOT EXISTS files_fts_names_insert AFTER INSERT ON files BEGIN
+    INSERT INTO fts_names (rowid, name, path) VALUES (new.id, new.name, new.path);
+END;
+
+CREATE TRIGGER IF NOT EXISTS files_fts_names_delete AFTER DELETE ON files BEGIN
+    INSERT INTO fts_names (fts_names, rowid, name, path) VALUES ('delete', old.id, old.name, old.path);
+END;
+
+-- Les upserts réécrivent le nom et le chemin : seules les vraies modifications touchent l'index
+CREATE TRIGGER IF NOT EXISTS files_fts_names_update AFTER UPDATE OF name, path ON files
+WHEN old.name IS NOT new.name OR old.path IS NOT new.path BEGIN
+    INSERT INTO fts_names (fts_names, rowid, name, path) VALUES ('delete', old.id, old.name, old.path);
+    INSERT INTO fts_names (rowid, name, path) VALUES (new.id, new.name, new.path);
+END;
+
+INSERT INTO fts_names (fts_names) VALUES ('rebuild');
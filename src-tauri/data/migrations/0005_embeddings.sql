@@ -0,0 +1,18 @@
+-- Migration 5 : vecteurs sémantiques des passages du contenu indexé (f32 little-endian, normalisés)
+
+CREATE TABLE IF NOT EXISTS embeddings (
+    id INTEGER PRIMARY KEY,
+    file_id INTEGER NOT NULL,
+    chunk_index INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    model TEXT NOT NULL,
+    dimensions INTEGER NOT NULL,
+    vector BLOB NOT NULL,
+    UNIQUE (file_id, chunk_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_embeddings_model ON embeddings(model, dimensions);
+
+CREATE TRIGGER IF NOT EXISTS files_embeddings_delete AFTER DELETE ON files BEGIN
+    DELETE FROM embeddings WHERE file_id = old.id;
+END;
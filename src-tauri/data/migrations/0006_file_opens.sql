@@ -0,0 +1,4 @@
+-- Migration 6 : ouvertures des fichiers depuis l'application, pour le signal de fréquence du classement
+
+ALTER TABLE files ADD COLUMN open_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE files ADD COLUMN last_opened INTEGER;
@@ -0,0 +1,12 @@
+-- Migration 7 : en-têtes des courriers électroniques et éléments virtuels contenus dans un fichier
+-- (messages d'une boîte mbox), rattachés à leur conteneur par parent_id
+
+ALTER TABLE files ADD COLUMN parent_id INTEGER;
+ALTER TABLE files ADD COLUMN mail_subject TEXT;
+ALTER TABLE files ADD COLUMN mail_from TEXT;
+ALTER TABLE files ADD COLUMN mail_to TEXT;
+ALTER TABLE files ADD COLUMN mail_date INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_files_parent_id ON files(parent_id);
+
+INSERT OR IGNORE INTO types (name) VALUES ('eml'), ('mbox');
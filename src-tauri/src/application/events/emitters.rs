@@ -22,6 +22,18 @@ pub const EVENT_FILE_CREATED: &str = "file_created";
 pub const EVENT_FILE_MODIFIED: &str = "file_modified";
 pub const EVENT_FILE_DELETED: &str = "file_deleted";
 
+// Event jobs
+pub const EVENT_JOB_STARTED: &str = "job_started";
+pub const EVENT_JOB_CANCELLED: &str = "job_cancelled";
+
+// Event IA
+pub const EVENT_AI_TOKEN: &str = "ai_token";
+pub const EVENT_AI_RESET: &str = "ai_reset";
+
+// Event questions sur les fichiers
+pub const EVENT_ASK_SOURCES: &str = "ask_sources";
+pub const EVENT_ASK_CHUNK: &str = "ask_answer_chunk";
+
 // Constantes pour les événements généraux
 pub const EVENT_STAT_UPDATED: &str = "stat_updated";
 
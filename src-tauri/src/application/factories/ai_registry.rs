@@ -0,0 +1,131 @@
+use std::sync::Arc;
+use serde::Serialize;
+use crate::domain::entities::ai::{AiProvider, AiSettings, AI_SETTINGS};
+use crate::domain::ports::ai::Ai;
+use crate::infrastructure::ai::{Ollama, OpenAiCompatible};
+use crate::infrastructure::repository::pool::RepositoryPool;
+use crate::shared::errors::{AppError, AppResult};
+
+/// Fournisseur d'IA pris en charge et ses valeurs par défaut, pour l'écran des réglages
+#[derive(Debug, Clone, Serialize)]
+pub struct AiProviderInfo {
+    pub provider: AiProvider,
+    pub label: &'static str,
+    pub default_url: &'static str,
+    pub uses_api_key: bool,
+}
+
+struct ProviderEntry {
+    info: AiProviderInfo,
+    create: fn(&AiSettings) -> Arc<dyn Ai>,
+}
+
+fn registry() -> [ProviderEntry; 4] {
+    [
+        ProviderEntry {
+            info: AiProviderInfo { provider: AiProvider::LmStudio, label: "LM Studio", default_url: "http://localhost:1234", uses_api_key: false },
+            create: |settings| Arc::new(OpenAiCompatible::new("LM Studio", settings.url.clone(), settings.model.clone(), None).with_json_schema()),
+        },
+        ProviderEntry {
+            info: AiProviderInfo { provider: AiProvider::LlamaCpp, label: "llama.cpp", default_url: "http://localhost:8080", uses_api_key: false },
+            create: |settings| Arc::new(OpenAiCompatible::new("llama.cpp", settings.url.clone(), settings.model.clone(), None).with_json_schema()),
+        },
+        ProviderEntry {
+            info: AiProviderInfo { provider: AiProvider::Ollama, label: "Ollama", default_url: "http://localhost:11434", uses_api_key: false },
+            create: |settings| Arc::new(Ollama::new(settings.url.clone(), settings.model.clone())),
+        },
+        ProviderEntry {
+            info: AiProviderInfo { provider: AiProvider::OpenAiCompatible, label: "Compatible OpenAI", default_url: "https://api.openai.com", uses_api_key: true },
+            create: |settings| Arc::new(OpenAiCompatible::new(
+                "OpenAI-compatible provider", settings.url.clone(), settings.model.clone(), settings.api_key.clone()
+            )),
+        },
+    ]
+}
+
+pub fn ai_providers() -> Vec<AiProviderInfo> {
+    registry().into_iter().map(|entry| entry.info).collect()
+}
+
+/// Adaptateur du fournisseur choisi dans les réglages
+pub fn create_ai(settings: &AiSettings) -> AppResult<Arc<dyn Ai>> {
+    if settings.url.trim().is_empty() {
+        return Err(AppError::Validation("L'URL du fournisseur d'IA est requise".to_string()));
+    }
+
+    registry().into_iter()
+        .find(|entry| entry.info.provider == settings.provider)
+        .map(|entry| (entry.create)(settings))
+        .ok_or_else(|| AppError::Unsupported(format!("Fournisseur d'IA {:?}", settings.provider)))
+}
+
+/// Réglages enregistrés, ou réglages par défaut (LM Studio local) s'ils sont absents ou illisibles
+pub fn load_ai_settings(service_repository: &RepositoryPool) -> AiSettings {
+    service_repository.read(|repo| repo.get_setting(AI_SETTINGS))
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default()
+}
+
+/// Réglages transmis à l'interface : la clé d'API est remplacée par `has_api_key`
+pub fn masked_ai_settings(service_repository: &RepositoryPool) -> AiSettings {
+    let mut settings = load_ai_settings(service_repository);
+    settings.has_api_key = settings.api_key.take().is_some();
+    settings
+}
+
+/// Réglages reçus de l'interface : sans nouvelle clé, la clé enregistrée est conservée tant que
+/// `has_api_key` est vrai (une clé vide avec `has_api_key` faux l'efface)
+pub fn with_stored_api_key(service_repository: &RepositoryPool, mut settings: AiSettings) -> AiSettings {
+    settings.api_key = settings.api_key.filter(|key| !key.trim().is_empty());
+    if settings.api_key.is_none() && settings.has_api_key {
+        settings.api_key = load_ai_settings(service_repository).api_key;
+    }
+    settings.has_api_key = settings.api_key.is_some();
+    settings
+}
+
+pub fn save_ai_settings(service_repository: &RepositoryPool, settings: &AiSettings) -> AppResult<()> {
+    if settings.model.trim().is_empty() {
+        return Err(AppError::Validation("Le modèle du fournisseur d'IA est requis".to_string()));
+    }
+    create_ai(settings)?;
+    let value = serde_json::to_string(settings)
+        .map_err(|e| AppError::Internal(format!("Sérialisation des réglages impossible: {}", e)))?;
+    service_repository.write(move |repo| repo.set_setting(AI_SETTINGS, Some(&value)))
+}
+
+/// Adaptateur du fournisseur enregistré dans les réglages
+pub fn get_ai(service_repository: &RepositoryPool) -> AppResult<Arc<dyn Ai>> {
+    create_ai(&load_ai_settings(service_repository))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_is_masked_and_kept() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pool = RepositoryPool::open(temp_dir.path().join("ai.db").to_str().unwrap(), 1).unwrap();
+
+        let settings = AiSettings {
+            provider: AiProvider::OpenAiCompatible,
+            api_key: Some("sk-secret".to_string()),
+            ..Default::default()
+        };
+        save_ai_settings(&pool, &with_stored_api_key(&pool, settings)).unwrap();
+
+        let masked = masked_ai_settings(&pool);
+        assert_eq!(masked.api_key, None);
+        assert!(masked.has_api_key);
+
+        // Les réglages renvoyés tels quels gardent la clé enregistrée
+        let kept = with_stored_api_key(&pool, masked);
+        assert_eq!(kept.api_key.as_deref(), Some("sk-secret"));
+
+        let cleared = with_stored_api_key(&pool, AiSettings { api_key: None, has_api_key: false, ..kept });
+        assert_eq!(cleared.api_key, None);
+    }
+}
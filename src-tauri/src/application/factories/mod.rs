@@ -1 +1,2 @@
-pub mod service_factory;
\ No newline at end of file
+pub mod service_factory;
+pub mod ai_registry;
@@ -1,7 +1,12 @@
-use crate::infrastructure::repository::sqlite::Db;
-use crate::domain::ports::repository::FileRepository;
-use crate::domain::services::file_service::FileService;
+use crate::domain::entities::embedding::EmbeddingSettings;
+use crate::domain::services::embedding_service::EmbeddingService;
+use crate::infrastructure::ai::OpenAiEmbeddings;
+use crate::infrastructure::repository::pool::RepositoryPool;
 use std::fs;
+use std::sync::Arc;
+
+// Connexions en lecture : recherches de l'UI, statistiques et lectures des tâches de fond
+const READER_COUNT: usize = 4;
 
 fn get_db_path() -> Result<String, String> {
     // Utiliser dirs pour la compatibilité cross-platform
@@ -23,10 +28,16 @@ fn get_db_path() -> Result<String, String> {
 }
 
 
-pub fn get_service_repository() -> Result<FileService<Db>, String> {
+pub fn get_service_repository() -> Result<RepositoryPool, String> {
     let db_path = get_db_path()?;
-    let db_adapter = Db::new(&db_path)
-        .map_err(|e| format!("Failed to create database: {}", e))?;
-    let service_repository = FileService::new(db_adapter);
-    Ok(service_repository)
-}
\ No newline at end of file
+    RepositoryPool::open(&db_path, READER_COUNT)
+        .map_err(|e| format!("Failed to initialize database: {}", e))
+}
+
+/// Service d'embeddings selon les réglages, `None` s'ils sont désactivés
+pub fn get_embedding_service(settings: &EmbeddingSettings) -> Option<EmbeddingService> {
+    settings.enabled.then(|| {
+        let embedder = OpenAiEmbeddings::new(settings.url.clone(), settings.model.clone());
+        EmbeddingService::new(Arc::new(embedder))
+    })
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::domain::entities::job::{JobInfo, JobKind, JobState};
+use crate::shared::errors::{AppError, AppResult};
+
+// Intervalle de vérification de l'état d'une tâche en pause, côté async
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Contrôle partagé entre une tâche de fond et les commandes qui la pilotent
+pub struct JobHandle {
+    pub id: u64,
+    pub kind: JobKind,
+    started_at: u64,
+    state: Mutex<JobState>,
+    state_changed: Condvar,
+}
+
+impl JobHandle {
+    fn new(id: u64, kind: JobKind) -> Self {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            id,
+            kind,
+            started_at,
+            state: Mutex::new(JobState::Running),
+            state_changed: Condvar::new(),
+        }
+    }
+
+    pub fn state(&self) -> JobState {
+        self.state.lock().map(|state| *state).unwrap_or(JobState::Cancelled)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state() == JobState::Cancelled
+    }
+
+    /// Point d'arrêt de la tâche : bloque tant qu'elle est en pause.
+    /// Retourne `false` si elle a été annulée et doit s'arrêter.
+    pub fn checkpoint(&self) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+
+        while *state == JobState::Paused {
+            state = match self.state_changed.wait(state) {
+                Ok(state) => state,
+                Err(_) => return false,
+            };
+        }
+
+        *state != JobState::Cancelled
+    }
+
+    /// Équivalent de `checkpoint` pour les tâches async : attend sans bloquer le thread du runtime
+    pub async fn checkpoint_async(&self) -> bool {
+        loop {
+            match self.state() {
+                JobState::Running => return true,
+                JobState::Cancelled => return false,
+                JobState::Paused => tokio::time::sleep(PAUSE_POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    /// Exécute `future` jusqu'à son terme ou jusqu'à l'annulation de la tâche ; le future est alors
+    /// abandonné (une requête HTTP en cours est interrompue)
+    pub async fn run_until_cancelled<T, F>(&self, future: F) -> AppResult<T>
+    where F: Future<Output = AppResult<T>>
+    {
+        let cancelled = async {
+            while !self.is_cancelled() {
+                tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+            }
+        };
+
+        tokio::select! {
+            result = future => result,
+            _ = cancelled => Err(AppError::Cancelled(format!("Tâche {} annulée", self.id))),
+        }
+    }
+
+    fn transition(&self, from: &[JobState], to: JobState) -> AppResult<()> {
+        let mut state = self.state.lock()
+            .map_err(|e| AppError::Internal(format!("État de la tâche inaccessible: {}", e)))?;
+
+        if !from.contains(&state) {
+            return Err(AppError::Validation(format!("Tâche {} dans l'état {:?}, impossible de passer à {:?}", self.id, *state, to)));
+        }
+
+        *state = to;
+        self.state_changed.notify_all();
+        Ok(())
+    }
+
+    pub fn info(&self) -> JobInfo {
+        JobInfo {
+            id: self.id,
+            kind: self.kind,
+            state: self.state(),
+            started_at: self.started_at,
+        }
+    }
+}
+
+/// Registre des tâches de fond en cours (scan, indexation du contenu)
+#[derive(Default)]
+pub struct JobManager {
+    next_id: AtomicU64,
+    jobs: Mutex<HashMap<u64, Arc<JobHandle>>>,
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre une nouvelle tâche ; elle est retirée du registre quand le garde est libéré
+    pub fn start(self: &Arc<Self>, kind: JobKind) -> AppResult<JobGuard> {
+        let mut jobs = self.jobs.lock()
+            .map_err(|e| AppError::Internal(format!("Registre des tâches inaccessible: {}", e)))?;
+
+        if kind.is_exclusive() && jobs.values().any(|job| job.kind == kind && job.state() != JobState::Cancelled) {
+            return Err(AppError::Validation(format!("Une tâche {:?} est déjà en cours", kind)));
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let handle = Arc::new(JobHandle::new(id, kind));
+        jobs.insert(id, handle.clone());
+
+        Ok(JobGuard { manager: self.clone(), handle })
+    }
+
+    pub fn list(&self) -> Vec<JobInfo> {
+        let mut jobs: Vec<JobInfo> = self.jobs.lock()
+            .map(|jobs| jobs.values().map(|job| job.info()).collect())
+            .unwrap_or_default();
+        jobs.sort_by_key(|job| job.id);
+        jobs
+    }
+
+    pub fn cancel(&self, id: u64) -> AppResult<()> {
+        self.get(id)?.transition(&[JobState::Running, JobState::Paused], JobState::Cancelled)
+    }
+
+    pub fn pause(&self, id: u64) -> AppResult<()> {
+        self.get(id)?.transition(&[JobState::Running], JobState::Paused)
+    }
+
+    pub fn resume(&self, id: u64) -> AppResult<()> {
+        self.get(id)?.transition(&[JobState::Paused], JobState::Running)
+    }
+
+    fn get(&self, id: u64) -> AppResult<Arc<JobHandle>> {
+        self.jobs.lock()
+            .map_err(|e| AppError::Internal(format!("Registre des tâches inaccessible: {}", e)))?
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| AppError::NotFound(format!("Tâche {} introuvable", id)))
+    }
+
+    fn remove(&self, id: u64) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.remove(&id);
+        }
+    }
+}
+
+/// Tâche en cours ; la libération du garde la retire du registre, quelle que soit l'issue
+pub struct JobGuard {
+    manager: Arc<JobManager>,
+    handle: Arc<JobHandle>,
+}
+
+impl Deref for JobGuard {
+    type Target = JobHandle;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        self.manager.remove(self.handle.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_pause_blocks_until_resume_and_cancel_stops() {
+        let manager = Arc::new(JobManager::new());
+        let job = manager.start(JobKind::Scan).unwrap();
+        let id = job.id;
+        let handle = job.handle.clone();
+
+        manager.pause(id).unwrap();
+        let worker = thread::spawn(move || handle.checkpoint());
+        thread::sleep(Duration::from_millis(50));
+        assert!(!worker.is_finished());
+
+        manager.resume(id).unwrap();
+        assert!(worker.join().unwrap());
+
+        manager.pause(id).unwrap();
+        let handle = job.handle.clone();
+        let worker = thread::spawn(move || handle.checkpoint());
+        manager.cancel(id).unwrap();
+        assert!(!worker.join().unwrap());
+        assert!(matches!(manager.resume(id), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_exclusive_jobs_and_registry_cleanup() {
+        let manager = Arc::new(JobManager::new());
+
+        let indexing = manager.start(JobKind::ContentIndexing).unwrap();
+        assert!(matches!(manager.start(JobKind::ContentIndexing), Err(AppError::Validation(_))));
+        let _scan_a = manager.start(JobKind::Scan).unwrap();
+        let _scan_b = manager.start(JobKind::Scan).unwrap();
+        assert_eq!(manager.list().len(), 3);
+
+        let id = indexing.id;
+        drop(indexing);
+        assert!(matches!(manager.cancel(id), Err(AppError::NotFound(_))));
+        assert!(manager.start(JobKind::ContentIndexing).is_ok());
+    }
+}
@@ -1,3 +1,4 @@
 pub mod events;
 pub mod use_cases;
-pub mod factories;
\ No newline at end of file
+pub mod factories;
+pub mod jobs;
\ No newline at end of file
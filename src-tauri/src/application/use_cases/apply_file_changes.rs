@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::application::use_cases::index_content::{index_single_file, load_reader_settings};
+use crate::domain::entities::file::File;
+use crate::domain::entities::file_event::{FileChange, FileChangeKind};
+use crate::infrastructure::filesystem::collect::collect_single_path;
+use crate::infrastructure::filesystem::path_filter::PathFilter;
+use crate::infrastructure::repository::pool::RepositoryPool;
+
+const UPSERT_CHUNK_SIZE: usize = 500;
+
+/// Applique un lot d'événements du watcher à l'index SQLite.
+/// Retourne les changements effectivement appliqués, une fois la base cohérente.
+pub fn apply_file_changes(
+    changes: Vec<FileChange>,
+    service_repository: &Arc<RepositoryPool>,
+) -> Result<Vec<FileChange>, String> {
+    let filter = load_path_filter(service_repository)?;
+
+    let mut upserts: Vec<File> = Vec::new();
+    let mut deletions: Vec<String> = Vec::new();
+    let mut applied = Vec::new();
+
+    for change in coalesce_changes(changes) {
+        // Un chemin supprimé n'a plus de type connu : on le traite comme un fichier
+        if filter.is_excluded(&change.path, change.path.is_dir()) {
+            continue;
+        }
+
+        // L'état du disque fait foi : l'événement peut être périmé au moment du traitement
+        if change.path.exists() {
+            if let Some(file) = collect_single_path(&change.path) {
+                upserts.push(file);
+                applied.push(change);
+            }
+        } else {
+            deletions.push(change.path.to_string_lossy().to_string());
+            applied.push(FileChange { path: change.path, kind: FileChangeKind::Deleted });
+        }
+    }
+
+    if applied.is_empty() {
+        return Ok(applied);
+    }
+
+    let deleted = service_repository.write(move |repo| repo.delete_files(&deletions))
+        .map_err(|e| format!("Erreur lors de la suppression des fichiers: {}", e))?;
+
+    for chunk in upserts.chunks(UPSERT_CHUNK_SIZE) {
+        let chunk = chunk.to_vec();
+        service_repository.write(move |repo| repo.upsert(chunk))
+            .map_err(|e| format!("Erreur lors de la mise à jour des fichiers: {}", e))?;
+    }
+
+    tracing::debug!("Watcher: {} entrées mises à jour, {} supprimées", upserts.len(), deleted);
+
+    // Réextraction du contenu des fichiers créés ou modifiés
+    let reader_settings = load_reader_settings(service_repository);
+    for file in upserts.iter().filter(|f| !f.is_dir) {
+        if let Err(e) = index_single_file(file, service_repository, &reader_settings) {
+            tracing::warn!("Erreur indexation contenu (watcher): {}", e);
+        }
+    }
+
+    Ok(applied)
+}
+
+/// Rechargées à chaque lot pour prendre en compte les règles et fichiers d'ignore modifiés
+fn load_path_filter(service_repository: &Arc<RepositoryPool>) -> Result<PathFilter, String> {
+    let (rules, roots) = service_repository
+        .read(|repo| Ok((repo.get_indexing_rules()?, repo.get_all_paths()?)))
+        .map_err(|e| format!("Erreur lecture des règles d'indexation: {}", e))?;
+
+    PathFilter::new(&rules, &roots).map_err(|e| e.to_string())
+}
+
+/// Fusionne les événements d'un même chemin en conservant l'ordre d'arrivée
+fn coalesce_changes(changes: Vec<FileChange>) -> Vec<FileChange> {
+    let mut positions: HashMap<_, usize> = HashMap::new();
+    let mut merged: Vec<FileChange> = Vec::new();
+
+    for change in changes {
+        match positions.get(&change.path) {
+            Some(&index) => {
+                let previous = merged[index].kind;
+                merged[index].kind = match (previous, change.kind) {
+                    (FileChangeKind::Created, FileChangeKind::Modified) => FileChangeKind::Created,
+                    (FileChangeKind::Deleted, FileChangeKind::Created) => FileChangeKind::Modified,
+                    (_, kind) => kind,
+                };
+            }
+            None => {
+                positions.insert(change.path.clone(), merged.len());
+                merged.push(change);
+            }
+        }
+    }
+
+    merged
+}
@@ -0,0 +1,124 @@
+use crate::domain::entities::ask::{question_terms, select_passages, AskAnswer, AskDocument, AskQuery, AskSource};
+use crate::domain::entities::search::{SearchQuery, SortBy};
+use crate::domain::services::ask_service::AskService;
+use crate::infrastructure::repository::pool::RepositoryPool;
+use crate::shared::errors::{AppError, AppResult};
+
+const MAX_FILES: u32 = 50;
+const MIN_TOKEN_BUDGET: u32 = 100;
+
+/// Répond à une question sur le contenu des fichiers : recherche plein texte des termes de la
+/// question, sélection des meilleurs passages dans le budget de tokens, puis réponse du modèle
+/// citant ses sources. `on_sources` reçoit les passages retenus avant la réponse, `on_chunk` le
+/// texte de la réponse au fur et à mesure.
+pub async fn ask_files<S, C>(
+    service_repository: &RepositoryPool,
+    ask_service: &AskService,
+    query: &AskQuery,
+    on_sources: S,
+    on_chunk: C,
+) -> AppResult<AskAnswer>
+where
+    S: FnOnce(&[AskSource]),
+    C: FnMut(&str) + Send,
+{
+    if query.max_files == 0 || query.max_files > MAX_FILES {
+        return Err(AppError::Validation(format!("Le nombre de fichiers doit être compris entre 1 et {}", MAX_FILES)));
+    }
+    if query.token_budget < MIN_TOKEN_BUDGET {
+        return Err(AppError::Validation(format!("Le budget doit être d'au moins {} tokens", MIN_TOKEN_BUDGET)));
+    }
+
+    let terms = question_terms(&query.question);
+    if terms.is_empty() {
+        return Err(AppError::Validation("La question ne contient aucun terme à rechercher".to_string()));
+    }
+
+    // Un seul terme suffit : le classement fait remonter les fichiers qui en contiennent le plus
+    let search = SearchQuery {
+        text: terms.iter().map(|term| format!("\"{}\"", term)).collect::<Vec<_>>().join(" OR "),
+        search_in_content: true,
+        sort_by: Some(SortBy::Relevance),
+        limit: query.max_files,
+        ..Default::default()
+    };
+    let documents = service_repository.read(|repo| {
+        let mut documents = Vec::new();
+        for file in repo.search(&search)? {
+            let path = file.path.to_string_lossy().to_string();
+            if let Some(content) = repo.get_content(&path)? {
+                documents.push(AskDocument { path, content });
+            }
+        }
+        Ok(documents)
+    })?;
+
+    let sources = select_passages(&terms, &documents, query.token_budget as usize);
+    if sources.is_empty() {
+        return Err(AppError::NotFound("Aucun passage des fichiers indexés ne correspond à la question".to_string()));
+    }
+    on_sources(&sources);
+
+    let answer = ask_service.answer(&query.question, &sources, on_chunk).await?;
+    Ok(AskAnswer { answer, sources })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::domain::entities::content::ExtractedContent;
+    use crate::domain::services::ask_service::tests::FakeAi;
+    use crate::infrastructure::readers::office::tests::file_for;
+
+    #[tokio::test]
+    async fn test_answer_cites_retrieved_passages() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pool = RepositoryPool::open(temp_dir.path().join("ask.db").to_str().unwrap(), 1).unwrap();
+
+        let contents = [
+            ("edf.txt", "Facture EDF de mars : montant 42 euros, échéance le 15 avril."),
+            ("recette.txt", "Mélanger la farine et les oeufs."),
+        ];
+        for (name, text) in contents {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, text).unwrap();
+            let file = file_for(path);
+            let content = ExtractedContent::from_text(text.to_string());
+            pool.write(move |repo| {
+                repo.insert(vec![file.clone()])?;
+                repo.update_file_index_status(&file, content, true)
+            }).unwrap();
+        }
+
+        let ai = Arc::new(FakeAi::new("Le montant est de 42 euros [1]."));
+        let service = AskService::new(ai.clone());
+        let query = AskQuery { question: "Quel est le montant de la facture EDF ?".to_string(), max_files: 5, token_budget: 1000 };
+
+        let mut streamed = String::new();
+        let mut announced = Vec::new();
+        let answer = ask_files(&pool, &service, &query, |sources| announced = sources.to_vec(), |chunk| streamed.push_str(chunk))
+            .await
+            .unwrap();
+
+        assert_eq!(answer.answer, "Le montant est de 42 euros [1].");
+        assert_eq!(streamed, answer.answer);
+        assert_eq!(announced, answer.sources);
+        assert_eq!(answer.sources.len(), 1);
+        let edf = temp_dir.path().join("edf.txt").to_string_lossy().to_string();
+        assert_eq!(answer.sources[0].path, edf);
+
+        // Le modèle reçoit les passages numérotés avec leur chemin, et ses consignes
+        {
+            let requests = ai.requests.lock().unwrap();
+            assert!(requests[0].prompt.contains(&format!("[1] {}\nFacture EDF de mars", edf)));
+            assert!(requests[0].prompt.ends_with("Question : Quel est le montant de la facture EDF ?"));
+            assert!(requests[0].system_prompt.as_deref().unwrap().contains("[1]"));
+        }
+
+        let unrelated = AskQuery { question: "Où sont les vacances ?".to_string(), ..query.clone() };
+        assert!(matches!(ask_files(&pool, &service, &unrelated, |_| {}, |_| {}).await, Err(AppError::NotFound(_))));
+        let tiny = AskQuery { token_budget: 10, ..query };
+        assert!(matches!(ask_files(&pool, &service, &tiny, |_| {}, |_| {}).await, Err(AppError::Validation(_))));
+    }
+}
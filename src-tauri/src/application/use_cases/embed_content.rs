@@ -0,0 +1,171 @@
+use crate::application::jobs::JobHandle;
+use crate::domain::entities::embedding::{EmbeddingSettings, SemanticHit, SemanticQuery, EMBEDDING_SETTINGS};
+use crate::domain::services::embedding_service::EmbeddingService;
+use crate::infrastructure::repository::pool::RepositoryPool;
+use crate::shared::errors::{AppError, AppResult};
+
+// Contenus chargés par lecture
+const PENDING_BATCH_SIZE: u32 = 20;
+// Échecs consécutifs au-delà desquels le service est considéré comme indisponible
+const MAX_CONSECUTIVE_FAILURES: usize = 5;
+
+/// Réglages enregistrés, ou réglages par défaut (désactivés) s'ils sont absents ou illisibles
+pub fn load_embedding_settings(service_repository: &RepositoryPool) -> EmbeddingSettings {
+    service_repository.read(|repo| repo.get_setting(EMBEDDING_SETTINGS))
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_embedding_settings(service_repository: &RepositoryPool, settings: &EmbeddingSettings) -> AppResult<()> {
+    let value = serde_json::to_string(settings)
+        .map_err(|e| AppError::Internal(format!("Sérialisation des réglages impossible: {}", e)))?;
+    service_repository.write(move |repo| repo.set_setting(EMBEDDING_SETTINGS, Some(&value)))
+}
+
+/// Calcule les embeddings des contenus indexés qui n'en ont pas encore pour le modèle courant.
+/// Un fichier refusé par le service est ignoré et sera retenté à la prochaine indexation ; après
+/// plusieurs échecs consécutifs (service injoignable, modèle absent...), le calcul s'arrête.
+/// `on_progress` reçoit le nombre de fichiers traités.
+pub async fn embed_pending_content<P>(
+    service_repository: &RepositoryPool,
+    embedding_service: &EmbeddingService,
+    job: &JobHandle,
+    mut on_progress: P,
+) -> AppResult<usize>
+where P: FnMut(usize)
+{
+    let model = embedding_service.model().to_string();
+    let mut after_id = 0;
+    let mut embedded = 0;
+    let mut consecutive_failures = 0;
+
+    loop {
+        let pending = service_repository.read(|repo| repo.get_pending_embeddings(&model, after_id, PENDING_BATCH_SIZE))?;
+        if pending.is_empty() {
+            return Ok(embedded);
+        }
+
+        for item in pending {
+            if !job.checkpoint_async().await {
+                return Err(AppError::Cancelled(format!("Calcul des embeddings annulé après {} fichiers", embedded)));
+            }
+
+            after_id = item.file_id;
+            let embeddings = match embedding_service.embed_content(&item.content).await {
+                Ok(embeddings) => embeddings,
+                Err(e) => {
+                    consecutive_failures += 1;
+                    tracing::warn!("Embeddings du fichier {} impossibles: {}", item.file_id, e);
+                    if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                        return Err(e);
+                    }
+                    continue;
+                }
+            };
+            consecutive_failures = 0;
+
+            let model = model.clone();
+            service_repository.write(move |repo| repo.save_embeddings(item.file_id, &model, &embeddings))?;
+
+            embedded += 1;
+            on_progress(embedded);
+        }
+    }
+}
+
+/// Fichiers dont le contenu est le plus proche du sens de la requête
+pub async fn semantic_search(
+    service_repository: &RepositoryPool,
+    embedding_service: &EmbeddingService,
+    query: &SemanticQuery,
+) -> AppResult<Vec<SemanticHit>> {
+    let vector = embedding_service.embed_query(&query.text).await?;
+    let model = embedding_service.model();
+    service_repository.read(|repo| repo.semantic_search(model, &vector, query.limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::application::jobs::JobManager;
+    use crate::domain::entities::content::ExtractedContent;
+    use crate::domain::entities::job::JobKind;
+    use crate::infrastructure::ai::openai_embeddings::tests::start_stub_server;
+    use crate::infrastructure::ai::OpenAiEmbeddings;
+    use crate::infrastructure::readers::office::tests::file_for;
+
+    fn insert_contents(pool: &RepositoryPool, dir: &std::path::Path, contents: &[(&str, &str)]) {
+        for (name, text) in contents {
+            let path = dir.join(name);
+            std::fs::write(&path, text).unwrap();
+            let file = file_for(path);
+            let content = ExtractedContent::from_text(text.to_string());
+            pool.write(move |repo| {
+                repo.insert(vec![file.clone()])?;
+                repo.update_file_index_status(&file, content, true)
+            }).unwrap();
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_indexed_content_is_embedded_and_searchable() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pool = RepositoryPool::open(temp_dir.path().join("embeddings.db").to_str().unwrap(), 1).unwrap();
+
+        insert_contents(&pool, temp_dir.path(), &[("abeilles.txt", "les abeilles butinent"), ("zoo.txt", "zzz zoo zozo"), ("vide.txt", "   ")]);
+
+        let service = EmbeddingService::new(Arc::new(OpenAiEmbeddings::new(start_stub_server(), "stub".to_string())));
+        let job_manager = Arc::new(JobManager::new());
+        let job = job_manager.start(JobKind::ContentIndexing).unwrap();
+
+        let mut progress = Vec::new();
+        let embedded = embed_pending_content(&pool, &service, &job, |count| progress.push(count)).await.unwrap();
+        // Le contenu vide n'a rien à vectoriser
+        assert_eq!(embedded, 2);
+        assert_eq!(progress, vec![1, 2]);
+        // Rien à refaire tant que le contenu ne change pas
+        assert_eq!(embed_pending_content(&pool, &service, &job, |_| {}).await.unwrap(), 0);
+
+        let hits = semantic_search(&pool, &service, &SemanticQuery { text: "zozo".to_string(), limit: 5 }).await.unwrap();
+        let names: Vec<&str> = hits.iter().map(|hit| hit.file.name.as_str()).collect();
+        assert_eq!(names, vec!["zoo.txt", "abeilles.txt"]);
+        assert!(hits[0].score > 0.9);
+        assert_eq!(hits[0].passage, "zzz zoo zozo");
+
+        // Les vecteurs disparaissent avec le fichier
+        let zoo = temp_dir.path().join("zoo.txt").to_string_lossy().to_string();
+        pool.write(move |repo| repo.delete_files(&[zoo])).unwrap();
+        let hits = semantic_search(&pool, &service, &SemanticQuery { text: "zozo".to_string(), limit: 5 }).await.unwrap();
+        assert_eq!(hits.len(), 1);
+
+        // Un autre modèle ne voit pas ces vecteurs
+        let other = EmbeddingService::new(Arc::new(OpenAiEmbeddings::new(start_stub_server(), "autre".to_string())));
+        assert!(semantic_search(&pool, &other, &SemanticQuery { text: "zozo".to_string(), limit: 5 }).await.unwrap().is_empty());
+
+        job_manager.cancel(job.id).unwrap();
+        assert!(matches!(embed_pending_content(&pool, &other, &job, |_| {}).await, Err(AppError::Cancelled(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rejected_content_is_skipped() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pool = RepositoryPool::open(temp_dir.path().join("embeddings.db").to_str().unwrap(), 1).unwrap();
+        insert_contents(&pool, temp_dir.path(), &[("refus.txt", "rejet du service"), ("zoo.txt", "zzz zoo zozo")]);
+
+        let service = EmbeddingService::new(Arc::new(OpenAiEmbeddings::new(start_stub_server(), "stub".to_string())));
+        let job_manager = Arc::new(JobManager::new());
+        let job = job_manager.start(JobKind::ContentIndexing).unwrap();
+
+        assert_eq!(embed_pending_content(&pool, &service, &job, |_| {}).await.unwrap(), 1);
+        let hits = semantic_search(&pool, &service, &SemanticQuery { text: "zozo".to_string(), limit: 5 }).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].file.name, "zoo.txt");
+
+        // Le fichier refusé reste à traiter
+        let pending = pool.read(|repo| repo.get_pending_embeddings("stub", 0, 10)).unwrap();
+        assert_eq!(pending.len(), 1);
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use crate::application::jobs::JobHandle;
+use crate::domain::entities::duplicate::{ChecksumCandidate, DuplicateGroup, DuplicateQuery, DuplicateReport};
+use crate::infrastructure::filesystem::checksum::compute_checksums;
+use crate::infrastructure::repository::pool::RepositoryPool;
+use crate::shared::errors::{AppError, AppResult};
+
+const CHECKSUM_CHUNK_SIZE: usize = 500;
+
+/// Calcule les empreintes manquantes des fichiers candidats, les enregistre puis regroupe les doublons.
+/// Les empreintes déjà connues (et toujours valides) ne sont pas recalculées.
+pub fn find_duplicates(
+    service_repository: &RepositoryPool,
+    job: &JobHandle,
+    query: DuplicateQuery,
+) -> AppResult<DuplicateReport> {
+    let mut candidates = service_repository.read(|repo| repo.get_duplicate_candidates(&query))?;
+    tracing::info!("Recherche de doublons: {} fichiers de même taille", candidates.len());
+
+    // La pause n'est attendue qu'entre deux lots : un thread rayon ne doit jamais bloquer
+    let computed = compute_checksums(&candidates, || job.checkpoint(), || !job.is_cancelled());
+
+    // Les empreintes calculées avant l'annulation sont conservées pour la prochaine recherche
+    for chunk in computed.chunks(CHECKSUM_CHUNK_SIZE) {
+        let chunk = chunk.to_vec();
+        service_repository.write(move |repo| repo.update_checksums(&chunk))?;
+    }
+
+    if job.is_cancelled() {
+        return Err(AppError::Cancelled(format!("Recherche de doublons annulée ({} empreintes calculées)", computed.len())));
+    }
+
+    let computed: HashMap<String, String> = computed.into_iter().collect();
+    for candidate in candidates.iter_mut().filter(|c| c.checksum.is_none()) {
+        candidate.checksum = computed.get(&candidate.path).cloned();
+    }
+
+    Ok(group_duplicates(candidates))
+}
+
+fn group_duplicates(candidates: Vec<ChecksumCandidate>) -> DuplicateReport {
+    let mut by_checksum: HashMap<(u64, String), Vec<String>> = HashMap::new();
+    for candidate in candidates {
+        if let Some(checksum) = candidate.checksum {
+            by_checksum.entry((candidate.size, checksum)).or_default().push(candidate.path);
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_checksum.into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, checksum), mut paths)| {
+            paths.sort();
+            let wasted_space = size * (paths.len() as u64 - 1);
+            DuplicateGroup { checksum, size, paths, wasted_space }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| b.wasted_space.cmp(&a.wasted_space).then_with(|| a.paths.cmp(&b.paths)));
+
+    DuplicateReport {
+        duplicate_files: groups.iter().map(|group| group.paths.len() - 1).sum(),
+        wasted_space: groups.iter().map(|group| group.wasted_space).sum(),
+        groups,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(path: &str, size: u64, checksum: Option<&str>) -> ChecksumCandidate {
+        ChecksumCandidate { path: path.to_string(), size, checksum: checksum.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_group_duplicates_computes_wasted_space() {
+        let report = group_duplicates(vec![
+            candidate("/a/film.mkv", 1000, Some("f1")),
+            candidate("/b/film.mkv", 1000, Some("f1")),
+            candidate("/c/film.mkv", 1000, Some("f1")),
+            candidate("/a/photo.jpg", 50, Some("p1")),
+            candidate("/b/photo.jpg", 50, Some("p1")),
+            candidate("/a/autre.jpg", 50, Some("p2")),
+            candidate("/a/inconnu.bin", 50, None),
+        ]);
+
+        assert_eq!(report.groups.len(), 2);
+        assert_eq!(report.groups[0].paths, vec!["/a/film.mkv", "/b/film.mkv", "/c/film.mkv"]);
+        assert_eq!(report.groups[0].wasted_space, 2000);
+        assert_eq!(report.groups[1].wasted_space, 50);
+        assert_eq!(report.duplicate_files, 3);
+        assert_eq!(report.wasted_space, 2050);
+    }
+}
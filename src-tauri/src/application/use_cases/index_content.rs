@@ -1,41 +1,52 @@
 use crate::application::events::emitters::{emit_event, emit_error_event, emit_started_event, emit_finished_event,
                            EVENT_INDEX_STARTED, EVENT_INDEX_PROGRESS, EVENT_INDEX_FINISHED,
-                           EVENT_INDEX_ERROR, EVENT_STAT_UPDATED};
+                           EVENT_INDEX_ERROR, EVENT_STAT_UPDATED, EVENT_JOB_STARTED, EVENT_JOB_CANCELLED};
 use tauri::WebviewWindow;
 use std::sync::{Arc, Mutex};
+use crate::application::factories::service_factory::get_embedding_service;
+use crate::application::jobs::{JobHandle, JobManager};
+use crate::application::use_cases::embed_content::{embed_pending_content, load_embedding_settings};
+use crate::domain::entities::job::{IndexCheckpoint, JobCancelled, JobKind};
 use crate::domain::entities::scan::{IndexProgress, IndexFinished};
+use crate::domain::entities::content::{ExtractedContent, ReaderSettings, READER_SETTINGS};
 use crate::domain::entities::file::File;
 use crate::domain::services::content_indexer_service::ContentIndexerService;
-use crate::domain::services::file_service::FileService;
-use crate::infrastructure::repository::sqlite::Db;
+use crate::infrastructure::repository::pool::RepositoryPool;
+use crate::shared::errors::AppError;
 
+/// Clé des paramètres sous laquelle l'avancement d'une indexation interrompue est conservé
+pub const CONTENT_INDEXING_CHECKPOINT: &str = "content_indexing_checkpoint";
+// Délai minimal entre deux enregistrements de l'avancement
+const CHECKPOINT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 struct ProgressTracker {
     total_files: usize,
     processed_files: usize,
     successful_files: usize,
+    skipped_files: usize,
     failed_files: usize,
     last_progress_update: std::time::Instant,
 }
 
 impl ProgressTracker {
-    fn new(total_files: usize) -> Self {
+    fn new(total_files: usize, already_processed: usize) -> Self {
         Self {
             total_files,
-            processed_files: 0,
+            processed_files: already_processed,
             successful_files: 0,
+            skipped_files: 0,
             failed_files: 0,
             last_progress_update: std::time::Instant::now(),
         }
     }
 
-    fn increment_processed(&mut self, success: bool) {
+    fn increment_processed(&mut self, outcome: IndexOutcome) {
         self.processed_files += 1;
-        if success {
-            self.successful_files += 1;
-        } else {
-            self.failed_files += 1;
+        match outcome {
+            IndexOutcome::Indexed => self.successful_files += 1,
+            IndexOutcome::Skipped => self.skipped_files += 1,
+            IndexOutcome::Failed => self.failed_files += 1,
         }
     }
 
@@ -54,6 +65,42 @@ impl ProgressTracker {
 
 
 
+/// Issue de l'indexation d'un fichier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexOutcome {
+    Indexed,
+    /// Format non pris en charge ou contenu binaire : pas une erreur
+    Skipped,
+    /// Lecture ou extraction en échec
+    Failed,
+}
+
+/// Avancement enregistré, ignoré s'il ne correspond plus aux fichiers restants
+/// (fichiers ajoutés ou supprimés depuis l'interruption)
+fn load_checkpoint(service_repository: &RepositoryPool, remaining: usize) -> IndexCheckpoint {
+    service_repository.read(|repo| repo.get_setting(CONTENT_INDEXING_CHECKPOINT))
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str::<IndexCheckpoint>(&value).ok())
+        .filter(|checkpoint| checkpoint.total.checked_sub(checkpoint.processed) == Some(remaining))
+        .unwrap_or_default()
+}
+
+/// `None` efface l'avancement (indexation terminée)
+fn save_checkpoint(service_repository: &RepositoryPool, checkpoint: Option<IndexCheckpoint>) {
+    let value = checkpoint.and_then(|checkpoint| serde_json::to_string(&checkpoint).ok());
+    if let Err(e) = service_repository.write(move |repo| repo.set_setting(CONTENT_INDEXING_CHECKPOINT, value.as_deref())) {
+        tracing::warn!("Impossible d'enregistrer l'avancement de l'indexation: {}", e);
+    }
+}
+
+/// Lus une fois par indexation, puis transmis à chaque fichier
+pub fn load_reader_settings(service_repository: &RepositoryPool) -> ReaderSettings {
+    service_repository.read(|repo| repo.get_setting(READER_SETTINGS))
+        .map(|value| ReaderSettings::from_setting(value.as_deref()))
+        .unwrap_or_default()
+}
+
 fn can_index_file(file: &File) -> bool {
     if !file.path.exists() || !file.path.is_file() {
         return false;
@@ -63,74 +110,132 @@ fn can_index_file(file: &File) -> bool {
     content_indexer.can_index_file(file)
 }
 
-async fn process_single_file(
-    file: File,
-    service_repository: Arc<Mutex<FileService<Db>>>,
-) -> Result<(), String> {
+/// Extrait le contenu d'un fichier puis met à jour son statut d'indexation.
+/// L'extraction se fait avant de passer par le thread d'écriture, qui n'est occupé que par la mise à jour.
+pub fn index_single_file(
+    file: &File,
+    service_repository: &Arc<RepositoryPool>,
+    reader_settings: &ReaderSettings,
+) -> Result<IndexOutcome, String> {
     let file_path = file.path.display().to_string();
-    
-    let mut repo = service_repository.lock()
-        .map_err(|e| format!("Erreur d'accès au repository pour {}: {}", file_path, e))?;
 
-    if !can_index_file(&file) {
+    let update_status = |content: ExtractedContent, is_indexable: bool| {
+        let file = file.clone();
+        service_repository.write(move |repo| repo.update_file_index_status(&file, content, is_indexable))
+    };
+
+    if !can_index_file(file) {
         // Marquer comme non indexable mais sans erreur
-        repo.update_file_index_status(&file, String::new(), false)
+        update_status(ExtractedContent::default(), false)
             .map_err(|e| format!("Erreur mise à jour fichier non indexable {}: {}", file_path, e))?;
-        return Ok(()); // Pas d'erreur, juste non indexable
+        return Ok(IndexOutcome::Skipped);
     }
 
-    let mut content_indexer = ContentIndexerService::new();
+    let mut content_indexer = ContentIndexerService::with_settings(reader_settings.clone());
+    let extraction = content_indexer.index_file_content(file);
 
-    let text_content = match content_indexer.index_file_content(&file) {
+    let text_content = match extraction {
         Ok(content) => {
-            tracing::debug!("Indexation réussie: {} ({} chars)", file_path, content.len());
+            tracing::debug!("Indexation réussie: {} ({} chars)", file_path, content.text.len());
             content
         },
         Err(e) => {
-            tracing::warn!("Échec indexation: {} - {}", file_path, e);
-            // En cas d'erreur de lecture, marquer le fichier comme non indexable
-            repo.update_file_index_status(&file, String::new(), false)
+            let outcome = match e {
+                AppError::Unsupported(reason) => {
+                    tracing::debug!("Contenu ignoré: {}", reason);
+                    IndexOutcome::Skipped
+                }
+                e => {
+                    tracing::warn!("Échec indexation: {} - {}", file_path, e);
+                    IndexOutcome::Failed
+                }
+            };
+            // Dans les deux cas le fichier est marqué non indexable pour ne pas être retenté
+            update_status(ExtractedContent::default(), false)
                 .map_err(|update_err| format!("Erreur mise à jour après échec pour {}: {}", file_path, update_err))?;
-            return Ok(()); // Pas d'erreur critique, juste échec d'indexation
+            return Ok(outcome);
         }
     };
 
     // Marquer le fichier comme indexé avec succès
-    repo.update_file_index_status(&file, text_content, true)
+    update_status(text_content, true)
         .map_err(|e| format!("Erreur mise à jour succès pour {}: {}", file_path, e))?;
 
-    Ok(())
+    Ok(IndexOutcome::Indexed)
 }
 
 
+/// Dernière étape de l'indexation : embeddings des contenus qui n'en ont pas, si le service est activé.
+/// Retourne `false` si la tâche a été annulée.
+async fn embed_indexed_content(window: &WebviewWindow, service_repository: &RepositoryPool, job: &JobHandle) -> bool {
+    let Some(embedding_service) = get_embedding_service(&load_embedding_settings(service_repository)) else {
+        return true;
+    };
+
+    tracing::info!("Calcul des embeddings avec le modèle {}", embedding_service.model());
+    let on_progress = |embedded: usize| {
+        emit_event(window, EVENT_INDEX_PROGRESS, IndexProgress {
+            progress: 1.0,
+            message: format!("Embeddings: {} fichiers vectorisés", embedded),
+            processed: embedded,
+            total: embedded,
+        });
+    };
+
+    match embed_pending_content(service_repository, &embedding_service, job, on_progress).await {
+        Ok(embedded) => {
+            tracing::info!("Embeddings calculés pour {} fichiers", embedded);
+            true
+        }
+        Err(AppError::Cancelled(message)) => {
+            emit_event(window, EVENT_JOB_CANCELLED, JobCancelled { id: job.id, kind: job.kind, message });
+            false
+        }
+        Err(e) => {
+            // Service injoignable : le contenu reste indexé, les embeddings seront repris plus tard
+            tracing::warn!("Calcul des embeddings interrompu: {}", e);
+            emit_error_event(window, EVENT_INDEX_ERROR, format!("Embeddings non calculés: {}", e));
+            true
+        }
+    }
+}
+
 pub fn index_content_async(
     window: WebviewWindow,
-    service_repository: Arc<Mutex<FileService<Db>>>
+    service_repository: Arc<RepositoryPool>,
+    job_manager: Arc<JobManager>,
 ) {
     tauri::async_runtime::spawn(async move {
 
+        let job = match job_manager.start(JobKind::ContentIndexing) {
+            Ok(job) => job,
+            // Une indexation est déjà en cours (lancée par un scan précédent par exemple)
+            Err(AppError::Validation(message)) => {
+                tracing::info!("Indexation du contenu non démarrée: {}", message);
+                return;
+            }
+            Err(e) => {
+                emit_error_event(&window, EVENT_INDEX_ERROR, e.to_string());
+                return;
+            }
+        };
+
+        emit_event(&window, EVENT_JOB_STARTED, job.info());
         emit_started_event(&window, EVENT_INDEX_STARTED);
 
-        let uncontent_indexed_files = {
-            let repo = match service_repository.lock() {
-                Ok(repo) => repo,
-                Err(e) => {
-                    emit_error_event(&window, EVENT_INDEX_ERROR, format!("Erreur accès repository: {}", e));
-                    return;
-                }
-            };
-            match repo.get_uncontent_indexed_files() {
-                Ok(files) => files,
-                Err(e) => {
-                    emit_error_event(&window, EVENT_INDEX_ERROR, format!("Erreur récupération fichiers: {}", e));
-                    return;
-                }
+        let uncontent_indexed_files = match service_repository.read(|repo| repo.get_uncontent_indexed_files()) {
+            Ok(files) => files,
+            Err(e) => {
+                emit_error_event(&window, EVENT_INDEX_ERROR, format!("Erreur récupération fichiers: {}", e));
+                return;
             }
         };
 
-        let total_files = uncontent_indexed_files.len();
-
-        if total_files == 0 {
+        if uncontent_indexed_files.is_empty() {
+            save_checkpoint(&service_repository, None);
+            if !embed_indexed_content(&window, &service_repository, &job).await {
+                return;
+            }
             emit_finished_event(&window, EVENT_INDEX_FINISHED, IndexFinished {
                 total: 0,
                 message: "Aucun fichier nécessite une indexation du contenu".to_string(),
@@ -138,10 +243,17 @@ pub fn index_content_async(
             return;
         }
 
+        // Une indexation interrompue reprend sa progression là où elle s'était arrêtée
+        let checkpoint = load_checkpoint(&service_repository, uncontent_indexed_files.len());
+        let total_files = checkpoint.processed + uncontent_indexed_files.len();
+
         // Initialisation du tracker de progrès
-        let progress_tracker = Arc::new(Mutex::new(ProgressTracker::new(total_files)));
+        let progress_tracker = Arc::new(Mutex::new(ProgressTracker::new(total_files, checkpoint.processed)));
 
-        tracing::info!("Démarrage de l'indexation de contenu pour {} fichiers", total_files);
+        tracing::info!(
+            "Démarrage de l'indexation de contenu pour {} fichiers ({} déjà traités)",
+            uncontent_indexed_files.len(), checkpoint.processed
+        );
         
         // Traitement séquentiel par chunks plus petit pour un meilleur feedback
         const CHUNK_SIZE_INDEX: usize = 10;
@@ -152,17 +264,38 @@ pub fn index_content_async(
 
         let total_chunks = chunks.len();
         tracing::info!("Traitement par {} chunks de {} fichiers", total_chunks, CHUNK_SIZE_INDEX);
+        let mut last_checkpoint = std::time::Instant::now();
+        let reader_settings = load_reader_settings(&service_repository);
 
         for (chunk_index, file_chunk) in chunks.into_iter().enumerate() {
+            // Pause et annulation sont prises en compte entre deux chunks
+            if !job.checkpoint_async().await {
+                let processed = progress_tracker.lock().map(|tracker| tracker.processed_files).unwrap_or(checkpoint.processed);
+                tracing::info!("Indexation du contenu annulée après {} fichiers sur {}", processed, total_files);
+                save_checkpoint(&service_repository, Some(IndexCheckpoint { processed, total: total_files }));
+
+                if let Ok(stat) = service_repository.read(|repo| repo.get_stat()) {
+                    emit_event(&window, EVENT_STAT_UPDATED, stat);
+                }
+                emit_event(&window, EVENT_JOB_CANCELLED, JobCancelled {
+                    id: job.id,
+                    kind: job.kind,
+                    message: format!("Indexation annulée: {} fichiers traités sur {}, reprise possible", processed, total_files),
+                });
+                return;
+            }
+
             tracing::info!("Traitement du chunk {} / {}", chunk_index + 1, total_chunks);
             
-            // Traitement parallèle du chunk avec futures
+            // Extraction et écriture bloquent : elles passent par le pool bloquant, pas par les
+            // threads du runtime qui servent aussi les commandes de recherche
             let mut handles = Vec::new();
             
             for file in file_chunk {
                 let service_repo_clone = service_repository.clone();
-                let handle = tokio::spawn(async move {
-                    process_single_file(file, service_repo_clone).await
+                let reader_settings = reader_settings.clone();
+                let handle = tokio::task::spawn_blocking(move || {
+                    index_single_file(&file, &service_repo_clone, &reader_settings)
                 });
                 handles.push(handle);
             }
@@ -172,7 +305,7 @@ pub fn index_content_async(
                 match handle.await {
                     Ok(result) => {
                         if let Ok(mut tracker) = progress_tracker.lock() {
-                            tracker.increment_processed(result.is_ok());
+                            tracker.increment_processed(*result.as_ref().unwrap_or(&IndexOutcome::Failed));
                         }
 
                         if let Err(e) = result {
@@ -182,13 +315,14 @@ pub fn index_content_async(
                     Err(e) => {
                         tracing::error!("Erreur task indexation: {}", e);
                         if let Ok(mut tracker) = progress_tracker.lock() {
-                            tracker.increment_processed(false);
+                            tracker.increment_processed(IndexOutcome::Failed);
                         }
                     }
                 }
             }
 
             // Mise à jour du progrès après chaque chunk
+            let mut checkpoint_to_save = None;
             {
                 if let Ok(mut tracker) = progress_tracker.lock() {
                     let progress = tracker.get_progress();
@@ -207,26 +341,37 @@ pub fn index_content_async(
                     });
 
                     tracker.update_progress_time();
+
+                    if last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+                        checkpoint_to_save = Some(IndexCheckpoint {
+                            processed: tracker.processed_files,
+                            total: total_files,
+                        });
+                    }
                 }
             }
 
+            // Écriture hors du verrou du tracker, au plus une fois par intervalle
+            if let Some(checkpoint) = checkpoint_to_save {
+                save_checkpoint(&service_repository, Some(checkpoint));
+                last_checkpoint = std::time::Instant::now();
+            }
+
             // IMPORTANT: Émettre les stats après chaque chunk
-            if let Ok(repo) = service_repository.lock() {
-                if let Ok(stat) = repo.get_stat() {
-                    tracing::debug!("Émission des stats après chunk {}: {} fichiers indexés", chunk_index + 1, stat.content_indexed_files);
-                    emit_event(&window, EVENT_STAT_UPDATED, stat);
-                }
+            if let Ok(stat) = service_repository.read(|repo| repo.get_stat()) {
+                tracing::debug!("Émission des stats après chunk {}: {} fichiers indexés", chunk_index + 1, stat.content_indexed_files);
+                emit_event(&window, EVENT_STAT_UPDATED, stat);
             }
         }
 
 
-        let final_stat = {
-            if let Ok(repo) = service_repository.lock() {
-                repo.get_stat().ok()
-            } else {
-                None
-            }
-        };
+        save_checkpoint(&service_repository, None);
+
+        if !embed_indexed_content(&window, &service_repository, &job).await {
+            return;
+        }
+
+        let final_stat = service_repository.read(|repo| repo.get_stat()).ok();
         
         if let Some(stat) = final_stat {
             emit_event(&window, EVENT_STAT_UPDATED, stat);
@@ -235,9 +380,10 @@ pub fn index_content_async(
         
         let message = if let Ok(final_tracker) = progress_tracker.lock() {
             format!(
-                "Indexation terminée: {} fichiers traités ({} indexés avec succès, {} échecs)",
+                "Indexation terminée: {} fichiers traités ({} indexés avec succès, {} ignorés, {} échecs)",
                 final_tracker.processed_files,
                 final_tracker.successful_files,
+                final_tracker.skipped_files,
                 final_tracker.failed_files
             )
         } else {
@@ -1 +1,5 @@
 pub mod index_content;
+pub mod apply_file_changes;
+pub mod find_duplicates;
+pub mod embed_content;
+pub mod ask_files;
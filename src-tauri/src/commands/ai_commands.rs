@@ -1,12 +1,35 @@
-use std::sync::Arc;
-use crate::infrastructure::ai::LmStudio;
+use crate::application::factories::ai_registry::{self, AiProviderInfo};
 use crate::domain::entities::search::SearchQuery;
 use crate::domain::services::ai_service::AiService;
+use crate::domain::entities::embedding::{EmbeddingSettings, SemanticHit, SemanticQuery};
+use crate::application::factories::service_factory::get_embedding_service;
+use crate::application::use_cases::{ask_files, embed_content};
+use crate::application::events::emitters::{
+    emit_event, EVENT_AI_RESET, EVENT_AI_TOKEN, EVENT_ASK_CHUNK, EVENT_ASK_SOURCES, EVENT_JOB_CANCELLED, EVENT_JOB_STARTED,
+};
+use crate::application::jobs::JobHandle;
+use crate::domain::entities::ai::{AiReset, AiSettings, AiStreamEvent, AiToken};
+use crate::domain::entities::job::{JobCancelled, JobKind};
+use crate::shared::errors::{AppError, AppResult};
+use crate::domain::entities::ask::{AskAnswer, AskQuery};
+use crate::domain::services::ask_service::AskService;
+use crate::shared::config::AppState;
+use tokio::sync::mpsc;
 
+// Fragments de la réponse en attente d'émission
+const TOKEN_BUFFER: usize = 64;
+
+/// Convertit la requête en `SearchQuery`. La génération est une tâche annulable (`cancel_job`) dont
+/// la réponse est émise au fil de l'eau (`ai_token`), `ai_reset` annonçant un nouvel essai.
 #[tauri::command]
-pub async fn ai_search(natural_query: String, model: String, ai_url: String) -> Result<SearchQuery, String> {
-    let ai_adapter = LmStudio::new(Some(ai_url), Some(model.clone()));
-    let ai_service = AiService::new(Arc::new(ai_adapter));
+pub async fn ai_search(
+    natural_query: String,
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, AppState>,
+) -> Result<SearchQuery, String> {
+    let settings = ai_registry::load_ai_settings(&state.service_repository);
+    let ai_service = AiService::new(ai_registry::create_ai(&settings)?);
+    let model = settings.model;
 
     let check = ai_service.model_is_available(&model).await;
 
@@ -14,25 +37,120 @@ pub async fn ai_search(natural_query: String, model: String, ai_url: String) ->
         return Err(format!("Model {} is not available: {}", model, e));
     }
 
-    let search_query = ai_service.generate(&natural_query).await
-        .map_err(|e| format!("AI generation failed: {}", e))?;
-    Ok(search_query)
+    let known_types = state.service_repository.read(|repo| repo.get_all_types()).unwrap_or_else(|e| {
+        tracing::warn!("Types de fichiers indisponibles: {}", e);
+        Vec::new()
+    });
+
+    let job = state.job_manager.start(JobKind::AiQuery).map_err(|e| e.to_string())?;
+    emit_event(&window, EVENT_JOB_STARTED, job.info());
+
+    let (events, mut received) = mpsc::channel(TOKEN_BUFFER);
+    let job_id = job.id;
+    let forward = async {
+        while let Some(event) = received.recv().await {
+            match event {
+                AiStreamEvent::Token(token) => emit_event(&window, EVENT_AI_TOKEN, AiToken { job_id, token }),
+                AiStreamEvent::Reset => emit_event(&window, EVENT_AI_RESET, AiReset { job_id }),
+            }
+        }
+    };
+    let generation = job.run_until_cancelled(ai_service.generate_stream(&natural_query, &known_types, events));
+    let (result, _) = tokio::join!(generation, forward);
+
+    emit_if_cancelled(&window, &job, &result);
+    result.map_err(|e| format!("AI generation failed: {}", e))
 }
 
+/// Vérifie les réglages indiqués (pas forcément enregistrés)
 #[tauri::command]
-pub async fn ai_health_check(ai_url: String) -> Result<bool, String> {
-    let ai_adapter = LmStudio::new(Some(ai_url), None);
-    let ai_service = AiService::new(Arc::new(ai_adapter));
+pub async fn ai_health_check(settings: AiSettings, state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    let settings = ai_registry::with_stored_api_key(&state.service_repository, settings);
+    let ai_service = AiService::new(ai_registry::create_ai(&settings)?);
     let health_check = ai_service.health_check().await
         .map_err(|e| format!("Health check failed: {}", e))?;
     Ok(health_check)
 }
 
 #[tauri::command]
-pub async fn ai_list_models(ai_url: String) -> Result<Vec<String>, String> {
-    let ai_adapter = LmStudio::new(Some(ai_url), None);
-    let ai_service = AiService::new(Arc::new(ai_adapter));
+pub async fn ai_list_models(settings: AiSettings, state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
+    let settings = ai_registry::with_stored_api_key(&state.service_repository, settings);
+    let ai_service = AiService::new(ai_registry::create_ai(&settings)?);
     let models = ai_service.list_models().await
         .map_err(|e| format!("Failed to list models: {}", e))?;
     Ok(models)
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub fn list_ai_providers() -> Vec<AiProviderInfo> {
+    ai_registry::ai_providers()
+}
+
+/// Réglages enregistrés, sans la clé d'API (seulement `has_api_key`)
+#[tauri::command]
+pub fn get_ai_settings(state: tauri::State<'_, AppState>) -> Result<AiSettings, String> {
+    Ok(ai_registry::masked_ai_settings(&state.service_repository))
+}
+
+/// Enregistre les réglages ; sans nouvelle clé d'API, la clé enregistrée est conservée
+#[tauri::command]
+pub fn save_ai_settings(settings: AiSettings, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let settings = ai_registry::with_stored_api_key(&state.service_repository, settings);
+    ai_registry::save_ai_settings(&state.service_repository, &settings).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_embedding_settings(state: tauri::State<'_, AppState>) -> Result<EmbeddingSettings, String> {
+    Ok(embed_content::load_embedding_settings(&state.service_repository))
+}
+
+/// Enregistre les réglages ; les embeddings manquants sont calculés à la prochaine indexation du contenu
+#[tauri::command]
+pub fn save_embedding_settings(settings: EmbeddingSettings, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if settings.enabled && (settings.url.trim().is_empty() || settings.model.trim().is_empty()) {
+        return Err("L'URL et le modèle d'embeddings sont requis".to_string());
+    }
+    embed_content::save_embedding_settings(&state.service_repository, &settings).map_err(|e| e.to_string())
+}
+
+/// Recherche par le sens : plus proches voisins de la requête parmi les passages vectorisés
+#[tauri::command]
+pub async fn semantic_search(query: SemanticQuery, state: tauri::State<'_, AppState>) -> Result<Vec<SemanticHit>, String> {
+    let settings = embed_content::load_embedding_settings(&state.service_repository);
+    let embedding_service = get_embedding_service(&settings)
+        .ok_or_else(|| "La recherche sémantique n'est pas activée".to_string())?;
+
+    embed_content::semantic_search(&state.service_repository, &embedding_service, &query).await
+        .map_err(|e| format!("Semantic search failed: {}", e))
+}
+
+/// Répond à une question à partir du contenu indexé, en tâche annulable (`cancel_job`). Les passages
+/// retenus sont émis (`ask_sources`) avant la réponse, puis le texte de la réponse (`ask_answer_chunk`).
+#[tauri::command]
+pub async fn ask_files(
+    query: AskQuery,
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, AppState>,
+) -> Result<AskAnswer, String> {
+    let ask_service = AskService::new(ai_registry::get_ai(&state.service_repository)?);
+
+    let job = state.job_manager.start(JobKind::AiQuery).map_err(|e| e.to_string())?;
+    emit_event(&window, EVENT_JOB_STARTED, job.info());
+
+    let result = job.run_until_cancelled(ask_files::ask_files(
+        &state.service_repository,
+        &ask_service,
+        &query,
+        |sources| emit_event(&window, EVENT_ASK_SOURCES, sources.to_vec()),
+        |chunk| emit_event(&window, EVENT_ASK_CHUNK, chunk.to_string()),
+    )).await;
+
+    emit_if_cancelled(&window, &job, &result);
+    result.map_err(|e| format!("Question failed: {}", e))
+}
+
+fn emit_if_cancelled<T>(window: &tauri::WebviewWindow, job: &JobHandle, result: &AppResult<T>) {
+    if let Err(AppError::Cancelled(message)) = result {
+        emit_event(window, EVENT_JOB_CANCELLED, JobCancelled { id: job.id, kind: job.kind, message: message.clone() });
+    }
+}
@@ -1,17 +1,69 @@
+use crate::application::use_cases::index_content::CONTENT_INDEXING_CHECKPOINT;
+use crate::application::events::emitters::{emit_event, EVENT_JOB_CANCELLED, EVENT_JOB_STARTED};
+use crate::application::use_cases::find_duplicates;
+use crate::domain::entities::content::{ReaderSettings, ENTRY_SEPARATOR, READER_SETTINGS};
+use crate::domain::entities::duplicate::{DuplicateQuery, DuplicateReport};
 use crate::domain::entities::file::File;
-use crate::domain::entities::search::SearchQuery;
+use crate::domain::entities::job::{JobCancelled, JobKind};
+use crate::domain::entities::ranking::{RankingWeights, RANKING_WEIGHTS};
+use crate::domain::entities::scan::ScanMode;
+use crate::domain::entities::search::{SearchHit, SearchQuery};
 use crate::infrastructure::filesystem::open_file::open_file_in_explorer;
 use crate::infrastructure::filesystem::scanner::scan_files_async;
+use crate::infrastructure::readers::archive::{extract_for_opening, ArchiveKind};
 use crate::infrastructure::watcher::restart_watcher::restart_file_watcher_with_new_paths_only;
 use crate::shared::config::AppState;
+use crate::shared::errors::AppError;
 use crate::shared::helpers::{with_service_repository, with_service_repository_readonly};
+use std::path::Path;
 
+/// Ouvre le fichier et compte l'ouverture pour le tri par pertinence
 #[tauri::command]
-pub fn open_file(path: String) -> Result<(), String> {
-    open_file_in_explorer(path)?;
+pub fn open_file(path: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let target = match split_virtual_path(&path) {
+        // Fichier d'une archive : extrait dans un dossier temporaire
+        Some((container, member)) if ArchiveKind::detect(Path::new(container)).is_some() => {
+            let settings = with_service_repository_readonly(&state, |repo| {
+                Ok(ReaderSettings::from_setting(repo.get_setting(READER_SETTINGS)?.as_deref()))
+            })?;
+            let max_size = settings.archive_max_size_mb.saturating_mul(1024 * 1024);
+            extract_for_opening(Path::new(container), member, max_size)?.to_string_lossy().to_string()
+        }
+        // Message d'une boîte mbox : c'est le fichier qui le contient qui est ouvert
+        Some((container, _)) => container.to_string(),
+        None => path.clone(),
+    };
+    open_file_in_explorer(target)?;
+    if let Err(e) = with_service_repository(&state, move |repo| repo.record_open(&path)) {
+        tracing::warn!("Ouverture non enregistrée : {}", e);
+    }
     Ok(())
 }
 
+/// Conteneur présent sur le disque et chemin de l'élément, pour un chemin virtuel (`<conteneur>!/<élément>`)
+fn split_virtual_path(path: &str) -> Option<(&str, &str)> {
+    if Path::new(path).exists() {
+        return None;
+    }
+    path.match_indices(ENTRY_SEPARATOR)
+        .map(|(index, _)| (&path[..index], &path[index + ENTRY_SEPARATOR.len()..]))
+        .find(|(container, _)| Path::new(container).is_file())
+}
+
+#[tauri::command]
+pub fn get_ranking_weights(state: tauri::State<'_, AppState>) -> Result<RankingWeights, String> {
+    with_service_repository_readonly(&state, |repo| {
+        Ok(RankingWeights::from_setting(repo.get_setting(RANKING_WEIGHTS)?.as_deref()))
+    })
+}
+
+#[tauri::command]
+pub fn save_ranking_weights(weights: RankingWeights, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    weights.validate()?;
+    let value = serde_json::to_string(&weights).map_err(|e| e.to_string())?;
+    with_service_repository(&state, move |repo| repo.set_setting(RANKING_WEIGHTS, Some(&value)))
+}
+
 #[tauri::command]
 pub fn search_files(
     query: SearchQuery,
@@ -20,6 +72,40 @@ pub fn search_files(
     with_service_repository_readonly(&state, |repo| repo.search(&query))
 }
 
+#[tauri::command]
+pub fn search_files_with_snippets(
+    query: SearchQuery,
+    state: tauri::State<'_, AppState>
+) -> Result<Vec<SearchHit>, String> {
+    with_service_repository_readonly(&state, |repo| repo.search_hits(&query))
+}
+
+/// Calcule à la demande les empreintes des fichiers de même taille puis regroupe les doublons.
+/// Le calcul est une tâche annulable (`cancel_job`).
+#[tauri::command]
+pub async fn find_duplicates(
+    query: DuplicateQuery,
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, AppState>
+) -> Result<DuplicateReport, String> {
+    let job = state.job_manager.start(JobKind::Checksums).map_err(|e| e.to_string())?;
+    emit_event(&window, EVENT_JOB_STARTED, job.info());
+    let (job_id, job_kind) = (job.id, job.kind);
+
+    let service_repository = state.service_repository.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        find_duplicates::find_duplicates(&service_repository, &job, query)
+    })
+    .await
+    .map_err(|e| format!("Recherche de doublons interrompue: {}", e))?;
+
+    if let Err(AppError::Cancelled(message)) = &result {
+        emit_event(&window, EVENT_JOB_CANCELLED, JobCancelled { id: job_id, kind: job_kind, message: message.clone() });
+    }
+
+    result.map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_all_types(state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
     with_service_repository_readonly(&state, |repo| repo.get_all_types())
@@ -37,7 +123,10 @@ pub fn get_all_paths(state: tauri::State<'_, AppState>) -> Result<Vec<String>, S
 
 #[tauri::command]
 pub fn reset_data(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    with_service_repository_readonly(&state, |repo| repo.reset_data())
+    with_service_repository(&state, |repo| {
+        repo.reset_data()?;
+        repo.set_setting(CONTENT_INDEXING_CHECKPOINT, None)
+    })
 }
 
 #[tauri::command]
@@ -46,15 +135,15 @@ pub fn save_paths(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, AppState>
 ) -> Result<String, String> {
-    let new_paths = with_service_repository(&state, |repo| {
-        repo.insert_paths(paths.clone())
+    let new_paths = with_service_repository(&state, move |repo| {
+        repo.insert_paths(paths)
     })?;
 
     let mut results = Vec::new();
 
     if !new_paths.is_empty() {
         let service_repository = state.service_repository.clone();
-        scan_files_async(window.clone(), new_paths.clone(), service_repository);
+        scan_files_async(window.clone(), new_paths.clone(), service_repository, ScanMode::Insert, state.job_manager.clone());
         tracing::info!("Started scan for {} new paths", new_paths.len());
         results.push(format!("Started scan for {} new paths", new_paths.len()));
     }
@@ -1,7 +1,12 @@
 use crate::application::use_cases::index_content::index_content_async;
+use crate::domain::entities::content::{ReaderSettings, READER_SETTINGS};
+use crate::domain::entities::job::JobKind;
+use crate::domain::entities::path_rule::IndexingRules;
+use crate::domain::entities::scan::ScanMode;
+use crate::infrastructure::filesystem::path_filter::PathFilter;
 use crate::infrastructure::filesystem::scanner::scan_files_async;
 use crate::shared::config::AppState;
-use crate::shared::helpers::with_service_repository_readonly;
+use crate::shared::helpers::{with_service_repository, with_service_repository_readonly};
 
 #[tauri::command]
 pub fn sync_files_and_folders(
@@ -25,7 +30,7 @@ pub fn sync_files_and_folders(
     tracing::info!("Démarrage de la synchronisation pour {} chemins", valid_paths.len());
 
     let service_repository = state.service_repository.clone();
-    scan_files_async(window, valid_paths, service_repository);
+    scan_files_async(window, valid_paths, service_repository, ScanMode::Reconcile, state.job_manager.clone());
 
     Ok(())
 }
@@ -35,8 +40,44 @@ pub fn start_content_indexing(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, AppState>
 ) -> Result<(), String> {
+    if state.job_manager.list().iter().any(|job| job.kind == JobKind::ContentIndexing) {
+        return Err("Une indexation du contenu est déjà en cours".to_string());
+    }
+
     // Utiliser le repository singleton depuis AppState
     let service_repository = state.service_repository.clone();
-    index_content_async(window, service_repository);
+    index_content_async(window, service_repository, state.job_manager.clone());
     Ok(())
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub fn get_indexing_rules(state: tauri::State<'_, AppState>) -> Result<IndexingRules, String> {
+    with_service_repository_readonly(&state, |repo| repo.get_indexing_rules())
+}
+
+/// Enregistre les règles ; elles s'appliquent au prochain scan et aux événements suivants du watcher
+#[tauri::command]
+pub fn save_indexing_rules(
+    rules: IndexingRules,
+    state: tauri::State<'_, AppState>
+) -> Result<(), String> {
+    // Compiler les motifs pour rejeter les globs invalides avant l'enregistrement
+    PathFilter::new(&rules, &[]).map_err(|e| e.to_string())?;
+
+    with_service_repository(&state, move |repo| repo.save_indexing_rules(&rules))
+}
+
+#[tauri::command]
+pub fn get_reader_settings(state: tauri::State<'_, AppState>) -> Result<ReaderSettings, String> {
+    with_service_repository_readonly(&state, |repo| {
+        Ok(ReaderSettings::from_setting(repo.get_setting(READER_SETTINGS)?.as_deref()))
+    })
+}
+
+/// Enregistre les limites d'extraction ; elles s'appliquent aux fichiers indexés ensuite
+#[tauri::command]
+pub fn save_reader_settings(settings: ReaderSettings, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    settings.validate()?;
+    let value = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    with_service_repository(&state, move |repo| repo.set_setting(READER_SETTINGS, Some(&value)))
+}
@@ -0,0 +1,26 @@
+use crate::domain::entities::job::JobInfo;
+use crate::shared::config::AppState;
+
+#[tauri::command]
+pub fn list_jobs(state: tauri::State<'_, AppState>) -> Vec<JobInfo> {
+    state.job_manager.list()
+}
+
+/// Arrête la tâche au prochain point de contrôle ; une indexation du contenu annulée reprendra là où elle s'est arrêtée
+#[tauri::command]
+pub fn cancel_job(id: u64, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    tracing::info!("Annulation de la tâche {}", id);
+    state.job_manager.cancel(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn pause_job(id: u64, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    tracing::info!("Mise en pause de la tâche {}", id);
+    state.job_manager.pause(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn resume_job(id: u64, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    tracing::info!("Reprise de la tâche {}", id);
+    state.job_manager.resume(id).map_err(|e| e.to_string())
+}
@@ -2,4 +2,5 @@ pub mod file_commands;
 pub mod ai_commands;
 pub mod indexing_commands;
 pub mod system_commands;
-pub mod watch_commands;
\ No newline at end of file
+pub mod watch_commands;
+pub mod job_commands;
\ No newline at end of file
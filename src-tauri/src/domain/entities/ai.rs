@@ -1,12 +1,55 @@
 use serde::{Serialize, Deserialize};
 
+/// Clé des paramètres sous laquelle les réglages du modèle d'IA sont conservés
+pub const AI_SETTINGS: &str = "ai_settings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AiProvider {
+    LmStudio,
+    /// `llama-server` de llama.cpp
+    LlamaCpp,
+    /// API native d'Ollama
+    Ollama,
+    /// Tout service compatible avec l'API OpenAI, avec une clé d'API éventuelle
+    OpenAiCompatible,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AiSettings {
+    pub provider: AiProvider,
+    pub url: String,
+    pub model: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Une clé d'API est enregistrée : l'interface ne reçoit que cet indicateur, jamais la clé
+    #[serde(default)]
+    pub has_api_key: bool,
+}
+
+impl Default for AiSettings {
+    fn default() -> Self {
+        Self {
+            provider: AiProvider::LmStudio,
+            url: "http://localhost:1234".to_string(),
+            model: "local-model".to_string(),
+            api_key: None,
+            has_api_key: false,
+        }
+    }
+}
+
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiRequest {
     pub prompt: String,
+    /// Consignes du modèle (message système)
+    pub system_prompt: Option<String>,
     pub model: Option<String>,
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
+    /// Schéma JSON imposé à la réponse, si le fournisseur le permet (`Ai::supports_json_schema`)
+    #[serde(default)]
+    pub response_schema: Option<serde_json::Value>,
 }
 
 
@@ -18,6 +61,28 @@ pub struct AiResponse {
 }
 
 
+/// Fragment de réponse d'une génération en cours (tâche `job_id`)
+#[derive(Debug, Clone, Serialize)]
+pub struct AiToken {
+    pub job_id: u64,
+    pub token: String,
+}
+
+/// Nouvel essai de la génération `job_id` : les fragments déjà reçus sont à oublier
+#[derive(Debug, Clone, Serialize)]
+pub struct AiReset {
+    pub job_id: u64,
+}
+
+/// Élément du flux d'une génération de requête, sur plusieurs essais
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AiStreamEvent {
+    Token(String),
+    /// Un nouvel essai commence : sa réponse remplace les fragments précédents
+    Reset,
+}
+
+
 #[derive(Debug, thiserror::Error)]
 pub enum AiError {
     #[error("Connection failed: {0}")]
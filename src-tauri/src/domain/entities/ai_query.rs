@@ -0,0 +1,363 @@
+//! Lecture tolérante de la `SearchQuery` produite par le modèle : extraction du JSON, valeurs par
+//! défaut, unités et échelles ramenées à celles de la recherche, types de fichiers connus
+
+use chrono::{DateTime, NaiveDate};
+use serde_json::{json, Value};
+use crate::domain::entities::search::SearchQuery;
+
+/// Nombre de résultats demandé quand le modèle n'en précise pas, et au plus
+pub const AI_QUERY_LIMIT: u32 = 1000;
+
+const MB: f64 = 1024.0 * 1024.0;
+// Borne « sans limite » en octets (u32::MAX)
+const UNBOUNDED_BYTES: f64 = 4_294_967_295.0;
+// Au-delà, un horodatage est en millisecondes
+const MILLIS_THRESHOLD: f64 = 1e11;
+// Au-delà (an 2255), une borne de date signifie « sans limite »
+const UNBOUNDED_SECONDS: f64 = 9e9;
+
+const SORT_BY: [&str; 6] = ["Name", "Size", "LastModified", "CreatedAt", "AccessedAt", "Relevance"];
+const SORT_ORDER: [&str; 2] = ["Asc", "Desc"];
+const DATE_MODE: [&str; 2] = ["Create", "Modify"];
+
+// Extensions équivalentes : demander l'une revient à demander toutes
+const TYPE_ALIASES: [&[&str]; 6] = [
+    &["jpg", "jpeg"],
+    &["tif", "tiff"],
+    &["htm", "html"],
+    &["yml", "yaml"],
+    &["mpg", "mpeg"],
+    &["md", "markdown"],
+];
+
+/// Schéma JSON de la réponse attendue, imposé au modèle quand le fournisseur le permet
+pub fn search_query_schema() -> Value {
+    let bounds = json!({ "type": "array", "items": { "type": "integer", "minimum": 0 }, "minItems": 2, "maxItems": 2 });
+    let strings = json!({ "type": "array", "items": { "type": "string" } });
+    json!({
+        "type": "object",
+        "properties": {
+            "text": { "type": "string" },
+            "filters": {
+                "type": "object",
+                "properties": {
+                    "is_dir": { "type": "boolean" },
+                    "folders": strings,
+                    "file_types": strings,
+                    "size_limit": bounds,
+                    "date_range": bounds,
+                    "date_mode": { "enum": DATE_MODE },
+                    "search_in_content": { "type": "boolean" },
+                    "sender": { "type": ["string", "null"] },
+                    "subject": { "type": ["string", "null"] },
+                },
+                "required": ["is_dir", "folders", "file_types", "size_limit", "date_range", "date_mode", "search_in_content"],
+            },
+            "sort_by": { "enum": SORT_BY },
+            "sort_order": { "enum": SORT_ORDER },
+            "search_in_content": { "type": "boolean" },
+            "path_pattern": { "type": ["string", "null"] },
+        },
+        "required": ["text", "filters", "sort_by", "sort_order", "search_in_content"],
+    })
+}
+
+/// Premier objet JSON lisible de la réponse, qu'il soit entouré de texte, placé dans un bloc
+/// de code ou (vieille habitude de certains modèles) échappé
+pub fn extract_json(content: &str) -> Option<Value> {
+    find_object(content).or_else(|| {
+        let unescaped = content
+            .replace("\\{", "{")
+            .replace("\\}", "}")
+            .replace("\\\"", "\"")
+            .replace("\\n", "\n")
+            .replace("\\t", " ");
+        find_object(&unescaped)
+    })
+}
+
+fn find_object(content: &str) -> Option<Value> {
+    content.match_indices('{').find_map(|(start, _)| {
+        let end = balanced_end(&content[start..])?;
+        serde_json::from_str::<Value>(&content[start..start + end])
+            .ok()
+            .filter(Value::is_object)
+    })
+}
+
+/// Longueur du bloc `{…}` qui commence le texte, accolades des chaînes ignorées
+fn balanced_end(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, c) in text.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(offset + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Lit la réponse du modèle. Les champs absents prennent leur valeur par défaut, les tailles
+/// sont ramenées en Mo et les dates en secondes ; l'erreur, en clair, peut être renvoyée au
+/// modèle pour qu'il corrige sa réponse.
+pub fn parse_ai_search_query(content: &str) -> Result<SearchQuery, String> {
+    let mut value = extract_json(content).ok_or("La réponse ne contient aucun objet JSON")?;
+    canonicalize(&mut value);
+
+    let mut query = serde_json::to_value(SearchQuery { limit: AI_QUERY_LIMIT, ..SearchQuery::default() })
+        .unwrap_or_default();
+    merge(&mut query, value);
+    serde_json::from_value(query).map_err(|e| format!("L'objet JSON ne correspond pas au format attendu : {}", e))
+}
+
+/// Recouvre `base` par les valeurs de `patch`, objet par objet ; `null` ne remplace rien
+fn merge(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                match base.get_mut(&key) {
+                    Some(slot) => merge(slot, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (_, Value::Null) => {}
+        (base, patch) => *base = patch,
+    }
+}
+
+fn canonicalize(value: &mut Value) {
+    canonical_variant(value.get_mut("sort_by"), &SORT_BY);
+    canonical_variant(value.get_mut("sort_order"), &SORT_ORDER);
+
+    let Some(filters) = value.get_mut("filters").filter(|filters| filters.is_object()) else { return };
+    canonical_variant(filters.get_mut("date_mode"), &DATE_MODE);
+    if let Some(Value::Array(bounds)) = filters.get_mut("size_limit") {
+        for bound in bounds.iter_mut() {
+            if let Some(size) = size_in_mb(bound) {
+                *bound = json!(size);
+            }
+        }
+    }
+    if let Some(Value::Array(bounds)) = filters.get_mut("date_range") {
+        for bound in bounds.iter_mut() {
+            if let Some(seconds) = timestamp_in_seconds(bound) {
+                *bound = json!(seconds);
+            }
+        }
+    }
+}
+
+/// « last_modified », « desc »… → variante attendue par serde
+fn canonical_variant(value: Option<&mut Value>, variants: &[&str]) {
+    let Some(value) = value else { return };
+    let Some(text) = value.as_str() else { return };
+    let key: String = text.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+    if let Some(variant) = variants.iter().find(|variant| variant.to_lowercase() == key) {
+        *value = json!(variant);
+    }
+}
+
+/// Taille en Mo : nombre de Mo, comme le demandent le prompt et le schéma, ou texte avec unité
+/// (« 5 Go », « 5242880 o »)
+fn size_in_mb(bound: &Value) -> Option<u32> {
+    let mb = match bound {
+        Value::Null => 0.0,
+        Value::Number(number) => number_in_mb(number.as_f64()?),
+        Value::String(text) => {
+            let text = text.trim().to_lowercase().replace(',', ".");
+            let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+            let amount: f64 = text[..split].trim().parse().ok()?;
+            match text[split..].trim() {
+                "" => number_in_mb(amount),
+                "b" | "o" | "bytes" | "octets" => amount / MB,
+                "k" | "kb" | "ko" | "kib" => amount / 1024.0,
+                "m" | "mb" | "mo" | "mib" => amount,
+                "g" | "gb" | "go" | "gib" => amount * 1024.0,
+                "t" | "tb" | "to" | "tib" => amount * 1024.0 * 1024.0,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(mb.max(0.0).ceil().min(u32::MAX as f64) as u32)
+}
+
+/// Nombre sans unité : des Mo, `u32::MAX` signifiant « sans limite »
+fn number_in_mb(amount: f64) -> f64 {
+    if amount >= UNBOUNDED_BYTES {
+        0.0
+    } else {
+        amount
+    }
+}
+
+/// Horodatage en secondes : secondes, millisecondes ou date (« 2024-05-01 », RFC 3339)
+fn timestamp_in_seconds(bound: &Value) -> Option<u64> {
+    let seconds = match bound {
+        Value::Null => 0.0,
+        Value::Number(number) => number.as_f64()?,
+        Value::String(text) => {
+            let text = text.trim();
+            match text.parse::<f64>() {
+                Ok(number) => number,
+                Err(_) => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map(|date| date.and_utc().timestamp())
+                    .or_else(|| DateTime::parse_from_rfc3339(text).ok().map(|date| date.timestamp()))?
+                    as f64,
+            }
+        }
+        _ => return None,
+    };
+    let seconds = if seconds >= MILLIS_THRESHOLD { seconds / 1000.0 } else { seconds };
+    if seconds >= UNBOUNDED_SECONDS {
+        return Some(0);
+    }
+    Some(seconds.max(0.0) as u64)
+}
+
+/// Met la requête du modèle en état d'être exécutée : bornes dans l'ordre, types de fichiers
+/// ramenés à ceux de l'index (`known_types`), pagination au début
+pub fn normalize_search_query(mut query: SearchQuery, known_types: &[String]) -> SearchQuery {
+    query.text = query.text.trim().to_string();
+    query.path_pattern = query.path_pattern
+        .map(|pattern| pattern.trim().to_string())
+        .filter(|pattern| !pattern.is_empty());
+    query.filters.folders = query.filters.folders.iter()
+        .map(|folder| folder.trim().to_string())
+        .filter(|folder| !folder.is_empty())
+        .collect();
+    query.filters.file_types = normalize_file_types(&query.filters.file_types, known_types);
+    for value in [&mut query.filters.sender, &mut query.filters.subject] {
+        *value = value.take()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+    }
+
+    if query.filters.size_limit[1] != 0 && query.filters.size_limit[0] > query.filters.size_limit[1] {
+        query.filters.size_limit.swap(0, 1);
+    }
+    if query.filters.date_range[1] != 0 && query.filters.date_range[0] > query.filters.date_range[1] {
+        query.filters.date_range.swap(0, 1);
+    }
+
+    query.search_in_content |= query.filters.search_in_content;
+    query.filters.search_in_content = query.search_in_content;
+    query.limit = if query.limit == 0 { AI_QUERY_LIMIT } else { query.limit.min(AI_QUERY_LIMIT) };
+    query.offset = 0;
+    query.cursor = None;
+    query
+}
+
+/// Extensions en minuscules et sans point, complétées de leurs équivalents, puis limitées aux
+/// types connus de l'index — sauf si aucune ne l'est : le filtre reste tel quel
+fn normalize_file_types(file_types: &[String], known_types: &[String]) -> Vec<String> {
+    let mut types: Vec<String> = Vec::new();
+    for file_type in file_types {
+        let file_type = file_type.trim().trim_start_matches('*').trim_start_matches('.').to_lowercase();
+        if file_type.is_empty() {
+            continue;
+        }
+        let aliases = TYPE_ALIASES.iter()
+            .find(|aliases| aliases.contains(&file_type.as_str()))
+            .map(|aliases| aliases.iter().map(|alias| alias.to_string()).collect())
+            .unwrap_or_else(|| vec![file_type]);
+        for alias in aliases {
+            if !types.contains(&alias) {
+                types.push(alias);
+            }
+        }
+    }
+
+    let known: Vec<String> = types.iter()
+        .filter(|file_type| known_types.iter().any(|known| known.eq_ignore_ascii_case(file_type)))
+        .cloned()
+        .collect();
+    if known.is_empty() { types } else { known }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::search::{DateMode, SearchFilters, SortBy};
+
+    #[test]
+    fn test_parse_wrapped_ai_output_with_units_and_millis() {
+        let content = "Voici la requête {demandée} :\n```json\n{\n  \"text\": \" rapport \",\n  \"filters\": {\
+            \"file_types\": [\".PDF\"], \"size_limit\": [\"5 Mo\", 4294967295],\
+            \"date_range\": [1722211200000, \"2024-08-05\"], \"date_mode\": \"modify\"\
+            },\n  \"sort_by\": \"last_modified\", \"limit\": null\n}\n```\nBonne recherche !";
+
+        let query = parse_ai_search_query(content).unwrap();
+        assert_eq!(query.text, " rapport ");
+        assert_eq!(query.filters.size_limit, [5, 0]);
+        assert_eq!(query.filters.date_range, [1722211200, 1722816000]);
+        assert_eq!(query.filters.date_mode, DateMode::Modify);
+        assert!(matches!(query.sort_by, Some(SortBy::LastModified)));
+        assert_eq!(query.limit, AI_QUERY_LIMIT);
+
+        // Sans unité, une taille est en Mo, même grande ; les octets doivent être précisés
+        let query = parse_ai_search_query(r#"{"filters": {"size_limit": ["5242880 o", 2048]}}"#).unwrap();
+        assert_eq!(query.filters.size_limit, [5, 2048]);
+
+        assert!(parse_ai_search_query("Je ne sais pas").is_err());
+        let error = parse_ai_search_query(r#"{"sort_by": "Biggest"}"#).unwrap_err();
+        assert!(error.contains("Biggest"), "{}", error);
+    }
+
+    #[test]
+    fn test_normalize_ai_search_query() {
+        let query = SearchQuery {
+            text: " facture ".to_string(),
+            filters: SearchFilters {
+                file_types: vec![".JPG".to_string(), "png".to_string(), "heic".to_string()],
+                size_limit: [100, 5],
+                date_range: [50, 0],
+                search_in_content: true,
+                ..SearchFilters::default()
+            },
+            offset: 40,
+            limit: 0,
+            ..SearchQuery::default()
+        };
+
+        let known = vec!["jpeg".to_string(), "png".to_string(), "pdf".to_string()];
+        let query = normalize_search_query(query, &known);
+        assert_eq!(query.text, "facture");
+        assert_eq!(query.filters.file_types, vec!["jpeg", "png"]);
+        assert_eq!(query.filters.size_limit, [5, 100]);
+        assert_eq!(query.filters.date_range, [50, 0]);
+        assert!(query.search_in_content);
+        assert_eq!((query.offset, query.limit), (0, AI_QUERY_LIMIT));
+
+        // Aucun type connu : le filtre est conservé plutôt qu'élargi à tous les fichiers
+        let query = SearchQuery {
+            filters: SearchFilters { file_types: vec!["heic".to_string()], ..SearchFilters::default() },
+            ..SearchQuery::default()
+        };
+        assert_eq!(normalize_search_query(query, &known).filters.file_types, vec!["heic"]);
+    }
+}
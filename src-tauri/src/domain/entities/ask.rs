@@ -0,0 +1,125 @@
+use serde::{Serialize, Deserialize};
+use crate::domain::entities::embedding::chunk_text;
+
+// Passages proposés au modèle, assez courts pour en citer plusieurs
+const PASSAGE_SIZE: usize = 800;
+const PASSAGE_OVERLAP: usize = 100;
+// Mots vides ignorés pour la recherche des passages (français et anglais)
+const STOP_WORDS: &[&str] = &[
+    "les", "des", "une", "est", "sont", "dans", "pour", "par", "sur", "avec", "que", "qui", "quoi",
+    "quel", "quelle", "quels", "quelles", "dont", "mes", "ses", "leurs", "aux", "pas", "plus", "comment",
+    "combien", "quand", "pourquoi", "elle", "ils", "nous", "vous", "fichier", "fichiers", "the", "and",
+    "are", "was", "were", "what", "which", "who", "when", "where", "how", "why", "does", "did", "for",
+    "with", "from", "about", "this", "that", "these", "those", "file", "files", "have", "has", "into",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AskQuery {
+    pub question: String,
+    /// Fichiers dont le contenu est proposé au modèle
+    #[serde(default = "default_max_files")]
+    pub max_files: u32,
+    /// Tokens (estimés) consacrés aux passages dans la requête au modèle
+    #[serde(default = "default_token_budget")]
+    pub token_budget: u32,
+}
+
+fn default_max_files() -> u32 {
+    5
+}
+
+fn default_token_budget() -> u32 {
+    3000
+}
+
+/// Passage fourni au modèle ; la réponse le cite par `[index]`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AskSource {
+    pub index: usize,
+    pub path: String,
+    pub passage: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AskAnswer {
+    pub answer: String,
+    pub sources: Vec<AskSource>,
+}
+
+/// Contenu indexé d'un fichier retenu par la recherche, du plus pertinent au moins pertinent
+#[derive(Debug, Clone)]
+pub struct AskDocument {
+    pub path: String,
+    pub content: String,
+}
+
+/// Estimation grossière (environ 4 caractères par token), suffisante pour respecter un budget
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Mots significatifs de la question, en minuscules et sans doublon
+pub fn question_terms(question: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in question.split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+        if word.chars().count() >= 3 && !STOP_WORDS.contains(&word.as_str()) && !terms.contains(&word) {
+            terms.push(word);
+        }
+    }
+    terms
+}
+
+/// Passages les plus proches des termes de la question, dans la limite de `token_budget`.
+/// Chaque passage est noté par le nombre de termes distincts qu'il contient ; à égalité, les
+/// fichiers les mieux classés par la recherche passent en premier.
+pub fn select_passages(terms: &[String], documents: &[AskDocument], token_budget: usize) -> Vec<AskSource> {
+    let mut candidates = Vec::new();
+    for (rank, document) in documents.iter().enumerate() {
+        for passage in chunk_text(&document.content, PASSAGE_SIZE, PASSAGE_OVERLAP) {
+            let lowercase = passage.to_lowercase();
+            let matches = terms.iter().filter(|term| lowercase.contains(term.as_str())).count();
+            if matches > 0 {
+                candidates.push((matches, rank, document.path.as_str(), passage));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let mut used = 0;
+    let mut sources = Vec::new();
+    for (_, _, path, passage) in candidates {
+        let cost = estimate_tokens(&passage);
+        if used + cost > token_budget {
+            continue;
+        }
+        used += cost;
+        sources.push(AskSource { index: sources.len() + 1, path: path.to_string(), passage });
+    }
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_passages_prefers_matching_terms_within_budget() {
+        let terms = question_terms("Quel est le montant de la facture EDF ?");
+        assert_eq!(terms, vec!["montant", "facture", "edf"]);
+
+        let documents = vec![
+            AskDocument { path: "/a.txt".to_string(), content: "La facture arrive bientôt.".to_string() },
+            AskDocument { path: "/b.txt".to_string(), content: "Facture EDF : montant 42 €.".to_string() },
+            AskDocument { path: "/c.txt".to_string(), content: "Rien à voir.".to_string() },
+        ];
+        let sources = select_passages(&terms, &documents, 1000);
+        let paths: Vec<&str> = sources.iter().map(|source| source.path.as_str()).collect();
+        assert_eq!(paths, vec!["/b.txt", "/a.txt"]);
+        assert_eq!(sources[0].index, 1);
+
+        // Le budget ne laisse la place qu'au premier passage
+        assert_eq!(select_passages(&terms, &documents, 8).len(), 1);
+        assert!(select_passages(&terms, &documents, 0).is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Serialize, Deserialize};
+
+/// Clé des paramètres sous laquelle les réglages d'extraction du contenu sont conservés
+pub const READER_SETTINGS: &str = "reader_settings";
+
+/// Sépare le chemin d'un conteneur de celui d'un de ses éléments : `sauvegarde.zip!/docs/rapport.pdf`
+pub const ENTRY_SEPARATOR: &str = "!/";
+
+/// Début de diapositive dans le texte indexé, suivi du numéro : un seul mot par diapositive,
+/// qu'aucune recherche ne peut atteindre, rendu « [Diapositive N] » à l'affichage
+pub const SLIDE_MARK: char = '\u{E002}';
+
+/// Texte extrait d'un fichier et métadonnées calculées pendant l'extraction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractedContent {
+    pub text: String,
+    /// Encodage du fichier source, uniquement pour les formats texte
+    pub encoding: Option<String>,
+    pub line_count: Option<u32>,
+    pub word_count: Option<u32>,
+    /// En-têtes du message, pour les courriers électroniques
+    pub mail: Option<MailHeaders>,
+    /// Éléments contenus dans le fichier (messages d'une boîte mbox, fichiers d'une archive), indexés comme des fichiers virtuels
+    pub entries: Vec<ExtractedEntry>,
+}
+
+/// En-têtes d'un courrier électronique, décodés
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MailHeaders {
+    pub subject: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Date d'envoi en secondes depuis l'époque Unix
+    pub date: Option<i64>,
+}
+
+/// Élément d'un fichier conteneur, indexé sous le chemin `<conteneur>!/<path>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedEntry {
+    /// Chemin relatif au conteneur, `!/` avant chaque archive imbriquée
+    pub path: String,
+    /// Nom affiché dans les résultats
+    pub name: String,
+    pub file_type: Option<String>,
+    pub size: u64,
+    /// Date de l'élément en secondes depuis l'époque Unix, celle du conteneur à défaut
+    pub modified: Option<i64>,
+    pub content: ExtractedContent,
+}
+
+impl ExtractedContent {
+    /// Fichier texte : les comptes portent sur `source`, le contenu avant nettoyage
+    pub fn from_source(text: String, source: &str, encoding: Option<String>) -> Self {
+        Self {
+            text,
+            encoding,
+            line_count: Some(count(source.lines())),
+            word_count: Some(count(source.split_whitespace())),
+            ..Self::default()
+        }
+    }
+
+    /// Document converti (PDF, Word...) : les lignes du texte extrait ne correspondent à rien, seuls les mots sont comptés
+    pub fn from_text(text: String) -> Self {
+        let word_count = Some(count(text.split_whitespace().filter(|word| !word.starts_with(SLIDE_MARK))));
+        Self { text, word_count, ..Self::default() }
+    }
+}
+
+/// Numéro de la diapositive où se trouve la position `offset` du texte
+pub fn slide_at(text: &str, offset: usize) -> Option<u32> {
+    let mark = text.get(..offset)?.rfind(SLIDE_MARK)?;
+    let digits = &text[mark + SLIDE_MARK.len_utf8()..];
+    let end = digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
+/// Remplace les marques de diapositive par « [Diapositive N] »
+pub fn render_slide_marks(text: &str) -> String {
+    let mut parts = text.split(SLIDE_MARK);
+    let mut rendered = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let end = part.find(|c: char| !c.is_ascii_digit()).unwrap_or(part.len());
+        rendered.push_str(&format!("[Diapositive {}]{}", &part[..end], &part[end..]));
+    }
+    rendered
+}
+
+/// Limites d'extraction propres à certains formats
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReaderSettings {
+    /// Cellules lues par classeur, hors ligne d'en-tête de chaque feuille
+    pub spreadsheet_max_cells: usize,
+    /// Niveaux d'archives ouverts : 1 ne lit pas les archives contenues dans une archive
+    pub archive_max_depth: usize,
+    /// Volume décompressé au plus par archive, archives imbriquées comprises, en Mo
+    pub archive_max_size_mb: u64,
+}
+
+impl Default for ReaderSettings {
+    fn default() -> Self {
+        Self {
+            spreadsheet_max_cells: 10_000,
+            archive_max_depth: 3,
+            archive_max_size_mb: 512,
+        }
+    }
+}
+
+impl ReaderSettings {
+    /// Réglages enregistrés dans les paramètres, ou réglages par défaut s'ils sont absents ou invalides
+    pub fn from_setting(value: Option<&str>) -> Self {
+        value.and_then(|value| serde_json::from_str::<Self>(value).ok())
+            .filter(|settings| settings.validate().is_ok())
+            .unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.spreadsheet_max_cells == 0 {
+            return Err("Le nombre de cellules lues par classeur doit être positif".to_string());
+        }
+        if !(1..=10).contains(&self.archive_max_depth) {
+            return Err("La profondeur des archives imbriquées doit être comprise entre 1 et 10".to_string());
+        }
+        if self.archive_max_size_mb == 0 {
+            return Err("La taille décompressée maximale des archives doit être positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn count<T>(items: impl Iterator<Item = T>) -> u32 {
+    u32::try_from(items.count()).unwrap_or(u32::MAX)
+}
@@ -0,0 +1,35 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DuplicateQuery {
+    /// Limite la recherche à un dossier indexé
+    pub root: Option<String>,
+    /// Taille minimale en octets
+    #[serde(default)]
+    pub min_size: u64,
+}
+
+/// Fichier dont la taille n'est pas unique dans l'index, donc susceptible d'avoir des doublons
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumCandidate {
+    pub path: String,
+    pub size: u64,
+    pub checksum: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub checksum: String,
+    pub size: u64,
+    pub paths: Vec<String>,
+    /// Espace récupérable en ne gardant qu'un exemplaire
+    pub wasted_space: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DuplicateReport {
+    /// Triés par espace gaspillé décroissant
+    pub groups: Vec<DuplicateGroup>,
+    pub duplicate_files: usize,
+    pub wasted_space: u64,
+}
@@ -0,0 +1,130 @@
+use serde::{Serialize, Deserialize};
+use crate::domain::entities::file::File;
+
+/// Clé des paramètres sous laquelle les réglages d'embeddings sont conservés
+pub const EMBEDDING_SETTINGS: &str = "embedding_settings";
+
+/// Service d'embeddings compatible OpenAI (`/v1/embeddings`), LM Studio par défaut
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingSettings {
+    pub enabled: bool,
+    pub url: String,
+    pub model: String,
+}
+
+impl Default for EmbeddingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "http://localhost:1234".to_string(),
+            model: "text-embedding-nomic-embed-text-v1.5".to_string(),
+        }
+    }
+}
+
+/// Passage du contenu d'un fichier et son vecteur (normalisé)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkEmbedding {
+    pub chunk_index: u32,
+    pub content: String,
+    pub vector: Vec<f32>,
+}
+
+/// Contenu indexé d'un fichier qui n'a pas encore d'embeddings pour le modèle courant
+#[derive(Debug, Clone)]
+pub struct PendingEmbedding {
+    pub file_id: i64,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticQuery {
+    pub text: String,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+fn default_limit() -> u32 {
+    20
+}
+
+/// Fichier le plus proche de la requête et son passage le plus proche
+#[derive(Debug, Clone, Serialize)]
+pub struct SemanticHit {
+    pub file: File,
+    /// Similarité cosinus, de -1 à 1 (plus grand = plus proche)
+    pub score: f32,
+    pub passage: String,
+}
+
+/// Découpe le texte en passages d'au plus `max_chars` caractères qui se chevauchent de `overlap`,
+/// en coupant de préférence sur un espace
+pub fn chunk_text(text: &str, max_chars: usize, overlap: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let overlap = overlap.min(max_chars / 2);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < chars.len() {
+        let mut end = (start + max_chars).min(chars.len());
+        if end < chars.len() {
+            // Reculer jusqu'au dernier espace de la seconde moitié du passage
+            if let Some(space) = chars[start + max_chars / 2..end].iter().rposition(|c| c.is_whitespace()) {
+                end = start + max_chars / 2 + space;
+            }
+        }
+
+        let chunk: String = chars[start..end].iter().collect();
+        let chunk = chunk.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !chunk.is_empty() {
+            chunks.push(chunk);
+        }
+
+        if end == chars.len() {
+            break;
+        }
+        // Le passage suivant reprend au début d'un mot dans la zone de chevauchement
+        let mut next = end.saturating_sub(overlap).max(start + 1);
+        if let Some(space) = chars[next..end].iter().position(|c| c.is_whitespace()) {
+            next += space + 1;
+        }
+        start = next;
+    }
+
+    chunks
+}
+
+/// Ramène le vecteur à une norme de 1 : la similarité cosinus devient un simple produit scalaire
+pub fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_overlaps_and_breaks_on_spaces() {
+        let text = "alpha beta gamma delta epsilon zeta eta theta iota kappa";
+        let chunks = chunk_text(text, 20, 6);
+
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 20));
+        assert_eq!(chunks.first().map(String::as_str), Some("alpha beta gamma"));
+        assert!(chunks.last().unwrap().ends_with("kappa"));
+        // Chaque mot se retrouve dans au moins un passage
+        for word in text.split(' ') {
+            assert!(chunks.iter().any(|chunk| chunk.split(' ').any(|w| w == word)), "{} absent de {:?}", word, chunks);
+        }
+
+        assert!(chunk_text("   \n ", 20, 6).is_empty());
+        assert_eq!(chunk_text("court", 20, 6), vec!["court"]);
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum JobKind {
+    Scan,
+    ContentIndexing,
+    Checksums,
+    /// Génération d'un modèle d'IA (recherche en langage naturel, question sur les fichiers)
+    AiQuery,
+}
+
+impl JobKind {
+    /// Une seule instance à la fois (deux indexations du contenu traiteraient les mêmes fichiers)
+    pub fn is_exclusive(&self) -> bool {
+        matches!(self, JobKind::ContentIndexing | JobKind::Checksums)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobState {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: u64,
+    pub kind: JobKind,
+    pub state: JobState,
+    /// Secondes depuis l'epoch
+    pub started_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobCancelled {
+    pub id: u64,
+    pub kind: JobKind,
+    pub message: String,
+}
+
+/// Avancement persisté d'une indexation du contenu interrompue, pour reprendre la progression
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexCheckpoint {
+    pub processed: usize,
+    pub total: usize,
+}
@@ -4,4 +4,14 @@ pub mod scan;
 pub mod search;
 pub mod progress;
 pub mod ai;
-pub mod query_builder;
\ No newline at end of file
+pub mod ai_query;
+pub mod query_builder;
+pub mod file_event;
+pub mod path_rule;
+pub mod job;
+pub mod duplicate;
+pub mod content;
+pub mod query_parser;
+pub mod embedding;
+pub mod ranking;
+pub mod ask;
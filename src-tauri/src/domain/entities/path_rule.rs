@@ -0,0 +1,42 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleKind {
+    Include,
+    Exclude,
+}
+
+impl RuleKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleKind::Include => "include",
+            RuleKind::Exclude => "exclude",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "include" => Some(RuleKind::Include),
+            "exclude" => Some(RuleKind::Exclude),
+            _ => None,
+        }
+    }
+}
+
+/// Règle glob d'inclusion ou d'exclusion.
+/// Sans `/`, le motif porte sur le nom du fichier ; avec `/`, sur le chemin relatif à la racine
+/// (ou sur le chemin absolu s'il commence par `/`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathRule {
+    pub pattern: String,
+    pub kind: RuleKind,
+    /// Racine indexée concernée, `None` pour une règle globale
+    pub root: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexingRules {
+    pub rules: Vec<PathRule>,
+    /// Respecter les fichiers `.gitignore` et `.ignore` rencontrés sous les racines
+    pub respect_ignore_files: bool,
+}
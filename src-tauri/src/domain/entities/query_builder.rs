@@ -1,3 +1,40 @@
+use crate::domain::entities::query_parser::{
+    CompareOp, Comparison, DateField, DateValue, FileFlag, QueryExpr, QueryTerm, TextValue,
+};
+use crate::domain::entities::content::SLIDE_MARK;
+use crate::domain::entities::ranking::{relevance_sql, RankingSignals, RankingWeights};
+
+// Marqueurs des correspondances dans les extraits : caractères à usage privé, remplacés
+// par `<mark>` une fois le contenu échappé (constantes, donc sûres à insérer dans le SQL)
+pub const HIGHLIGHT_START: &str = "\u{E000}";
+pub const HIGHLIGHT_END: &str = "\u{E001}";
+pub const SNIPPET_ELLIPSIS: &str = "…";
+const SNIPPET_TOKENS: u32 = 16;
+// Extrait élargi (maximum accepté par FTS5) redécoupé autour de chaque correspondance
+const CONTEXT_TOKENS: u32 = 64;
+/// Fonction SQL `fuzzy_score(motif, nom, chemin)` enregistrée sur chaque connexion
+pub const FUZZY_SCORE_FUNCTION: &str = "fuzzy_score";
+// Score maximal d'un mot pour `fuzzy_score` (correspondance exacte du nom)
+const FUZZY_WORD_MAX_SCORE: f64 = 1000.0;
+
+/// Fautes de frappe tolérées par `fuzzy_score` pour un mot : une dès trois caractères, deux à partir de huit
+pub fn fuzzy_max_typos(word_len: usize) -> usize {
+    match word_len {
+        0..=2 => 0,
+        3..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Correspondance des termes sans champ avec le nom des fichiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameMatch {
+    /// Sous-chaîne du nom (index trigramme)
+    Substring,
+    /// Approximative, sur le nom ou à défaut le chemin : fautes de frappe, sous-séquences
+    Fuzzy,
+}
+
 pub struct QueryBuilder {
     pub conditions: Vec<String>,
     pub params: Vec<Box<dyn rusqlite::ToSql>>,
@@ -5,6 +42,10 @@ pub struct QueryBuilder {
     pub cte_params: Vec<Box<dyn rusqlite::ToSql>>,
     pub has_fts: bool,
     pub fts_query: Option<String>,
+    /// Termes sans champ cherchés dans les noms, qui donnent le score de nom du classement
+    pub name_pattern: Option<String>,
+    /// Tri par pertinence : poids et horodatage courant
+    pub ranking: Option<(RankingWeights, i64)>,
 }
 
 impl QueryBuilder {
@@ -16,6 +57,8 @@ impl QueryBuilder {
             cte_params: Vec::new(),
             has_fts: false,
             fts_query: None,
+            name_pattern: None,
+            ranking: None,
         }
     }
 
@@ -38,7 +81,101 @@ impl QueryBuilder {
         self.fts_query = Some(query);
     }
 
+    /// Filtre les fichiers dont le nom (ou à défaut le chemin) correspond approximativement
+    /// à tous les mots du motif. Aucun index ne couvre les sous-séquences ni les fautes de frappe :
+    /// chaque fichier est évalué, d'où son usage en repli quand la recherche par sous-chaîne est vide.
+    pub fn add_fuzzy_condition(&mut self, pattern: &str) {
+        self.add_condition(
+            format!("{}(?, files.name, files.path) IS NOT NULL", FUZZY_SCORE_FUNCTION),
+            Box::new(pattern.to_string()),
+        );
+    }
+
+    /// Classe les résultats par pertinence (contenu, nom, fraîcheur, ouvertures) avant le tri demandé
+    pub fn set_ranking(&mut self, weights: RankingWeights, now: i64) {
+        self.ranking = Some((weights, now));
+    }
+
+    /// Sous-chaîne du nom ou du chemin, via l'index trigramme quand c'est possible
+    pub fn add_substring_condition(&mut self, column: &str, value: &TextValue) {
+        let mut params = Vec::new();
+        let condition = like_condition(column, value, &mut params);
+        self.add_simple_condition(condition);
+        self.params.extend(params);
+    }
+
+    /// Sous-chaîne insensible à la casse sur une colonne absente de l'index trigramme
+    pub fn add_contains_condition(&mut self, column: &str, value: &str) {
+        self.add_condition(format!("files.{} LIKE ? ESCAPE '\\'", column), Box::new(format!("%{}%", escape_like(value))));
+    }
+
+    /// Ajoute une requête analysée. Les parties de premier niveau qui ne portent que sur le contenu
+    /// (sans négation) forment la requête FTS5, pour garder le score BM25 et les extraits ; le reste
+    /// devient des conditions SQL, le contenu y étant cherché par sous-requête.
+    /// `content_by_default` : les termes sans champ portent sur le contenu plutôt que sur le nom.
+    /// `name_match` : comment les termes sans champ de premier niveau sont cherchés dans le nom.
+    pub fn add_query(&mut self, expr: &QueryExpr, content_by_default: bool, name_match: NameMatch, now: i64) {
+        let conjuncts: Vec<&QueryExpr> = match expr {
+            QueryExpr::And(items) => items.iter().collect(),
+            other => vec![other],
+        };
+
+        let mut fts_parts = Vec::new();
+        let mut name_words = Vec::new();
+        for conjunct in conjuncts {
+            if let Some(fts) = fts_expression(conjunct, content_by_default) {
+                fts_parts.push(fts);
+                continue;
+            }
+
+            // Les expressions exactes et les jokers gardent la recherche par sous-chaîne
+            if let QueryExpr::Term(QueryTerm::Text(value)) = conjunct {
+                if !value.phrase && !value.value.contains('*') {
+                    name_words.push(value.value.as_str());
+                    if name_match == NameMatch::Fuzzy {
+                        continue;
+                    }
+                }
+            }
+
+            let mut params = Vec::new();
+            let condition = sql_expression(conjunct, content_by_default, now, &mut params);
+            self.add_simple_condition(condition);
+            self.params.extend(params);
+        }
+
+        if !fts_parts.is_empty() {
+            self.add_fts_condition(fts_parts.join(" AND "));
+        }
+        if !name_words.is_empty() {
+            let pattern = name_words.join(" ");
+            if name_match == NameMatch::Fuzzy {
+                self.add_fuzzy_condition(&pattern);
+            }
+            self.name_pattern = Some(pattern);
+        }
+    }
+
     pub fn build(self, sort_by: &str, sort_order: &str, limit: u32, offset: u32, cursor: Option<i64>) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        self.build_select("files.*", "*", sort_by, sort_order, limit, offset, cursor)
+    }
+
+    /// Comme `build`, avec en plus le score BM25, un extrait `snippet()` court, un extrait élargi
+    /// et, pour les présentations, le contenu qui situe les extraits (colonnes NULL hors recherche plein texte)
+    pub fn build_hits(self, sort_by: &str, sort_order: &str, limit: u32, offset: u32, cursor: Option<i64>) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let fts_columns = format!(
+            "files.*, bm25(fts_content) AS score, \
+             snippet(fts_content, 0, '{start}', '{end}', '{ellipsis}', {tokens}) AS snippet, \
+             snippet(fts_content, 0, '{start}', '{end}', '{ellipsis}', {context}) AS highlighted, \
+             CASE WHEN instr(fts_content.content, '{slide}') > 0 THEN fts_content.content END AS slides",
+            start = HIGHLIGHT_START, end = HIGHLIGHT_END, ellipsis = SNIPPET_ELLIPSIS,
+            tokens = SNIPPET_TOKENS, context = CONTEXT_TOKENS, slide = SLIDE_MARK
+        );
+        self.build_select(&fts_columns, "*, NULL AS score, NULL AS snippet, NULL AS highlighted, NULL AS slides", sort_by, sort_order, limit, offset, cursor)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build_select(self, fts_columns: &str, columns: &str, sort_by: &str, sort_order: &str, limit: u32, offset: u32, cursor: Option<i64>) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
         let mut all_params = Vec::new();
 
         let cte_prefix = if !self.cte_conditions.is_empty() {
@@ -49,6 +186,29 @@ impl QueryBuilder {
             String::new()
         };
 
+        // 1. Score de pertinence (colonne `relevance`), classé avant le tri demandé.
+        // Sans terme de recherche, il n'y a rien à classer : seul le tri demandé s'applique.
+        let rankable = self.has_fts || self.name_pattern.is_some();
+        let ranking = self.ranking.filter(|_| rankable);
+        let (relevance_column, relevance_order) = match ranking {
+            Some((weights, now)) => {
+                let name_score = self.name_pattern.as_ref().map(|pattern| format!(
+                    "(COALESCE({}(?, files.name, files.path), 0) / {:.1})",
+                    FUZZY_SCORE_FUNCTION, FUZZY_WORD_MAX_SCORE * pattern.split_whitespace().count().max(1) as f64
+                ));
+                let signals = RankingSignals { name_score: name_score.as_deref(), has_content: self.has_fts };
+
+                // Paramètres dans l'ordre de l'expression : fraîcheur, ouvertures, puis motif du nom
+                all_params.push(Box::new(now) as Box<dyn rusqlite::ToSql>);
+                all_params.push(Box::new(now) as Box<dyn rusqlite::ToSql>);
+                if let Some(pattern) = &self.name_pattern {
+                    all_params.push(Box::new(pattern.clone()) as Box<dyn rusqlite::ToSql>);
+                }
+                (format!(", {} AS relevance", relevance_sql(&weights, &signals)), "relevance DESC, ")
+            }
+            None => (String::new(), ""),
+        };
+
         let mut where_clause = if self.conditions.is_empty() {
             "1=1".to_string()
         } else {
@@ -85,21 +245,209 @@ impl QueryBuilder {
 
         let sql = if self.has_fts {
             format!(
-                "{}SELECT files.* FROM files \
+                "{}SELECT {}{} FROM files \
                  JOIN fts_content ON files.id = fts_content.file_id \
                  WHERE fts_content.content MATCH ? AND {} \
-                 ORDER BY bm25(fts_content) ASC, files.{} {} {}",
-                cte_prefix, where_clause, sort_by, sort_order, pagination
+                 ORDER BY {}, files.{} {} {}",
+                cte_prefix, fts_columns, relevance_column, where_clause,
+                if relevance_order.is_empty() { "bm25(fts_content) ASC" } else { "relevance DESC" },
+                sort_by, sort_order, pagination
             )
         } else {
             format!(
-                "{}SELECT * FROM files \
+                "{}SELECT {}{} FROM files \
                  WHERE {} \
-                 ORDER BY {} {} {}",
-                cte_prefix, where_clause, sort_by, sort_order, pagination
+                 ORDER BY {}{} {} {}",
+                cte_prefix, columns, relevance_column, where_clause, relevance_order, sort_by, sort_order, pagination
             )
         };
 
         (sql, all_params)
     }
-}
\ No newline at end of file
+}
+
+fn content_value(term: &QueryTerm, content_by_default: bool) -> Option<&TextValue> {
+    match term {
+        QueryTerm::Content(value) => Some(value),
+        QueryTerm::Text(value) if content_by_default => Some(value),
+        _ => None,
+    }
+}
+
+/// Terme FTS5 : toujours entre guillemets, `*` final pour une recherche par préfixe
+fn fts_term(value: &TextValue) -> String {
+    let (text, prefix) = match value.value.strip_suffix('*') {
+        Some(text) if !value.phrase && !text.is_empty() => (text, true),
+        _ => (value.value.as_str(), false),
+    };
+    format!("\"{}\"{}", text.replace('"', "\"\""), if prefix { "*" } else { "" })
+}
+
+/// Expression FTS5 si l'expression ne porte que sur le contenu (FTS5 n'a pas de NOT unaire)
+fn fts_expression(expr: &QueryExpr, content_by_default: bool) -> Option<String> {
+    match expr {
+        QueryExpr::Term(term) => content_value(term, content_by_default).map(fts_term),
+        QueryExpr::And(items) | QueryExpr::Or(items) => {
+            let operator = if matches!(expr, QueryExpr::And(_)) { " AND " } else { " OR " };
+            let parts = items.iter()
+                .map(|item| fts_expression(item, content_by_default))
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("({})", parts.join(operator)))
+        }
+        QueryExpr::Not(_) => None,
+    }
+}
+
+fn sql_expression(expr: &QueryExpr, content_by_default: bool, now: i64, params: &mut Vec<Box<dyn rusqlite::ToSql>>) -> String {
+    match expr {
+        QueryExpr::And(items) | QueryExpr::Or(items) => {
+            let operator = if matches!(expr, QueryExpr::And(_)) { " AND " } else { " OR " };
+            let parts: Vec<String> = items.iter()
+                .map(|item| sql_expression(item, content_by_default, now, params))
+                .collect();
+            format!("({})", parts.join(operator))
+        }
+        QueryExpr::Not(inner) => format!("NOT {}", sql_expression(inner, content_by_default, now, params)),
+        QueryExpr::Term(term) => sql_term(term, content_by_default, now, params),
+    }
+}
+
+fn sql_term(term: &QueryTerm, content_by_default: bool, now: i64, params: &mut Vec<Box<dyn rusqlite::ToSql>>) -> String {
+    if let Some(value) = content_value(term, content_by_default) {
+        params.push(Box::new(fts_term(value)));
+        return "(files.id IN (SELECT file_id FROM fts_content WHERE fts_content MATCH ?))".to_string();
+    }
+
+    match term {
+        QueryTerm::Text(value) | QueryTerm::Name(value) => like_condition("name", value, params),
+        QueryTerm::Path(value) => like_condition("path", value, params),
+        QueryTerm::Extension(extension) => equals_condition("file_type", extension, params),
+        QueryTerm::Owner(owner) => equals_condition("owner", owner, params),
+        QueryTerm::Group(group) => equals_condition("`group`", group, params),
+        QueryTerm::Encoding(encoding) => equals_condition("encoding", encoding, params),
+        QueryTerm::Mime(mime) => {
+            // `image/*` ou `image` : toute la famille
+            let family = mime.trim_end_matches("/*");
+            if family.contains('/') {
+                equals_condition("mime_type", family, params)
+            } else {
+                params.push(Box::new(format!("{}/%", escape_like(family))));
+                "(files.mime_type LIKE ? ESCAPE '\\')".to_string()
+            }
+        }
+        QueryTerm::Size(comparison) => compare_condition("size", comparison, params),
+        QueryTerm::Lines(comparison) => compare_condition("line_count", comparison, params),
+        QueryTerm::Words(comparison) => compare_condition("word_count", comparison, params),
+        QueryTerm::Date(field, comparison) => date_condition(*field, comparison, now, params),
+        QueryTerm::Flag(flag) => match flag {
+            FileFlag::Dir => "(files.is_dir = 1)",
+            FileFlag::File => "(files.is_dir = 0)",
+            FileFlag::Hidden => "(files.is_hidden = 1)",
+            FileFlag::Readonly => "(files.is_readonly = 1)",
+            FileFlag::Executable => "(files.is_executable = 1)",
+            FileFlag::Symlink => "(files.is_symlink = 1)",
+            FileFlag::System => "(files.is_system = 1)",
+            FileFlag::Encrypted => "(files.is_encrypted = 1)",
+            FileFlag::ContentIndexed => "(files.content_indexed = 1)",
+        }.to_string(),
+        QueryTerm::Content(_) => unreachable!("terme de contenu traité par FTS"),
+    }
+}
+
+fn equals_condition(column: &str, value: &str, params: &mut Vec<Box<dyn rusqlite::ToSql>>) -> String {
+    params.push(Box::new(value.to_string()));
+    format!("(files.{} = ? COLLATE NOCASE)", column)
+}
+
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Sous-chaîne insensible à la casse ; `*` sert de joker hors expression exacte.
+/// Les segments d'au moins trois caractères passent par l'index trigramme `fts_names` :
+/// le LIKE ne vérifie plus que les candidats au lieu de parcourir toute la table.
+fn like_condition(column: &str, value: &TextValue, params: &mut Vec<Box<dyn rusqlite::ToSql>>) -> String {
+    let segments: Vec<&str> = if value.phrase {
+        vec![value.value.as_str()]
+    } else {
+        value.value.split('*').collect()
+    };
+    let pattern = segments.iter().map(|segment| escape_like(segment)).collect::<Vec<_>>().join("%");
+    let like = format!("files.{} LIKE ? ESCAPE '\\'", column);
+
+    match trigram_query(column, &segments) {
+        Some(query) => {
+            params.push(Box::new(query));
+            params.push(Box::new(format!("%{}%", pattern)));
+            format!("(files.id IN (SELECT rowid FROM fts_names WHERE fts_names MATCH ?) AND {})", like)
+        }
+        None => {
+            params.push(Box::new(format!("%{}%", pattern)));
+            format!("({})", like)
+        }
+    }
+}
+
+/// Requête FTS5 trigramme limitée à la colonne ; `None` si aucun segment n'a trois caractères
+fn trigram_query(column: &str, segments: &[&str]) -> Option<String> {
+    let phrases: Vec<String> = segments.iter()
+        .filter(|segment| segment.chars().count() >= 3)
+        .map(|segment| format!("{} : \"{}\"", column, segment.replace('"', "\"\"")))
+        .collect();
+    (!phrases.is_empty()).then(|| phrases.join(" AND "))
+}
+
+fn sql_operator(op: CompareOp) -> &'static str {
+    match op {
+        CompareOp::Eq => "=",
+        CompareOp::Gt => ">",
+        CompareOp::Ge => ">=",
+        CompareOp::Lt => "<",
+        CompareOp::Le => "<=",
+    }
+}
+
+fn compare_condition(column: &str, comparison: &Comparison<u64>, params: &mut Vec<Box<dyn rusqlite::ToSql>>) -> String {
+    params.push(Box::new(i64::try_from(comparison.value).unwrap_or(i64::MAX)));
+    format!("(files.{} {} ?)", column, sql_operator(comparison.op))
+}
+
+fn date_condition(field: DateField, comparison: &Comparison<DateValue>, now: i64, params: &mut Vec<Box<dyn rusqlite::ToSql>>) -> String {
+    const DAY: i64 = 86_400;
+
+    let column = match field {
+        DateField::Modified => "files.last_modified",
+        DateField::Created => "files.created_at",
+        DateField::Accessed => "files.accessed_at",
+    };
+
+    // Bornes [début, fin) sur la colonne
+    let (start, end) = match comparison.value {
+        DateValue::Age(age) => {
+            let threshold = now.saturating_sub(i64::try_from(age).unwrap_or(i64::MAX));
+            match comparison.op {
+                // `<2w` ou `2w` : modifié il y a moins de deux semaines
+                CompareOp::Lt | CompareOp::Le | CompareOp::Eq => (Some(threshold), None),
+                CompareOp::Gt | CompareOp::Ge => (None, Some(threshold)),
+            }
+        }
+        DateValue::Day(day) => match comparison.op {
+            CompareOp::Eq => (Some(day), Some(day + DAY)),
+            CompareOp::Gt => (Some(day + DAY), None),
+            CompareOp::Ge => (Some(day), None),
+            CompareOp::Lt => (None, Some(day)),
+            CompareOp::Le => (None, Some(day + DAY)),
+        },
+    };
+
+    let mut conditions = Vec::new();
+    if let Some(start) = start {
+        conditions.push(format!("{} >= ?", column));
+        params.push(Box::new(start));
+    }
+    if let Some(end) = end {
+        conditions.push(format!("{} < ?", column));
+        params.push(Box::new(end));
+    }
+    format!("({})", conditions.join(" AND "))
+}
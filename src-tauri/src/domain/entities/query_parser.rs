@@ -0,0 +1,463 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use crate::shared::errors::AppError;
+
+/// Arbre d'une requête de la barre de recherche, par exemple
+/// `ext:pdf size:>5MB modified:<2w -draft "annual report"`
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryExpr {
+    And(Vec<QueryExpr>),
+    Or(Vec<QueryExpr>),
+    Not(Box<QueryExpr>),
+    Term(QueryTerm),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryTerm {
+    /// Terme sans champ : nom du fichier, ou contenu si la recherche porte sur le contenu
+    Text(TextValue),
+    Name(TextValue),
+    Path(TextValue),
+    Content(TextValue),
+    Extension(String),
+    Owner(String),
+    Group(String),
+    Encoding(String),
+    Mime(String),
+    Size(Comparison<u64>),
+    Lines(Comparison<u64>),
+    Words(Comparison<u64>),
+    Date(DateField, Comparison<DateValue>),
+    Flag(FileFlag),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextValue {
+    pub value: String,
+    /// Entre guillemets : expression exacte
+    pub phrase: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comparison<T> {
+    pub op: CompareOp,
+    pub value: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    Modified,
+    Created,
+    Accessed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateValue {
+    /// Âge relatif en secondes (`2w`) : `<` signifie « plus récent que »
+    Age(u64),
+    /// Début du jour local (`2024-01-31`), en secondes depuis l'epoch
+    Day(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFlag {
+    Dir,
+    File,
+    Hidden,
+    Readonly,
+    Executable,
+    Symlink,
+    System,
+    Encrypted,
+    ContentIndexed,
+}
+
+/// Erreur de syntaxe, `position` en caractères depuis le début de la requête
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl From<QueryParseError> for AppError {
+    fn from(error: QueryParseError) -> Self {
+        AppError::Validation(format!("Requête invalide à la position {}: {}", error.position, error.message))
+    }
+}
+
+type ParseResult<T> = Result<T, QueryParseError>;
+
+fn error<T>(position: usize, message: impl Into<String>) -> ParseResult<T> {
+    Err(QueryParseError { position, message: message.into() })
+}
+
+/// Analyse la requête ; `None` si elle est vide
+pub fn parse_query(input: &str) -> ParseResult<Option<QueryExpr>> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parser = Parser { tokens, index: 0, end: input.chars().count() };
+    let expr = parser.parse_or()?;
+
+    match parser.peek() {
+        None => Ok(Some(expr)),
+        Some(token) if token.kind == TokenKind::RParen => error(token.position, "parenthèse fermante inattendue"),
+        Some(token) => error(token.position, "terme inattendu"),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term(QueryTerm),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+}
+
+fn tokenize(input: &str) -> ParseResult<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token { kind: TokenKind::LParen, position: start });
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token { kind: TokenKind::RParen, position: start });
+            i += 1;
+        } else if c == '-' && chars.get(i + 1).is_some_and(|next| !next.is_whitespace() && *next != ')') {
+            tokens.push(Token { kind: TokenKind::Not, position: start });
+            i += 1;
+        } else if c == '"' {
+            let (phrase, next) = read_quoted(&chars, i)?;
+            tokens.push(Token { kind: TokenKind::Term(QueryTerm::Text(TextValue { value: phrase, phrase: true })), position: start });
+            i = next;
+        } else {
+            let mut word = String::new();
+            while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], '(' | ')' | '"') {
+                word.push(chars[i]);
+                i += 1;
+            }
+
+            let kind = match word.as_str() {
+                "AND" => TokenKind::And,
+                "OR" => TokenKind::Or,
+                "NOT" => TokenKind::Not,
+                _ => match word.split_once(':') {
+                    // Champ connu suivi d'une valeur ; sinon (`C:\Users`, `https://…`, `note:`) le mot
+                    // entier est un terme de recherche
+                    Some((field, rest)) if is_field_name(field) && (!rest.is_empty() || chars.get(i) == Some(&'"')) => {
+                        let value_position = start + field.chars().count() + 1;
+                        // Valeur entre guillemets : `name:"annual report"`
+                        let value = if rest.is_empty() && chars.get(i) == Some(&'"') {
+                            let (phrase, next) = read_quoted(&chars, i)?;
+                            i = next;
+                            TextValue { value: phrase, phrase: true }
+                        } else {
+                            TextValue { value: rest.to_string(), phrase: false }
+                        };
+                        TokenKind::Term(parse_field(field, value, start, value_position)?)
+                    }
+                    _ => TokenKind::Term(QueryTerm::Text(TextValue { value: word, phrase: false })),
+                },
+            };
+            tokens.push(Token { kind, position: start });
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn read_quoted(chars: &[char], start: usize) -> ParseResult<(String, usize)> {
+    let mut i = start + 1;
+    let mut value = String::new();
+    while i < chars.len() && chars[i] != '"' {
+        value.push(chars[i]);
+        i += 1;
+    }
+    if i >= chars.len() {
+        return error(start, "guillemet non fermé");
+    }
+    Ok((value, i + 1))
+}
+
+const FIELDS: &[&str] = &[
+    "name", "path", "in", "content", "ext", "type", "owner", "group", "encoding", "mime",
+    "size", "lines", "words", "modified", "created", "accessed", "is",
+];
+
+/// Seuls les champs connus sont interprétés : `12:30` ou `Re:` restent des termes de recherche
+fn is_field_name(field: &str) -> bool {
+    FIELDS.contains(&field.to_lowercase().as_str())
+}
+
+fn parse_field(field: &str, value: TextValue, position: usize, value_position: usize) -> ParseResult<QueryTerm> {
+    let keyword = |value: &TextValue| value.value.to_lowercase();
+
+    let term = match field.to_lowercase().as_str() {
+        "name" => QueryTerm::Name(value),
+        "path" | "in" => QueryTerm::Path(value),
+        "content" => QueryTerm::Content(value),
+        "ext" | "type" => QueryTerm::Extension(keyword(&value).trim_start_matches('.').to_string()),
+        "owner" => QueryTerm::Owner(value.value),
+        "group" => QueryTerm::Group(value.value),
+        "encoding" => QueryTerm::Encoding(value.value),
+        "mime" => QueryTerm::Mime(keyword(&value)),
+        "size" => QueryTerm::Size(parse_comparison(&value.value, value_position, parse_size)?),
+        "lines" => QueryTerm::Lines(parse_comparison(&value.value, value_position, parse_count)?),
+        "words" => QueryTerm::Words(parse_comparison(&value.value, value_position, parse_count)?),
+        "modified" => QueryTerm::Date(DateField::Modified, parse_comparison(&value.value, value_position, parse_date)?),
+        "created" => QueryTerm::Date(DateField::Created, parse_comparison(&value.value, value_position, parse_date)?),
+        "accessed" => QueryTerm::Date(DateField::Accessed, parse_comparison(&value.value, value_position, parse_date)?),
+        "is" => QueryTerm::Flag(match keyword(&value).as_str() {
+            "dir" | "folder" => FileFlag::Dir,
+            "file" => FileFlag::File,
+            "hidden" => FileFlag::Hidden,
+            "readonly" => FileFlag::Readonly,
+            "executable" => FileFlag::Executable,
+            "symlink" => FileFlag::Symlink,
+            "system" => FileFlag::System,
+            "encrypted" => FileFlag::Encrypted,
+            "indexed" => FileFlag::ContentIndexed,
+            other => return error(value_position, format!("propriété inconnue 'is:{}'", other)),
+        }),
+        _ => return error(position, format!("champ inconnu '{}'", field)),
+    };
+
+    Ok(term)
+}
+
+fn parse_comparison<T>(value: &str, position: usize, parse: fn(&str) -> Option<T>) -> ParseResult<Comparison<T>> {
+    let (op, operand) = if let Some(rest) = value.strip_prefix(">=") {
+        (CompareOp::Ge, rest)
+    } else if let Some(rest) = value.strip_prefix("<=") {
+        (CompareOp::Le, rest)
+    } else if let Some(rest) = value.strip_prefix('>') {
+        (CompareOp::Gt, rest)
+    } else if let Some(rest) = value.strip_prefix('<') {
+        (CompareOp::Lt, rest)
+    } else if let Some(rest) = value.strip_prefix('=') {
+        (CompareOp::Eq, rest)
+    } else {
+        (CompareOp::Eq, value)
+    };
+
+    let operand_position = position + (value.chars().count() - operand.chars().count());
+    match parse(operand) {
+        Some(value) => Ok(Comparison { op, value }),
+        None => error(operand_position, format!("valeur invalide '{}'", operand)),
+    }
+}
+
+/// `512`, `10KB`, `1.5MB`, `2G` (multiples de 1024, comme les filtres de taille)
+fn parse_size(value: &str) -> Option<u64> {
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier: u64 = match unit.to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "ko" => 1024,
+        "m" | "mb" | "mo" => 1024 * 1024,
+        "g" | "gb" | "go" => 1024 * 1024 * 1024,
+        "t" | "tb" | "to" => 1024 * 1024 * 1024 * 1024,
+        _ => return None,
+    };
+
+    Some((number * multiplier as f64).round() as u64)
+}
+
+fn parse_count(value: &str) -> Option<u64> {
+    value.parse().ok()
+}
+
+/// Âge relatif (`30min`, `12h`, `3d`, `2w`, `6mo`, `1y`) ou date `AAAA-MM-JJ`
+fn parse_date(value: &str) -> Option<DateValue> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0)?;
+        let timestamp = Local.from_local_datetime(&midnight).earliest()
+            .map(|dt| dt.timestamp())
+            .unwrap_or_else(|| midnight.and_utc().timestamp());
+        return Some(DateValue::Day(timestamp));
+    }
+
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().ok()?;
+
+    let seconds: u64 = match unit.to_lowercase().as_str() {
+        "s" => 1,
+        "min" => 60,
+        "h" => 3600,
+        "d" | "j" => 86_400,
+        "w" => 7 * 86_400,
+        "mo" => 30 * 86_400,
+        "y" => 365 * 86_400,
+        _ => return None,
+    };
+
+    Some(DateValue::Age(number.checked_mul(seconds)?))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+    /// Position de fin de la requête, pour les erreurs en fin de saisie
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn position(&self) -> usize {
+        self.peek().map(|token| token.position).unwrap_or(self.end)
+    }
+
+    fn parse_or(&mut self) -> ParseResult<QueryExpr> {
+        let mut items = vec![self.parse_and()?];
+        while self.peek().is_some_and(|token| token.kind == TokenKind::Or) {
+            self.next();
+            items.push(self.parse_and()?);
+        }
+        Ok(if items.len() == 1 { items.remove(0) } else { QueryExpr::Or(items) })
+    }
+
+    /// Termes juxtaposés : ET implicite
+    fn parse_and(&mut self) -> ParseResult<QueryExpr> {
+        let mut items = vec![self.parse_unary()?];
+        loop {
+            match self.peek().map(|token| &token.kind) {
+                Some(TokenKind::And) => {
+                    self.next();
+                    items.push(self.parse_unary()?);
+                }
+                Some(TokenKind::Or) | Some(TokenKind::RParen) | None => break,
+                Some(_) => items.push(self.parse_unary()?),
+            }
+        }
+        Ok(if items.len() == 1 { items.remove(0) } else { QueryExpr::And(items) })
+    }
+
+    fn parse_unary(&mut self) -> ParseResult<QueryExpr> {
+        if self.peek().is_some_and(|token| token.kind == TokenKind::Not) {
+            self.next();
+            return Ok(QueryExpr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> ParseResult<QueryExpr> {
+        let position = self.position();
+        match self.next() {
+            Some(Token { kind: TokenKind::Term(term), .. }) => Ok(QueryExpr::Term(term)),
+            Some(Token { kind: TokenKind::LParen, .. }) => {
+                if self.peek().is_some_and(|token| token.kind == TokenKind::RParen) {
+                    return error(position, "groupe vide");
+                }
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token { kind: TokenKind::RParen, .. }) => Ok(expr),
+                    _ => error(position, "parenthèse non fermée"),
+                }
+            }
+            Some(Token { kind: TokenKind::RParen, .. }) => error(position, "parenthèse fermante inattendue"),
+            Some(Token { kind: TokenKind::And | TokenKind::Or, .. }) => error(position, "opérateur sans terme à gauche"),
+            Some(Token { kind: TokenKind::Not, .. }) | None => error(position, "terme attendu"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> QueryExpr {
+        QueryExpr::Term(QueryTerm::Text(TextValue { value: value.to_string(), phrase: false }))
+    }
+
+    #[test]
+    fn test_parse_fields_negation_and_phrases() {
+        let expr = parse_query(r#"ext:pdf size:>5MB modified:<2w -draft "annual report""#).unwrap().unwrap();
+
+        assert_eq!(expr, QueryExpr::And(vec![
+            QueryExpr::Term(QueryTerm::Extension("pdf".to_string())),
+            QueryExpr::Term(QueryTerm::Size(Comparison { op: CompareOp::Gt, value: 5 * 1024 * 1024 })),
+            QueryExpr::Term(QueryTerm::Date(DateField::Modified, Comparison { op: CompareOp::Lt, value: DateValue::Age(14 * 86_400) })),
+            QueryExpr::Not(Box::new(text("draft"))),
+            QueryExpr::Term(QueryTerm::Text(TextValue { value: "annual report".to_string(), phrase: true })),
+        ]));
+    }
+
+    #[test]
+    fn test_parse_boolean_precedence_and_grouping() {
+        // ET lie plus fort que OU
+        let expr = parse_query("a b OR c").unwrap().unwrap();
+        assert_eq!(expr, QueryExpr::Or(vec![QueryExpr::And(vec![text("a"), text("b")]), text("c")]));
+
+        let expr = parse_query(r#"NOT (ext:jpg OR ext:png) AND name:"mon fichier" 12:30"#).unwrap().unwrap();
+        assert_eq!(expr, QueryExpr::And(vec![
+            QueryExpr::Not(Box::new(QueryExpr::Or(vec![
+                QueryExpr::Term(QueryTerm::Extension("jpg".to_string())),
+                QueryExpr::Term(QueryTerm::Extension("png".to_string())),
+            ]))),
+            QueryExpr::Term(QueryTerm::Name(TextValue { value: "mon fichier".to_string(), phrase: true })),
+            text("12:30"),
+        ]));
+
+        assert_eq!(parse_query("   ").unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_errors_report_position() {
+        let position = |input: &str| parse_query(input).unwrap_err().position;
+
+        assert_eq!(position(r#"rapport "annuel"#), 8);
+        assert_eq!(position("(a OR b"), 0);
+        assert_eq!(position("a OR"), 4);
+        assert_eq!(position("a ) b"), 2);
+        assert_eq!(position("size:>cinq"), 6);
+
+        let error: AppError = parse_query("is:rouge").unwrap_err().into();
+        assert!(matches!(error, AppError::Validation(message) if message.contains("position 3")));
+    }
+
+    #[test]
+    fn test_parse_unknown_fields_as_text() {
+        assert_eq!(parse_query(r"C:\Users\rapport").unwrap().unwrap(), text(r"C:\Users\rapport"));
+        assert_eq!(parse_query("https://example.com/facture").unwrap().unwrap(), text("https://example.com/facture"));
+        assert_eq!(parse_query("Re: budget").unwrap().unwrap(), QueryExpr::And(vec![text("Re:"), text("budget")]));
+        assert_eq!(parse_query("extt:pdf note:").unwrap().unwrap(), QueryExpr::And(vec![text("extt:pdf"), text("note:")]));
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Serialize, Deserialize};
+
+/// Clé des paramètres sous laquelle les poids du classement sont conservés
+pub const RANKING_WEIGHTS: &str = "ranking_weights";
+
+/// Poids des signaux du tri par pertinence ; chaque signal est ramené entre 0 et 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingWeights {
+    /// Score BM25 du contenu
+    pub content: f64,
+    /// Qualité de la correspondance du nom (exacte, préfixe, début de mot...)
+    pub name: f64,
+    /// Fraîcheur de la dernière modification
+    pub recency: f64,
+    /// Fréquence et fraîcheur des ouvertures depuis l'application
+    pub frecency: f64,
+    /// Âge (en jours) auquel le signal de fraîcheur est divisé par deux
+    pub recency_half_life_days: f64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            content: 1.0,
+            name: 1.0,
+            recency: 0.2,
+            frecency: 0.3,
+            recency_half_life_days: 30.0,
+        }
+    }
+}
+
+impl RankingWeights {
+    /// Poids enregistrés dans les paramètres, ou poids par défaut s'ils sont absents ou invalides
+    pub fn from_setting(value: Option<&str>) -> Self {
+        value.and_then(|value| serde_json::from_str::<Self>(value).ok())
+            .filter(|weights| weights.validate().is_ok())
+            .unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let weights = [self.content, self.name, self.recency, self.frecency];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err("Les poids doivent être positifs ou nuls".to_string());
+        }
+        if weights.iter().all(|w| *w == 0.0) {
+            return Err("Au moins un poids doit être non nul".to_string());
+        }
+        if !self.recency_half_life_days.is_finite() || self.recency_half_life_days <= 0.0 {
+            return Err("La demi-vie de fraîcheur doit être positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Signaux disponibles pour une requête donnée
+pub struct RankingSignals<'a> {
+    /// Expression SQL du score de nom déjà normalisé, si la requête a des termes de nom
+    pub name_score: Option<&'a str>,
+    /// Requête plein texte : `bm25(fts_content)` est disponible
+    pub has_content: bool,
+}
+
+/// Expression SQL du score de pertinence (plus grand = plus pertinent). Les deux paramètres `?`
+/// attendent l'horodatage courant en secondes. Les poids sont des nombres, insérés tels quels.
+pub fn relevance_sql(weights: &RankingWeights, signals: &RankingSignals) -> String {
+    const DAY: f64 = 86_400.0;
+    // Décroissance hyperbolique : 1 aujourd'hui, 1/2 à la demi-vie
+    let recency = format!(
+        "1.0 / (1.0 + MAX(0, ? - files.last_modified) / {:.1})",
+        weights.recency_half_life_days * DAY
+    );
+    // Ouvertures fréquentes et récentes (demi-vie d'une semaine)
+    let frecency = format!(
+        "(files.open_count / (files.open_count + 1.0)) / (1.0 + MAX(0, ? - COALESCE(files.last_opened, 0)) / {:.1})",
+        7.0 * DAY
+    );
+
+    let mut terms = vec![
+        format!("{:?} * {}", weights.recency, recency),
+        format!("{:?} * {}", weights.frecency, frecency),
+    ];
+    if let Some(name_score) = signals.name_score {
+        terms.push(format!("{:?} * {}", weights.name, name_score));
+    }
+    if signals.has_content {
+        // bm25 est négatif, d'autant plus que la correspondance est bonne
+        terms.push(format!("{:?} * (-bm25(fts_content) / (1.0 - bm25(fts_content)))", weights.content));
+    }
+    format!("({})", terms.join(" + "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_meaningless_weights() {
+        assert!(RankingWeights::default().validate().is_ok());
+        assert!(RankingWeights { content: -1.0, ..Default::default() }.validate().is_err());
+        assert!(RankingWeights { name: f64::NAN, ..Default::default() }.validate().is_err());
+        assert!(RankingWeights { content: 0.0, name: 0.0, recency: 0.0, frecency: 0.0, ..Default::default() }.validate().is_err());
+        assert!(RankingWeights { recency_half_life_days: 0.0, ..Default::default() }.validate().is_err());
+    }
+}
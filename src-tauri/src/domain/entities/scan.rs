@@ -20,10 +20,29 @@ pub struct InsertProgress {
     pub total: usize,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Default)]
 pub struct ScanFinished {
     pub total: usize,
     pub message: String,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanMode {
+    /// Insère uniquement les chemins absents de la base (nouveaux dossiers)
+    Insert,
+    /// Compare l'arborescence à la base et applique ajouts, mises à jour et suppressions
+    Reconcile,
+}
+
+/// Empreinte d'un fichier indexé, utilisée pour détecter les changements lors d'une resynchronisation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSignature {
+    pub path: String,
+    pub size: Option<u64>,
+    pub last_modified: i64,
 }
 
 #[derive(Serialize, Clone)]
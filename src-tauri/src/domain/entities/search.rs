@@ -1,10 +1,13 @@
 use serde::{Serialize, Deserialize};
+use crate::domain::entities::file::File;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchQuery {
     pub text: String,
     pub filters: SearchFilters,
-    pub sort_by: SortBy,
+    /// Tri demandé ; à défaut, voir `SearchQuery::sort`
+    #[serde(default)]
+    pub sort_by: Option<SortBy>,
     pub sort_order: SortOrder,
     pub limit: u32,
     pub offset: u32,
@@ -18,7 +21,7 @@ impl Default for SearchQuery {
         Self {
             text: String::new(),
             filters: SearchFilters::default(),
-            sort_by: SortBy::Name,
+            sort_by: None,
             sort_order: SortOrder::Asc,
             limit: 10,
             offset: 0,
@@ -29,6 +32,18 @@ impl Default for SearchQuery {
     }
 }
 
+impl SearchQuery {
+    /// Tri effectif : celui demandé, sinon la pertinence pour une recherche textuelle
+    /// et le nom pour un simple listage
+    pub fn sort(&self) -> SortBy {
+        match &self.sort_by {
+            Some(sort_by) => sort_by.clone(),
+            None if self.text.trim().is_empty() => SortBy::Name,
+            None => SortBy::Relevance,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchFilters {
     pub is_dir: bool,
@@ -38,6 +53,24 @@ pub struct SearchFilters {
     pub date_range: [u64; 2],
     pub date_mode: DateMode,
     pub search_in_content: bool,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Nombre de lignes [min, max], 0 = pas de borne
+    #[serde(default)]
+    pub line_count: [u32; 2],
+    /// Nombre de mots [min, max], 0 = pas de borne
+    #[serde(default)]
+    pub word_count: [u32; 2],
+    /// Expéditeur des courriers (nom ou adresse), recherché par sous-chaîne
+    #[serde(default)]
+    pub sender: Option<String>,
+    /// Objet des courriers, recherché par sous-chaîne
+    #[serde(default)]
+    pub subject: Option<String>,
 }
 
 impl Default for SearchFilters {
@@ -50,6 +83,13 @@ impl Default for SearchFilters {
             date_range: [0, 0],
             date_mode: DateMode::Create,
             search_in_content: false,
+            owner: None,
+            group: None,
+            encoding: None,
+            line_count: [0, 0],
+            word_count: [0, 0],
+            sender: None,
+            subject: None,
         }
     }
 }
@@ -61,6 +101,10 @@ pub enum SortBy {
     LastModified,
     CreatedAt,
     AccessedAt,
+    /// Pertinence (voir `RankingWeights`) : contenu, qualité de la correspondance du nom, fraîcheur
+    /// et ouvertures ; faute de correspondance exacte, le nom est cherché approximativement.
+    /// Départage par nom, seul à s'appliquer sans terme de recherche ; le sens de tri ne porte que sur lui.
+    Relevance,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,4 +117,13 @@ pub enum SortOrder {
 pub enum DateMode {
     Create,
     Modify,
-}
\ No newline at end of file
+}
+/// Résultat de recherche enrichi : score BM25 et extraits surlignés du contenu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub file: File,
+    /// Score BM25 (plus petit = plus pertinent), absent hors recherche plein texte
+    pub score: Option<f64>,
+    /// Extraits HTML échappés où les correspondances sont entourées de `<mark>` / `</mark>`
+    pub snippets: Vec<String>,
+}
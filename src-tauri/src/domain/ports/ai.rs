@@ -1,10 +1,22 @@
 use crate::domain::entities::ai::{AiRequest, AiResponse};
 use async_trait::async_trait;
+use tokio::sync::mpsc;
 use crate::shared::errors::AppResult;
 
 #[async_trait]
 pub trait Ai: Send + Sync {
     async fn generate(&self, request: AiRequest) -> AppResult<AiResponse>;
+    /// Comme `generate`, en envoyant chaque fragment de la réponse dans `tokens` dès sa réception.
+    /// Un récepteur fermé interrompt la génération. Par défaut, la réponse complète en un fragment.
+    async fn generate_stream(&self, request: AiRequest, tokens: mpsc::Sender<String>) -> AppResult<AiResponse> {
+        let response = self.generate(request).await?;
+        let _ = tokens.send(response.content.clone()).await;
+        Ok(response)
+    }
+    /// Le fournisseur sait contraindre la réponse à `AiRequest::response_schema`
+    fn supports_json_schema(&self) -> bool {
+        false
+    }
     async fn list_models(&self) -> AppResult<Vec<String>>;
     async fn health_check(&self) -> AppResult<bool>;
-}
\ No newline at end of file
+}
@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+use crate::shared::errors::AppResult;
+
+/// Calcul de vecteurs sémantiques (embeddings) pour des textes
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Un vecteur par texte, dans l'ordre des entrées
+    async fn embed(&self, inputs: Vec<String>) -> AppResult<Vec<Vec<f32>>>;
+    /// Modèle utilisé : des vecteurs de modèles différents ne sont pas comparables
+    fn model(&self) -> &str;
+}
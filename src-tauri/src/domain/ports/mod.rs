@@ -1,3 +1,4 @@
 pub mod repository;
 pub mod reader;
-pub mod ai;
\ No newline at end of file
+pub mod ai;
+pub mod embedder;
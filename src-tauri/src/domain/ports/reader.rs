@@ -1,6 +1,12 @@
+use crate::domain::entities::content::ExtractedContent;
 use crate::domain::entities::file::File;
 use crate::shared::errors::AppResult;
 
 pub trait Reader {
     fn read(&self, file: &File) -> AppResult<String>;
-}
\ No newline at end of file
+
+    /// Texte et métadonnées (encodage, nombre de lignes et de mots) pour l'indexation du contenu
+    fn extract(&self, file: &File) -> AppResult<ExtractedContent> {
+        self.read(file).map(ExtractedContent::from_text)
+    }
+}
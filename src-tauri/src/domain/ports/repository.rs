@@ -1,19 +1,45 @@
+use crate::domain::entities::content::ExtractedContent;
 use crate::domain::entities::file::File;
 use crate::domain::entities::stat::Stat;
-use crate::domain::entities::search::SearchQuery;
+use crate::domain::entities::search::{SearchHit, SearchQuery};
+use crate::domain::entities::scan::FileSignature;
+use crate::domain::entities::path_rule::IndexingRules;
+use crate::domain::entities::duplicate::{ChecksumCandidate, DuplicateQuery};
+use crate::domain::entities::embedding::{ChunkEmbedding, PendingEmbedding, SemanticHit};
 use crate::shared::errors::AppResult;
 
 pub trait FileRepository {
     fn new(path: &str) -> AppResult<Self> where Self: Sized;
     fn init(&self) -> AppResult<()>;
     fn insert(&mut self, files: Vec<File>) -> AppResult<()>;
+    fn upsert(&mut self, files: Vec<File>) -> AppResult<()>;
+    fn delete_files(&mut self, paths: &[String]) -> AppResult<usize>;
     fn insert_paths(&mut self, paths: Vec<String>) -> AppResult<Vec<String>>;
     fn get_stat(&self) -> AppResult<Stat>;
     fn get_all_types(&self) -> AppResult<Vec<String>>;
     fn get_all_paths(&self) -> AppResult<Vec<String>>;
     fn get_all_folders(&self) -> AppResult<Vec<String>>;
     fn search(&self, query: &SearchQuery) -> AppResult<Vec<File>>;
+    fn search_hits(&self, query: &SearchQuery) -> AppResult<Vec<SearchHit>>;
     fn reset_data(&self) -> AppResult<()>;
-    fn update_file_index_status(&mut self, file: &File, content_hash: String, is_indexable: bool) -> AppResult<()>;
+    fn update_file_index_status(&mut self, file: &File, content: ExtractedContent, is_indexable: bool) -> AppResult<()>;
     fn get_uncontent_indexed_files(&self) -> AppResult<Vec<File>>;
+    fn get_file_signatures(&self, root: &str) -> AppResult<Vec<FileSignature>>;
+    fn get_indexing_rules(&self) -> AppResult<IndexingRules>;
+    fn save_indexing_rules(&mut self, rules: &IndexingRules) -> AppResult<()>;
+    fn get_duplicate_candidates(&self, query: &DuplicateQuery) -> AppResult<Vec<ChecksumCandidate>>;
+    fn update_checksums(&mut self, checksums: &[(String, String)]) -> AppResult<usize>;
+    /// Contenus indexés sans embeddings pour `model`, par id croissant à partir de `after_id` exclu
+    fn get_pending_embeddings(&self, model: &str, after_id: i64, limit: u32) -> AppResult<Vec<PendingEmbedding>>;
+    /// Remplace les embeddings du fichier
+    fn save_embeddings(&mut self, file_id: i64, model: &str, embeddings: &[ChunkEmbedding]) -> AppResult<()>;
+    /// Plus proches voisins de `vector` (normalisé), un résultat par fichier
+    fn semantic_search(&self, model: &str, vector: &[f32], limit: u32) -> AppResult<Vec<SemanticHit>>;
+    /// Contenu indexé du fichier, s'il en a un (marques de diapositive rendues en clair)
+    fn get_content(&self, path: &str) -> AppResult<Option<String>>;
+    /// Ouverture d'un fichier depuis l'application (signal de fréquence du classement)
+    fn record_open(&mut self, path: &str) -> AppResult<()>;
+    fn get_setting(&self, key: &str) -> AppResult<Option<String>>;
+    /// `None` supprime la clé
+    fn set_setting(&mut self, key: &str, value: Option<&str>) -> AppResult<()>;
 }
\ No newline at end of file
@@ -1,9 +1,17 @@
 use std::sync::Arc;
-use crate::domain::entities::ai::{AiError, AiRequest};
+use tokio::sync::mpsc;
+use crate::domain::entities::ai::{AiError, AiRequest, AiStreamEvent};
+use crate::domain::entities::ai_query::{normalize_search_query, parse_ai_search_query, search_query_schema};
 use crate::domain::entities::search::SearchQuery;
 use crate::domain::ports::ai::Ai;
 use crate::shared::errors::AppResult;
 
+const SEARCH_SYSTEM_PROMPT: &str = include_str!("../../../data/prompt.txt");
+// Essais de génération, réparations comprises
+const MAX_ATTEMPTS: usize = 3;
+// Fragments de la réponse en attente de transmission
+const TOKEN_BUFFER: usize = 64;
+
 pub struct AiService {
     ai_port: Arc<dyn Ai>,
 }
@@ -14,38 +22,50 @@ impl AiService {
         Self { ai_port }
     }
 
-    pub async fn generate(&self, prompt: &str) -> AppResult<SearchQuery> {
-        let request = AiRequest {
+    /// Convertit la requête en `SearchQuery`, en envoyant les fragments de la réponse dans `events`
+    /// au fil de l'eau. Une réponse illisible est renvoyée au modèle avec l'erreur pour qu'il la
+    /// corrige, jusqu'à `MAX_ATTEMPTS` essais, chacun précédé de `AiStreamEvent::Reset` ;
+    /// `known_types` sert à normaliser les extensions.
+    pub async fn generate_stream(&self, prompt: &str, known_types: &[String], events: mpsc::Sender<AiStreamEvent>) -> AppResult<SearchQuery> {
+        let mut request = self.search_request(prompt);
+        let mut last_error = String::new();
+        for attempt in 1..=MAX_ATTEMPTS {
+            if attempt > 1 {
+                let _ = events.send(AiStreamEvent::Reset).await;
+            }
+
+            // Un récepteur fermé interrompt la génération en cours
+            let (tokens, mut received) = mpsc::channel::<String>(TOKEN_BUFFER);
+            let forward = async {
+                while let Some(token) = received.recv().await {
+                    if events.send(AiStreamEvent::Token(token)).await.is_err() {
+                        break;
+                    }
+                }
+            };
+            let (response, _) = tokio::join!(self.ai_port.generate_stream(request.clone(), tokens), forward);
+            let response = response?;
+            match parse_ai_search_query(&response.content) {
+                Ok(query) => return Ok(normalize_search_query(query, known_types)),
+                Err(error) => {
+                    tracing::warn!("Réponse du modèle invalide (essai {}/{}): {}", attempt, MAX_ATTEMPTS, error);
+                    request.prompt = repair_prompt(prompt, &response.content, &error);
+                    last_error = error;
+                }
+            }
+        }
+        Err(AiError::ParsingError(format!("Invalid SearchQuery after {} attempts: {}", MAX_ATTEMPTS, last_error)).into())
+    }
+
+    fn search_request(&self, prompt: &str) -> AiRequest {
+        AiRequest {
             prompt: prompt.to_string(),
+            system_prompt: Some(SEARCH_SYSTEM_PROMPT.to_string()),
             model: None,
-            temperature: Some(0.7),
+            temperature: Some(0.2),
             max_tokens: Some(500),
-        };
-
-        let response = self.ai_port.generate(request).await?;
-
-        println!("AI Response content: {}", response.content);
-
-        let cleaned_content = response.content
-            .trim()
-            .replace("\\{", "{")
-            .replace("\\}", "}")
-            .replace("\\\"", "\"")
-            .replace("\\n", "")
-            .replace("\\t", "");
-
-        println!("Cleaned content: {}", cleaned_content);
-
-        let search_query = serde_json::from_str::<SearchQuery>(&cleaned_content)
-            .map_err(|e| {
-                println!("JSON parsing error: {}", e);
-                println!("Failed to parse content: {}", cleaned_content);
-                AiError::ParsingError(format!("Failed to parse AI response as SearchQuery: {}", e))
-            })?;
-
-        println!("Parsed search_query: {:?}", search_query);
-
-        Ok(search_query)
+            response_schema: self.ai_port.supports_json_schema().then(search_query_schema),
+        }
     }
 
     pub async fn list_models(&self) -> AppResult<Vec<String>> {
@@ -63,4 +83,47 @@ impl AiService {
         };
         Ok(available_models.iter().any(|m| m == model))
     }
-}
\ No newline at end of file
+}
+
+fn repair_prompt(prompt: &str, previous: &str, error: &str) -> String {
+    format!(
+        "{}\n\nTa réponse précédente :\n{}\n\nElle est invalide : {}\nCorrige-la et réponds uniquement avec l'objet JSON.",
+        prompt, previous, error
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::search::SortBy;
+    use crate::domain::services::ask_service::tests::FakeAi;
+
+    #[tokio::test]
+    async fn test_invalid_answer_is_repaired() {
+        let ai = Arc::new(FakeAi::scripted(&[
+            r#"{"text": "budget", "sort_by": "Biggest"}"#,
+            "```json\n{\"text\": \"budget\", \"filters\": {\"file_types\": [\"XLSX\"]}, \"sort_by\": \"Size\"}\n```",
+        ]));
+        let service = AiService::new(ai.clone());
+        let (events, mut received) = mpsc::channel::<AiStreamEvent>(16);
+
+        let query = service.generate_stream("gros budgets", &["xlsx".to_string()], events).await.unwrap();
+        assert_eq!(query.text, "budget");
+        assert_eq!(query.filters.file_types, vec!["xlsx"]);
+        assert!(matches!(query.sort_by, Some(SortBy::Size)));
+
+        // La réponse rejetée est annulée avant le second essai
+        let mut streamed = Vec::new();
+        while let Ok(event) = received.try_recv() {
+            streamed.push(event);
+        }
+        assert_eq!(streamed.len(), 3);
+        assert_eq!(streamed[1], AiStreamEvent::Reset);
+
+        let requests = ai.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].response_schema.is_none());
+        assert!(requests[1].prompt.starts_with("gros budgets"));
+        assert!(requests[1].prompt.contains("Biggest"), "{}", requests[1].prompt);
+    }
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use crate::domain::entities::ai::AiRequest;
+use crate::domain::entities::ask::AskSource;
+use crate::domain::ports::ai::Ai;
+use crate::shared::errors::{AppError, AppResult};
+
+const ASK_SYSTEM_PROMPT: &str = include_str!("../../../data/ask_prompt.txt");
+const ANSWER_MAX_TOKENS: u32 = 800;
+// Fragments de la réponse en attente de `on_chunk`
+const TOKEN_BUFFER: usize = 64;
+
+/// Réponses aux questions sur le contenu des fichiers, à partir des passages retrouvés
+pub struct AskService {
+    ai_port: Arc<dyn Ai>,
+}
+
+impl AskService {
+    pub fn new(ai_port: Arc<dyn Ai>) -> Self {
+        Self { ai_port }
+    }
+
+    /// Demande au modèle une réponse citant les passages par leur numéro. `on_chunk` reçoit le
+    /// texte de la réponse au fur et à mesure.
+    pub async fn answer<F>(&self, question: &str, sources: &[AskSource], mut on_chunk: F) -> AppResult<String>
+    where F: FnMut(&str) + Send
+    {
+        if question.trim().is_empty() {
+            return Err(AppError::Validation("Question vide".to_string()));
+        }
+
+        let request = AiRequest {
+            prompt: build_prompt(question.trim(), sources),
+            system_prompt: Some(ASK_SYSTEM_PROMPT.to_string()),
+            model: None,
+            temperature: Some(0.2),
+            max_tokens: Some(ANSWER_MAX_TOKENS),
+            response_schema: None,
+        };
+
+        let (tokens, mut received) = mpsc::channel::<String>(TOKEN_BUFFER);
+        let forward = async {
+            while let Some(token) = received.recv().await {
+                on_chunk(&token);
+            }
+        };
+        let (response, _) = tokio::join!(self.ai_port.generate_stream(request, tokens), forward);
+        Ok(response?.content)
+    }
+}
+
+fn build_prompt(question: &str, sources: &[AskSource]) -> String {
+    let mut prompt = String::from("Passages :\n\n");
+    for source in sources {
+        prompt.push_str(&format!("[{}] {}\n{}\n\n", source.index, source.path, source.passage));
+    }
+    prompt.push_str(&format!("Question : {}", question));
+    prompt
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+    use async_trait::async_trait;
+    use crate::domain::entities::ai::AiResponse;
+
+    /// Modèle factice : retourne les réponses prévues dans l'ordre (la dernière ensuite) et garde
+    /// les requêtes reçues
+    pub(crate) struct FakeAi {
+        pub answers: Mutex<VecDeque<String>>,
+        pub requests: Mutex<Vec<AiRequest>>,
+    }
+
+    impl FakeAi {
+        pub(crate) fn new(answer: &str) -> Self {
+            Self::scripted(&[answer])
+        }
+
+        pub(crate) fn scripted(answers: &[&str]) -> Self {
+            Self {
+                answers: Mutex::new(answers.iter().map(|answer| answer.to_string()).collect()),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Ai for FakeAi {
+        async fn generate(&self, request: AiRequest) -> AppResult<AiResponse> {
+            self.requests.lock().unwrap().push(request);
+            let mut answers = self.answers.lock().unwrap();
+            let content = if answers.len() > 1 { answers.pop_front() } else { answers.front().cloned() };
+            Ok(AiResponse { content: content.unwrap_or_default(), model_used: "fake".to_string(), tokens_used: None })
+        }
+
+        async fn list_models(&self) -> AppResult<Vec<String>> {
+            Ok(vec!["fake".to_string()])
+        }
+
+        async fn health_check(&self) -> AppResult<bool> {
+            Ok(true)
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::domain::entities::content::{ExtractedContent, ReaderSettings};
 use crate::domain::entities::file::File;
 use crate::domain::services::reader_service::ReaderService;
 use crate::shared::errors::{AppError, AppResult};
@@ -15,12 +16,18 @@ impl ContentIndexerService {
         }
     }
 
-    pub fn index_file_content(&mut self, file: &File) -> AppResult<String> {
+    pub fn with_settings(settings: ReaderSettings) -> Self {
+        Self {
+            reader_service: ReaderService::with_settings(settings),
+        }
+    }
+
+    pub fn index_file_content(&mut self, file: &File) -> AppResult<ExtractedContent> {
         if !ReaderService::can_read_file(&file) {
             return Err(AppError::NotFound("Impossible de lire le fichier".to_string()));
         }
 
-        self.reader_service.read(file)
+        self.reader_service.extract(file)
     }
 
     pub fn can_index_file(&self, file: &File) -> bool {
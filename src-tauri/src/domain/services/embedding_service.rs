@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use crate::domain::entities::embedding::{chunk_text, normalize, ChunkEmbedding};
+use crate::domain::ports::embedder::Embedder;
+use crate::shared::errors::{AppError, AppResult};
+
+// Passages d'environ 250 mots, assez courts pour la fenêtre des modèles d'embeddings courants
+const CHUNK_SIZE: usize = 1200;
+const CHUNK_OVERLAP: usize = 200;
+// Au-delà, la fin des très longs documents n'est pas vectorisée
+const MAX_CHUNKS_PER_FILE: usize = 64;
+// Passages envoyés par requête
+const EMBEDDING_BATCH_SIZE: usize = 16;
+
+pub struct EmbeddingService {
+    embedder: Arc<dyn Embedder>,
+}
+
+impl EmbeddingService {
+    pub fn new(embedder: Arc<dyn Embedder>) -> Self {
+        Self { embedder }
+    }
+
+    pub fn model(&self) -> &str {
+        self.embedder.model()
+    }
+
+    /// Découpe le contenu extrait en passages et calcule leurs vecteurs normalisés
+    pub async fn embed_content(&self, content: &str) -> AppResult<Vec<ChunkEmbedding>> {
+        let chunks: Vec<String> = chunk_text(content, CHUNK_SIZE, CHUNK_OVERLAP)
+            .into_iter()
+            .take(MAX_CHUNKS_PER_FILE)
+            .collect();
+
+        let mut embeddings = Vec::with_capacity(chunks.len());
+        for batch in chunks.chunks(EMBEDDING_BATCH_SIZE) {
+            let vectors = self.embedder.embed(batch.to_vec()).await?;
+            for (chunk, vector) in batch.iter().zip(vectors) {
+                embeddings.push(ChunkEmbedding {
+                    chunk_index: embeddings.len() as u32,
+                    content: chunk.clone(),
+                    vector: normalize(vector),
+                });
+            }
+        }
+        Ok(embeddings)
+    }
+
+    pub async fn embed_query(&self, query: &str) -> AppResult<Vec<f32>> {
+        if query.trim().is_empty() {
+            return Err(AppError::Validation("Requête vide".to_string()));
+        }
+
+        let vector = self.embedder.embed(vec![query.trim().to_string()]).await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::Internal("Aucun vecteur retourné pour la requête".to_string()))?;
+        Ok(normalize(vector))
+    }
+}
@@ -1,7 +1,12 @@
 use crate::domain::ports::repository::FileRepository;
+use crate::domain::entities::content::ExtractedContent;
 use crate::domain::entities::file::File;
 use crate::domain::entities::stat::Stat;
-use crate::domain::entities::search::SearchQuery;
+use crate::domain::entities::search::{SearchHit, SearchQuery};
+use crate::domain::entities::scan::FileSignature;
+use crate::domain::entities::path_rule::IndexingRules;
+use crate::domain::entities::duplicate::{ChecksumCandidate, DuplicateQuery};
+use crate::domain::entities::embedding::{ChunkEmbedding, PendingEmbedding, SemanticHit};
 use crate::shared::errors::{AppError, AppResult};
 
 pub struct FileService<T: FileRepository> {
@@ -22,6 +27,11 @@ impl<T: FileRepository> FileService<T> {
         self.repository.search(query)
     }
 
+    pub fn search_hits(&self, query: &SearchQuery) -> AppResult<Vec<SearchHit>> {
+        Self::validate_search_query(query)?;
+        self.repository.search_hits(query)
+    }
+
     pub fn get_stat(&self) -> AppResult<Stat> {
         self.repository.get_stat()
     }
@@ -44,6 +54,23 @@ impl<T: FileRepository> FileService<T> {
         self.repository.insert(files)
     }
 
+    pub fn upsert(&mut self, files: Vec<File>) -> AppResult<()> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        if files.len() > 10000 {
+            return Err(AppError::Validation("Too many files in single batch".to_string()));
+        }
+        self.repository.upsert(files)
+    }
+
+    pub fn delete_files(&mut self, paths: &[String]) -> AppResult<usize> {
+        if paths.is_empty() {
+            return Ok(0);
+        }
+        self.repository.delete_files(paths)
+    }
+
     pub fn reset_data(&self) -> AppResult<()> {
         self.repository.reset_data()
     }
@@ -61,17 +88,74 @@ impl<T: FileRepository> FileService<T> {
         self.repository.get_uncontent_indexed_files()
     }
 
+    pub fn get_file_signatures(&self, root: &str) -> AppResult<Vec<FileSignature>> {
+        self.repository.get_file_signatures(root)
+    }
+
+    pub fn get_indexing_rules(&self) -> AppResult<IndexingRules> {
+        self.repository.get_indexing_rules()
+    }
+
+    pub fn save_indexing_rules(&mut self, rules: &IndexingRules) -> AppResult<()> {
+        if rules.rules.iter().any(|rule| rule.pattern.trim().is_empty()) {
+            return Err(AppError::Validation("Rule pattern cannot be empty".to_string()));
+        }
+        self.repository.save_indexing_rules(rules)
+    }
+
+    pub fn get_duplicate_candidates(&self, query: &DuplicateQuery) -> AppResult<Vec<ChecksumCandidate>> {
+        self.repository.get_duplicate_candidates(query)
+    }
+
+    pub fn update_checksums(&mut self, checksums: &[(String, String)]) -> AppResult<usize> {
+        self.repository.update_checksums(checksums)
+    }
+
+    pub fn get_pending_embeddings(&self, model: &str, after_id: i64, limit: u32) -> AppResult<Vec<PendingEmbedding>> {
+        self.repository.get_pending_embeddings(model, after_id, limit)
+    }
+
+    pub fn save_embeddings(&mut self, file_id: i64, model: &str, embeddings: &[ChunkEmbedding]) -> AppResult<()> {
+        self.repository.save_embeddings(file_id, model, embeddings)
+    }
+
+    pub fn semantic_search(&self, model: &str, vector: &[f32], limit: u32) -> AppResult<Vec<SemanticHit>> {
+        if limit == 0 {
+            return Err(AppError::Validation("Limit cannot be zero".to_string()));
+        }
+        if limit > 1000 {
+            return Err(AppError::Validation("Limit too high (max: 1000)".to_string()));
+        }
+        self.repository.semantic_search(model, vector, limit)
+    }
+
+    pub fn get_content(&self, path: &str) -> AppResult<Option<String>> {
+        self.repository.get_content(path)
+    }
+
+    pub fn record_open(&mut self, path: &str) -> AppResult<()> {
+        self.repository.record_open(path)
+    }
+
+    pub fn get_setting(&self, key: &str) -> AppResult<Option<String>> {
+        self.repository.get_setting(key)
+    }
+
+    pub fn set_setting(&mut self, key: &str, value: Option<&str>) -> AppResult<()> {
+        self.repository.set_setting(key, value)
+    }
+
     pub fn update_file_index_status(
         &mut self,
         file: &File,
-        content_hash: String,
+        content: ExtractedContent,
         is_indexable: bool
     ) -> AppResult<()> {
         if !file.path.exists() {
             return Err(AppError::NotFound(format!("File not found: {}", file.path.display())));
         }
 
-        self.repository.update_file_index_status(file, content_hash, is_indexable)
+        self.repository.update_file_index_status(file, content, is_indexable)
     }
 
     fn validate_search_query(query: &SearchQuery) -> AppResult<()> {
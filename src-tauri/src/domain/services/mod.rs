@@ -1,4 +1,6 @@
 pub mod file_service;
 pub mod reader_service;
 pub mod content_indexer_service;
-pub mod ai_service;
\ No newline at end of file
+pub mod ai_service;
+pub mod embedding_service;
+pub mod ask_service;
@@ -1,26 +1,116 @@
 use crate::domain::ports::reader::Reader;
-use crate::infrastructure::readers::{TextReader, CodeReader, CsvReader, PdfReader, WordReader};
+use crate::infrastructure::readers::{
+    TextReader, CodeReader, CsvReader, EpubReader, HtmlReader, MailReader, PdfReader, PresentationReader, RtfReader,
+    SpreadsheetReader, WordReader,
+};
+use crate::infrastructure::readers::archive::{for_each_member, ArchiveKind, ScratchDir, SizeBudget};
+use crate::domain::entities::content::{ExtractedContent, ExtractedEntry, ReaderSettings, ENTRY_SEPARATOR};
 use crate::domain::entities::file::File;
+use std::fs;
+use std::io;
 use std::path::Path;
 use crate::shared::errors::AppResult;
 
 pub struct ReaderService {
     reader: Box<dyn Reader>,
+    settings: ReaderSettings,
 }
 
 impl ReaderService {
     pub fn new() -> Self {
+        Self::with_settings(ReaderSettings::default())
+    }
+
+    pub fn with_settings(settings: ReaderSettings) -> Self {
         Self {
-            reader: Box::new(TextReader::new())
+            reader: Box::new(TextReader::new()),
+            settings,
         }
     }
 
-    pub fn read(&mut self, file: &File) -> AppResult<String> {
-        self.reader = Self::get_reader_for_file(&file);
-        self.reader.read(&file)
+    pub fn extract(&mut self, file: &File) -> AppResult<ExtractedContent> {
+        if let Some(kind) = ArchiveKind::detect(&file.path) {
+            let budget = SizeBudget::new(self.settings.archive_max_size_mb.saturating_mul(1024 * 1024));
+            let entries = self.archive_entries(file, kind, &budget, 1)?;
+            return Ok(ExtractedContent { entries, ..ExtractedContent::from_text(String::new()) });
+        }
+
+        self.reader = self.get_reader_for_file(file);
+        self.reader.extract(file)
+    }
+
+    /// Fichiers d'une archive, décompressés un à un dans un dossier temporaire pour passer par les lecteurs habituels.
+    /// Les éléments des archives imbriquées (jusqu'à `archive_max_depth`) et des boîtes mbox sont mis à plat.
+    fn archive_entries(&self, file: &File, kind: ArchiveKind, budget: &SizeBudget, depth: usize) -> AppResult<Vec<ExtractedEntry>> {
+        let scratch = ScratchDir::new()?;
+        let mut entries = Vec::new();
+
+        let walk = for_each_member(&file.path, kind, budget, |member, data| {
+            let extension = Path::new(member.name()).extension().map(|ext| ext.to_string_lossy().to_lowercase());
+            let member_file = File {
+                path: scratch.path().join(member.name()),
+                name: member.name().to_string(),
+                file_type: extension.clone(),
+                size: Some(member.size),
+                ..file.clone()
+            };
+            let nested = ArchiveKind::detect(&member_file.path);
+            let wanted = match nested {
+                Some(_) => depth < self.settings.archive_max_depth,
+                None => Self::can_read_file(&member_file),
+            };
+
+            let mut content = ExtractedContent::default();
+            if let (true, Some(data)) = (wanted, data) {
+                let written = fs::File::create(&member_file.path).and_then(|mut target| io::copy(data, &mut target));
+                match written {
+                    Ok(_) => {
+                        let extraction = match nested {
+                            Some(kind) => self.archive_entries(&member_file, kind, budget, depth + 1)
+                                .map(|entries| ExtractedContent { entries, ..ExtractedContent::default() }),
+                            None => self.get_reader_for_file(&member_file).extract(&member_file),
+                        };
+                        match extraction {
+                            Ok(extracted) => content = extracted,
+                            Err(e) => tracing::debug!("Contenu ignoré pour {} dans {}: {}", member.path, file.path.display(), e),
+                        }
+                    }
+                    // Limite atteinte : le parcours s'arrête, les éléments déjà lus sont gardés
+                    Err(e) if budget.is_exhausted() => return Err(e.into()),
+                    // Disque plein, nom invalide... : seul cet élément est ignoré
+                    Err(e) => tracing::warn!("Élément non extrait {} de {}: {}", member.path, file.path.display(), e),
+                }
+                // Le dossier temporaire est de toute façon supprimé à la fin de l'archive
+                let _ = fs::remove_file(&member_file.path);
+            }
+
+            let children = std::mem::take(&mut content.entries);
+            entries.push(ExtractedEntry {
+                path: member.path.clone(),
+                name: member.name().to_string(),
+                file_type: extension,
+                size: member.size,
+                modified: member.modified,
+                content,
+            });
+            entries.extend(children.into_iter().map(|child| ExtractedEntry {
+                path: format!("{}{}{}", member.path, ENTRY_SEPARATOR, child.path),
+                ..child
+            }));
+            Ok(())
+        });
+
+        match walk {
+            // Limite atteinte : les éléments déjà lus sont gardés
+            Err(e) if budget.is_exhausted() => {
+                tracing::warn!("Archive {} lue partiellement: {}", file.path.display(), e);
+            }
+            result => result?,
+        }
+        Ok(entries)
     }
 
-    fn get_reader_for_file(file: &File) -> Box<dyn Reader> {
+    fn get_reader_for_file(&self, file: &File) -> Box<dyn Reader> {
         let path = Path::new(&file.path);
         
         if let Some(extension) = path.extension() {
@@ -29,11 +119,16 @@ impl ReaderService {
             match ext_str.as_str() {
                 // Fichiers de code
                 "js" | "ts" | "jsx" | "tsx" | "py" | "java" | "cpp" | "c" | "h" | "hpp" | 
-                "rs" | "go" | "php" | "rb" | "pl" | "sh" | "sql" | "html" | "htm" | "css" |
+                "rs" | "go" | "php" | "rb" | "pl" | "sh" | "sql" | "css" |
                 "xml" | "yaml" | "yml" | "toml" | "ini" | "cfg" | "conf" => {
                     Box::new(CodeReader::new())
                 },
                 
+                // Pages web : texte visible uniquement
+                "html" | "htm" | "xhtml" => {
+                    Box::new(HtmlReader::new())
+                },
+                
                 // Fichiers CSV
                 "csv" | "tsv" => {
                     Box::new(CsvReader::new())
@@ -45,10 +140,34 @@ impl ReaderService {
                 },
                 
                 // Fichiers Word
-                "docx" | "doc" => {
+                "docx" | "doc" | "odt" => {
                     Box::new(WordReader::new())
                 },
                 
+                // Classeurs
+                "xlsx" | "xlsm" | "xls" | "ods" => {
+                    Box::new(SpreadsheetReader::with_max_cells(self.settings.spreadsheet_max_cells))
+                },
+                
+                // Présentations
+                "pptx" | "odp" => {
+                    Box::new(PresentationReader::new())
+                },
+                
+                // Livres numériques et texte enrichi
+                "epub" => {
+                    Box::new(EpubReader::new())
+                },
+                
+                "rtf" => {
+                    Box::new(RtfReader::new())
+                },
+                
+                // Courriers électroniques
+                "eml" | "mbox" => {
+                    Box::new(MailReader::new())
+                },
+                
                 // Fichiers texte simples
                 "txt" | "md" | "json" | "log" => {
                     Box::new(TextReader::new())
@@ -70,11 +189,17 @@ impl ReaderService {
             
             let supported_extensions = [
                 "js", "ts", "jsx", "tsx", "py", "java", "cpp", "c", "h", "hpp", 
-                "rs", "go", "php", "rb", "pl", "sh", "sql", "html", "htm", "css",
+                "rs", "go", "php", "rb", "pl", "sh", "sql", "css",
                 "xml", "yaml", "yml", "toml", "ini", "cfg", "conf",
+                "html", "htm", "xhtml",
                 "csv", "tsv",
                 "pdf",
-                "docx", "doc",
+                "docx", "doc", "odt",
+                "xlsx", "xlsm", "xls", "ods",
+                "pptx", "odp",
+                "epub", "rtf",
+                "eml", "mbox",
+                "zip", "tar", "tgz", "gz",
                 "txt", "md", "json", "log"
             ];
             
@@ -89,4 +214,47 @@ impl Default for ReaderService {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::readers::archive::tests::tar_bytes;
+    use crate::infrastructure::readers::office::tests::file_for;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_archive_members_are_flattened_up_to_max_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sauvegarde.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        let members: [(&str, Vec<u8>); 3] = [
+            ("docs/notes.txt", b"ordre du jour".to_vec()),
+            ("docs/anciens.tar", tar_bytes(&[("2024/bilan.md", b"compte rendu annuel")])),
+            ("photo.png", vec![0x89, b'P', b'N', b'G', 0, 0]),
+        ];
+        for (name, content) in &members {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let extract = |archive_max_depth: usize| {
+            let settings = ReaderSettings { archive_max_depth, ..ReaderSettings::default() };
+            ReaderService::with_settings(settings).extract(&file_for(path.clone())).unwrap().entries
+        };
+
+        let entries = extract(2);
+        let paths: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(paths, vec!["docs/notes.txt", "docs/anciens.tar", "docs/anciens.tar!/2024/bilan.md", "photo.png"]);
+        assert_eq!(entries[0].content.text, "ordre du jour");
+        assert_eq!(entries[2].content.text, "compte rendu annuel");
+        assert_eq!((entries[2].name.as_str(), entries[2].file_type.as_deref()), ("bilan.md", Some("md")));
+        assert_eq!(entries[3].size, 6);
+        assert!(entries[3].content.text.is_empty());
+
+        // Profondeur 1 : l'archive imbriquée est listée sans être ouverte
+        assert_eq!(extract(1).len(), 3);
+    }
+}
@@ -0,0 +1,177 @@
+//! Contrat commun des adaptateurs `Ai`, vérifié contre une imitation locale de chaque API
+
+use std::sync::{mpsc as std_mpsc, Mutex};
+use serde_json::json;
+use tokio::sync::mpsc;
+use crate::domain::entities::ai::{AiError, AiRequest};
+use crate::domain::ports::ai::Ai;
+use crate::infrastructure::ai::mock_server::{self, MockResponse};
+use crate::infrastructure::ai::{Ollama, OpenAiCompatible};
+use crate::shared::errors::AppError;
+
+const MODEL: &str = "stub-model";
+// Réponse du modèle, découpée comme lors du streaming
+const TOKENS: [&str; 3] = ["Bon", "jour ", "é"];
+
+fn request(model: Option<&str>) -> AiRequest {
+    AiRequest {
+        prompt: "Salut".to_string(),
+        system_prompt: Some("Consignes".to_string()),
+        model: model.map(str::to_string),
+        temperature: None,
+        max_tokens: None,
+        response_schema: None,
+    }
+}
+
+/// Corps en morceaux de 7 octets : des lignes et des caractères UTF-8 sont coupés entre deux morceaux
+fn chunked(body: String) -> Vec<Vec<u8>> {
+    body.as_bytes().chunks(7).map(<[u8]>::to_vec).collect()
+}
+
+fn openai_server(api_key: Option<&'static str>) -> String {
+    mock_server::start(move |request| {
+        if let Some(api_key) = api_key {
+            if request.header("authorization") != Some(format!("Bearer {}", api_key).as_str()) {
+                return MockResponse::Json(401, json!({ "error": { "message": "invalid api key" } }));
+            }
+        }
+
+        match request.path.as_str() {
+            "/v1/models" => MockResponse::Json(200, json!({ "data": [{ "id": MODEL, "object": "model", "owned_by": "me" }] })),
+            "/v1/chat/completions" if request.body["model"] != MODEL => {
+                MockResponse::Json(404, json!({ "error": { "message": "model not found" } }))
+            }
+            "/v1/chat/completions" => {
+                assert_eq!(request.body["messages"][0], json!({ "role": "system", "content": "Consignes" }));
+                if request.body["stream"] == true {
+                    let mut body: String = TOKENS.iter()
+                        .map(|token| format!("data: {}\n\n", json!({ "model": MODEL, "choices": [{ "delta": { "content": token } }] })))
+                        .collect();
+                    body.push_str(": keep-alive\n\ndata: [DONE]\n\n");
+                    MockResponse::Stream("text/event-stream", chunked(body))
+                } else {
+                    MockResponse::Json(200, json!({
+                        "model": MODEL,
+                        "choices": [{ "message": { "role": "assistant", "content": TOKENS.concat() } }],
+                        "usage": { "prompt_tokens": 3, "completion_tokens": 2, "total_tokens": 5 },
+                    }))
+                }
+            }
+            _ => MockResponse::Json(404, json!({ "error": { "message": "not found" } })),
+        }
+    })
+}
+
+fn ollama_server() -> String {
+    mock_server::start(|request| match request.path.as_str() {
+        "/api/tags" => MockResponse::Json(200, json!({ "models": [{ "name": MODEL, "size": 1 }] })),
+        "/api/chat" if request.body["model"] != MODEL => {
+            MockResponse::Json(404, json!({ "error": "model not found, try pulling it first" }))
+        }
+        "/api/chat" => {
+            assert_eq!(request.body["messages"][0], json!({ "role": "system", "content": "Consignes" }));
+            let message = |content: &str| json!({ "role": "assistant", "content": content });
+            if request.body["stream"] == true {
+                let mut body: String = TOKENS.iter()
+                    .map(|token| format!("{}\n", json!({ "model": MODEL, "message": message(token), "done": false })))
+                    .collect();
+                body.push_str(&json!({ "model": MODEL, "message": message(""), "done": true, "prompt_eval_count": 3, "eval_count": 2 }).to_string());
+                MockResponse::Stream("application/x-ndjson", chunked(body))
+            } else {
+                MockResponse::Json(200, json!({
+                    "model": MODEL, "message": message(&TOKENS.concat()), "done": true, "prompt_eval_count": 3, "eval_count": 2,
+                }))
+            }
+        }
+        _ => MockResponse::Json(404, json!({ "error": "not found" })),
+    })
+}
+
+async fn assert_contract(ai: &dyn Ai) {
+    assert!(ai.health_check().await.unwrap());
+    assert_eq!(ai.list_models().await.unwrap(), vec![MODEL]);
+
+    let response = ai.generate(request(None)).await.unwrap();
+    assert_eq!(response.content, TOKENS.concat());
+    assert_eq!(response.model_used, MODEL);
+    assert_eq!(response.tokens_used, Some(5));
+
+    let (sender, mut receiver) = mpsc::channel(16);
+    let response = ai.generate_stream(request(None), sender).await.unwrap();
+    let mut tokens = Vec::new();
+    while let Some(token) = receiver.recv().await {
+        tokens.push(token);
+    }
+    assert_eq!(tokens, TOKENS);
+    assert_eq!(response.content, TOKENS.concat());
+
+    // Plus de destinataire : la génération s'arrête
+    let (sender, receiver) = mpsc::channel(1);
+    drop(receiver);
+    assert!(matches!(ai.generate_stream(request(None), sender).await, Err(AppError::Cancelled(_))));
+
+    let error = ai.generate(request(Some("inconnu"))).await.unwrap_err();
+    assert!(matches!(error, AppError::Ai(AiError::RequestFailed(_))), "{}", error);
+    assert!(error.to_string().contains("not found"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_openai_compatible_contract() {
+    let ai = OpenAiCompatible::new("LM Studio", openai_server(None), MODEL.to_string(), None);
+    assert_contract(&ai).await;
+}
+
+#[tokio::test]
+async fn test_openai_compatible_sends_api_key() {
+    let url = openai_server(Some("secret"));
+    assert_contract(&OpenAiCompatible::new("Provider", url.clone(), MODEL.to_string(), Some("secret".to_string()))).await;
+
+    let anonymous = OpenAiCompatible::new("Provider", url, MODEL.to_string(), None);
+    let error = anonymous.list_models().await.unwrap_err();
+    assert!(error.to_string().contains("invalid api key"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_ollama_contract() {
+    let ai = Ollama::new(ollama_server(), MODEL.to_string());
+    assert_contract(&ai).await;
+}
+
+/// Serveur qui garde le corps de chaque requête et répond `reply`
+fn recording_server(reply: serde_json::Value) -> (String, std_mpsc::Receiver<serde_json::Value>) {
+    let (sender, receiver) = std_mpsc::channel();
+    let sender = Mutex::new(sender);
+    let url = mock_server::start(move |request| {
+        let _ = sender.lock().unwrap().send(request.body);
+        MockResponse::Json(200, reply.clone())
+    });
+    (url, receiver)
+}
+
+#[tokio::test]
+async fn test_json_schema_is_forwarded() {
+    let schema = json!({ "type": "object" });
+    let with_schema = AiRequest { response_schema: Some(schema.clone()), ..request(None) };
+    let completion = json!({ "model": MODEL, "choices": [{ "message": { "role": "assistant", "content": "{}" } }] });
+
+    let (url, bodies) = recording_server(completion.clone());
+    let ai = OpenAiCompatible::new("LM Studio", url, MODEL.to_string(), None).with_json_schema();
+    assert!(ai.supports_json_schema());
+    ai.generate(with_schema.clone()).await.unwrap();
+    assert_eq!(bodies.recv().unwrap()["response_format"]["json_schema"]["schema"], schema);
+    ai.generate(request(None)).await.unwrap();
+    assert!(bodies.recv().unwrap().get("response_format").is_none());
+
+    // Fournisseur sans prise en charge : le schéma n'est pas envoyé
+    let (url, bodies) = recording_server(completion);
+    let ai = OpenAiCompatible::new("Provider", url, MODEL.to_string(), None);
+    assert!(!ai.supports_json_schema());
+    ai.generate(with_schema.clone()).await.unwrap();
+    assert!(bodies.recv().unwrap().get("response_format").is_none());
+
+    let (url, bodies) = recording_server(json!({ "model": MODEL, "message": { "role": "assistant", "content": "{}" }, "done": true }));
+    let ai = Ollama::new(url, MODEL.to_string());
+    ai.generate(with_schema).await.unwrap();
+    assert_eq!(bodies.recv().unwrap()["format"], schema);
+}
@@ -0,0 +1,83 @@
+//! Serveur HTTP local minimal pour tester les adaptateurs d'IA sans modèle
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+pub(crate) struct MockRequest {
+    pub path: String,
+    /// En-têtes, noms en minuscules
+    pub headers: Vec<(String, String)>,
+    pub body: serde_json::Value,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+pub(crate) enum MockResponse {
+    Json(u16, serde_json::Value),
+    /// Corps envoyé en plusieurs écritures espacées, sans longueur annoncée
+    Stream(&'static str, Vec<Vec<u8>>),
+}
+
+/// Démarre le serveur et retourne son URL de base ; `handler` répond à chaque requête
+pub(crate) fn start<H>(handler: H) -> String
+where H: Fn(MockRequest) -> MockResponse + Send + 'static
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap_or(0);
+            let path = request_line.split_whitespace().nth(1).unwrap_or_default().to_string();
+
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+                }
+            }
+            let content_length = headers.iter()
+                .find(|(name, _)| name == "content-length")
+                .and_then(|(_, value)| value.parse().ok())
+                .unwrap_or(0);
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let body = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+
+            match handler(MockRequest { path, headers, body }) {
+                MockResponse::Json(status, json) => {
+                    let json = json.to_string();
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status, json.len(), json
+                    );
+                }
+                MockResponse::Stream(content_type, chunks) => {
+                    let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nConnection: close\r\n\r\n", content_type);
+                    for chunk in chunks {
+                        let _ = stream.write_all(&chunk);
+                        let _ = stream.flush();
+                        thread::sleep(Duration::from_millis(5));
+                    }
+                }
+            }
+        }
+    });
+
+    url
+}
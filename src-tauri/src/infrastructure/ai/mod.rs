@@ -1,4 +1,13 @@
-pub mod lm_studio;
+pub mod openai_compatible;
+pub mod ollama;
+pub mod openai_embeddings;
+pub mod stream;
+#[cfg(test)]
+pub(crate) mod mock_server;
+#[cfg(test)]
+mod contract_tests;
 
 
-pub use lm_studio::LmStudio;
\ No newline at end of file
+pub use openai_compatible::OpenAiCompatible;
+pub use ollama::Ollama;
+pub use openai_embeddings::OpenAiEmbeddings;
@@ -0,0 +1,199 @@
+use crate::domain::ports::ai::Ai;
+use crate::domain::entities::ai::{AiRequest, AiResponse, AiError};
+use crate::infrastructure::ai::stream::{forward_token, ResponseLines};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use tokio::sync::mpsc;
+use crate::shared::errors::{AppError, AppResult};
+
+#[derive(Debug, Serialize)]
+struct OllamaChatRequest {
+    model: String,
+    messages: Vec<OllamaMessage>,
+    stream: bool,
+    options: OllamaOptions,
+    /// Schéma JSON imposé à la réponse
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OllamaMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+    num_predict: u32,
+}
+
+/// Réponse complète, ou une ligne du flux NDJSON en streaming
+#[derive(Debug, Deserialize)]
+struct OllamaChatResponse {
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    message: Option<OllamaMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    eval_count: Option<u32>,
+}
+
+impl OllamaChatResponse {
+    fn tokens_used(&self) -> Option<u32> {
+        match (self.prompt_eval_count, self.eval_count) {
+            (None, None) => None,
+            (prompt, eval) => Some(prompt.unwrap_or(0) + eval.unwrap_or(0)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaTagsResponse {
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaErrorResponse {
+    error: String,
+}
+
+/// API native d'Ollama (`/api/chat`, `/api/tags`)
+pub struct Ollama {
+    client: Client,
+    base_url: String,
+    default_model: String,
+}
+
+impl Ollama {
+    pub fn new(base_url: String, default_model: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            default_model,
+        }
+    }
+
+    fn build_request(&self, request: AiRequest, stream: bool) -> OllamaChatRequest {
+        let mut messages = Vec::new();
+        if let Some(system_prompt) = request.system_prompt {
+            messages.push(OllamaMessage { role: "system".to_string(), content: system_prompt });
+        }
+        messages.push(OllamaMessage { role: "user".to_string(), content: request.prompt });
+
+        OllamaChatRequest {
+            model: request.model.unwrap_or_else(|| self.default_model.clone()),
+            messages,
+            stream,
+            options: OllamaOptions {
+                temperature: request.temperature.unwrap_or(0.7),
+                num_predict: request.max_tokens.unwrap_or(500),
+            },
+            format: request.response_schema,
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> AppResult<reqwest::Response> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    AiError::ConnectionError(format!("Cannot connect to Ollama at {}: {}", self.base_url, e))
+                } else if e.is_timeout() {
+                    AiError::ConnectionError("Request timeout".to_string())
+                } else {
+                    AiError::ConnectionError(format!("Network error: {}", e))
+                }
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<OllamaErrorResponse>(&text)
+                .map(|error| error.error)
+                .unwrap_or(text);
+            return Err(AppError::Ai(AiError::RequestFailed(format!("Ollama error ({}): {}", status, message))));
+        }
+        Ok(response)
+    }
+
+    fn parse(&self, text: &str) -> AppResult<OllamaChatResponse> {
+        serde_json::from_str(text)
+            .map_err(|e| AppError::Ai(AiError::ParsingError(format!("Failed to parse response: {}. Body: {}", e, text))))
+    }
+}
+
+#[async_trait]
+impl Ai for Ollama {
+    fn supports_json_schema(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, request: AiRequest) -> AppResult<AiResponse> {
+        let body = self.build_request(request, false);
+        let response = self.send(self.client.post(format!("{}/api/chat", self.base_url)).json(&body)).await?;
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| AiError::RequestFailed(format!("Failed to read response body: {}", e)))?;
+        let response = self.parse(&text)?;
+
+        Ok(AiResponse {
+            tokens_used: response.tokens_used(),
+            content: response.message.map(|message| message.content).unwrap_or_default(),
+            model_used: response.model.unwrap_or(body.model),
+        })
+    }
+
+    async fn generate_stream(&self, request: AiRequest, tokens: mpsc::Sender<String>) -> AppResult<AiResponse> {
+        let body = self.build_request(request, true);
+        let response = self.send(self.client.post(format!("{}/api/chat", self.base_url)).json(&body)).await?;
+
+        let mut lines = ResponseLines::new(response);
+        let mut content = String::new();
+        let mut tokens_used = None;
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let chunk = self.parse(&line)?;
+            if let Some(token) = chunk.message.as_ref().map(|message| message.content.clone()).filter(|token| !token.is_empty()) {
+                content.push_str(&token);
+                forward_token(&tokens, token).await?;
+            }
+            if chunk.done {
+                tokens_used = chunk.tokens_used();
+                break;
+            }
+        }
+
+        Ok(AiResponse { content, model_used: body.model, tokens_used })
+    }
+
+    async fn list_models(&self) -> AppResult<Vec<String>> {
+        let response = self.send(self.client.get(format!("{}/api/tags", self.base_url))).await?;
+        let tags = response
+            .json::<OllamaTagsResponse>()
+            .await
+            .map_err(|e| AiError::RequestFailed(format!("Failed to parse response: {}", e)))?;
+        Ok(tags.models.into_iter().map(|model| model.name).collect())
+    }
+
+    async fn health_check(&self) -> AppResult<bool> {
+        self.list_models().await.map(|_| true)
+    }
+}
@@ -0,0 +1,337 @@
+use crate::domain::ports::ai::Ai;
+use crate::domain::entities::ai::{AiRequest, AiResponse, AiError};
+use crate::infrastructure::ai::stream::{forward_token, sse_data, ResponseLines};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+use tokio::sync::mpsc;
+use crate::shared::errors::{AppError, AppResult};
+
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+    max_tokens: u32,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ChatMessage {
+    role: String,
+    content: ChatContent,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+enum ChatContent {
+    Text(String),
+    Parts(Vec<ChatContentPart>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ChatContentPart {
+    #[serde(default)]
+    r#type: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+impl ChatContent {
+    fn into_string(self) -> String {
+        match self {
+            ChatContent::Text(s) => s,
+            ChatContent::Parts(parts) => parts
+                .into_iter()
+                .filter_map(|p| p.text)
+                .collect::<Vec<_>>()
+                .join(""),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+/// Morceau d'une réponse en streaming (`data:` d'un événement SSE)
+#[derive(Debug, Deserialize)]
+struct ChatStreamChunk {
+    #[serde(default)]
+    choices: Vec<ChatStreamChoice>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatStreamChoice {
+    #[serde(default)]
+    delta: Option<ChatDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatModel {
+    id: String,
+    object: String,
+    owned_by: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatModelsResponse {
+    data: Vec<ChatModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    total_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatErrorResponse {
+    error: ChatErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatErrorDetail {
+    message: String,
+    #[serde(rename = "type")]
+    error_type: Option<String>,
+    code: Option<String>,
+}
+
+/// Fournisseur compatible avec l'API OpenAI `/v1/chat/completions` : LM Studio, serveur llama.cpp,
+/// services distants (clé d'API en en-tête `Authorization`)
+pub struct OpenAiCompatible {
+    client: Client,
+    /// Nom du fournisseur dans les messages d'erreur
+    label: String,
+    base_url: String,
+    default_model: String,
+    api_key: Option<String>,
+    /// Accepte `response_format` de type `json_schema`
+    json_schema: bool,
+}
+
+impl OpenAiCompatible {
+    pub fn new(label: &str, base_url: String, default_model: String, api_key: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            label: label.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            default_model,
+            api_key: api_key.filter(|key| !key.trim().is_empty()),
+            json_schema: false,
+        }
+    }
+
+    /// Le serveur contraint ses réponses à un schéma JSON (LM Studio, llama.cpp)
+    pub fn with_json_schema(mut self) -> Self {
+        self.json_schema = true;
+        self
+    }
+
+    fn build_request(&self, request: AiRequest, stream: bool) -> ChatRequest {
+        let mut messages = Vec::new();
+        if let Some(system_prompt) = request.system_prompt {
+            messages.push(ChatMessage {
+                role: "system".to_string(),
+                content: ChatContent::Text(system_prompt),
+            });
+        }
+        messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: ChatContent::Text(request.prompt),
+        });
+
+        ChatRequest {
+            model: request.model.unwrap_or_else(|| self.default_model.clone()),
+            messages,
+            temperature: request.temperature.unwrap_or(0.7),
+            max_tokens: request.max_tokens.unwrap_or(500),
+            stream,
+            response_format: request.response_schema
+                .filter(|_| self.json_schema)
+                .map(|schema| json!({ "type": "json_schema", "json_schema": { "name": "response", "schema": schema } })),
+        }
+    }
+
+    fn authorized(&self, builder: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(api_key) => builder.bearer_auth(api_key),
+            None => builder,
+        }
+    }
+
+    async fn send(&self, request: &ChatRequest) -> AppResult<reqwest::Response> {
+        let response = self
+            .authorized(self.client.post(format!("{}/v1/chat/completions", self.base_url)))
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    AiError::ConnectionError(format!(
+                        "Cannot connect to {} at {}: {}",
+                        self.label, self.base_url, e
+                    ))
+                } else if e.is_timeout() {
+                    AiError::ConnectionError("Request timeout".to_string())
+                } else {
+                    AiError::ConnectionError(format!("Network error: {}", e))
+                }
+            })?;
+
+        if !response.status().is_success() {
+            let error = self.handle_error_response(response).await;
+            return Err(AppError::Ai(error));
+        }
+        Ok(response)
+    }
+
+    async fn handle_error_response(&self, response: reqwest::Response) -> AiError {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        
+        if let Ok(error_response) = serde_json::from_str::<ChatErrorResponse>(&text) {
+            AiError::RequestFailed(format!(
+                "{} error ({}): {}",
+                self.label,
+                status,
+                error_response.error.message
+            ))
+        } else {
+            AiError::RequestFailed(format!(
+                "HTTP {} - {}",
+                status,
+                if text.is_empty() { "Unknown error" } else { &text }
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl Ai for OpenAiCompatible {
+    fn supports_json_schema(&self) -> bool {
+        self.json_schema
+    }
+
+    async fn generate(&self, request: AiRequest) -> AppResult<AiResponse> {
+        let response = self.send(&self.build_request(request, false)).await?;
+
+        let body_text = response
+            .text()
+            .await
+            .map_err(|e| AiError::RequestFailed(format!("Failed to read response body: {}", e)))?;
+
+        let response_body: ChatResponse = serde_json::from_str(&body_text)
+            .map_err(|e| AiError::RequestFailed(format!(
+                "Failed to parse response: {}. Body: {}",
+                e, body_text
+            )))?;
+
+        let choice = response_body
+            .choices
+            .first()
+            .ok_or_else(|| AiError::ParsingError("No choices in response".to_string()))?;
+
+        let content = choice.message.content.clone().into_string();
+
+        Ok(AiResponse {
+            content,
+            model_used: response_body.model.unwrap_or_else(|| "unknown".to_string()),
+            tokens_used: response_body.usage.map(|usage| usage.total_tokens as u32),
+        })
+    }
+
+    async fn generate_stream(&self, request: AiRequest, tokens: mpsc::Sender<String>) -> AppResult<AiResponse> {
+        let response = self.send(&self.build_request(request, true)).await?;
+
+        let mut lines = ResponseLines::new(response);
+        let mut content = String::new();
+        let mut model_used = None;
+        let mut usage = None;
+
+        while let Some(line) = lines.next_line().await? {
+            let Some(data) = sse_data(&line) else { continue };
+            if data == "[DONE]" {
+                break;
+            }
+
+            let chunk: ChatStreamChunk = serde_json::from_str(data)
+                .map_err(|e| AiError::ParsingError(format!("Failed to parse stream chunk: {}. Data: {}", e, data)))?;
+            model_used = chunk.model.or(model_used);
+            usage = chunk.usage.or(usage);
+
+            let token = chunk.choices.into_iter()
+                .next()
+                .and_then(|choice| choice.delta)
+                .and_then(|delta| delta.content)
+                .filter(|token| !token.is_empty());
+            if let Some(token) = token {
+                content.push_str(&token);
+                forward_token(&tokens, token).await?;
+            }
+        }
+
+        Ok(AiResponse {
+            content,
+            model_used: model_used.unwrap_or_else(|| "unknown".to_string()),
+            tokens_used: usage.map(|usage| usage.total_tokens as u32),
+        })
+    }
+
+    async fn list_models(&self) -> AppResult<Vec<String>> {
+        let response = self
+            .authorized(self.client.get(format!("{}/v1/models", self.base_url)))
+            .send()
+            .await
+            .map_err(|e| AiError::ConnectionError(format!("Failed to list models: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error = self.handle_error_response(response).await;
+            return Err(AppError::Ai(error));
+        }
+        
+        let response_body = response
+            .json::<ChatModelsResponse>()
+            .await
+            .map_err(|e| AiError::RequestFailed(format!("Failed to parse response: {}", e)))?;
+
+        let models = response_body.data.iter().map(|model| model.id.clone()).collect();
+
+        Ok(models)
+    }
+
+    async fn health_check(&self) -> AppResult<bool> {
+        match self.list_models().await {
+            Ok(_) => Ok(true),
+            Err(e) => Err(e)
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::domain::ports::embedder::Embedder;
+use crate::domain::entities::ai::AiError;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use crate::shared::errors::{AppError, AppResult};
+
+#[derive(Debug, Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingsErrorResponse {
+    error: EmbeddingsErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingsErrorDetail {
+    message: String,
+}
+
+/// Embeddings via un endpoint compatible OpenAI `/v1/embeddings` (LM Studio, Ollama, llama.cpp...)
+pub struct OpenAiEmbeddings {
+    client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl OpenAiEmbeddings {
+    pub fn new(base_url: String, model: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbeddings {
+    async fn embed(&self, inputs: Vec<String>) -> AppResult<Vec<Vec<f32>>> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let expected = inputs.len();
+
+        let response = self
+            .client
+            .post(format!("{}/v1/embeddings", self.base_url))
+            .json(&EmbeddingsRequest { model: &self.model, input: inputs })
+            .send()
+            .await
+            .map_err(|e| AiError::ConnectionError(format!(
+                "Cannot reach embeddings endpoint at {}: {}", self.base_url, e
+            )))?;
+
+        let status = response.status();
+        let body_text = response
+            .text()
+            .await
+            .map_err(|e| AiError::RequestFailed(format!("Failed to read response body: {}", e)))?;
+
+        if !status.is_success() {
+            let message = serde_json::from_str::<EmbeddingsErrorResponse>(&body_text)
+                .map(|error| error.error.message)
+                .unwrap_or(body_text);
+            return Err(AppError::Ai(AiError::RequestFailed(format!("Embeddings error ({}): {}", status, message))));
+        }
+
+        let mut body: EmbeddingsResponse = serde_json::from_str(&body_text)
+            .map_err(|e| AiError::ParsingError(format!("Failed to parse embeddings response: {}", e)))?;
+
+        if body.data.len() != expected {
+            return Err(AppError::Ai(AiError::ParsingError(format!(
+                "Expected {} embeddings, got {}", expected, body.data.len()
+            ))));
+        }
+
+        // L'API ne garantit pas l'ordre : `index` fait foi quand il est présent
+        body.data.sort_by_key(|data| data.index.unwrap_or(usize::MAX));
+        Ok(body.data.into_iter().map(|data| data.embedding).collect())
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::infrastructure::ai::mock_server::{self, MockResponse};
+
+    /// Vecteur déterministe : nombre d'occurrences de chaque lettre de l'alphabet
+    pub(crate) fn letter_vector(text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; 26];
+        for c in text.to_lowercase().chars().filter(char::is_ascii_lowercase) {
+            vector[(c as u8 - b'a') as usize] += 1.0;
+        }
+        vector
+    }
+
+    /// Serveur HTTP local qui répond à `/v1/embeddings` avec `letter_vector`, dans l'ordre inverse
+    /// (les `index` restent exacts). Les entrées contenant « rejet » sont refusées. Retourne l'URL de base.
+    pub(crate) fn start_stub_server() -> String {
+        mock_server::start(|request| {
+            if request.body["model"] == "inconnu" {
+                return MockResponse::Json(404, serde_json::json!({ "error": { "message": "model not found" } }));
+            }
+            let inputs: Vec<String> = serde_json::from_value(request.body["input"].clone()).unwrap();
+            if inputs.iter().any(|input| input.contains("rejet")) {
+                return MockResponse::Json(400, serde_json::json!({ "error": { "message": "input rejected" } }));
+            }
+            let data: Vec<serde_json::Value> = inputs.iter().enumerate().rev()
+                .map(|(index, input)| serde_json::json!({ "index": index, "embedding": letter_vector(input) }))
+                .collect();
+            MockResponse::Json(200, serde_json::json!({ "data": data }))
+        })
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_follow_input_order() {
+        let url = start_stub_server();
+
+        let embedder = OpenAiEmbeddings::new(url.clone(), "stub".to_string());
+        let vectors = embedder.embed(vec!["aab".to_string(), "zz".to_string()]).await.unwrap();
+        assert_eq!(vectors, vec![letter_vector("aab"), letter_vector("zz")]);
+
+        let unknown = OpenAiEmbeddings::new(url, "inconnu".to_string());
+        let error = unknown.embed(vec!["texte".to_string()]).await.unwrap_err();
+        assert!(error.to_string().contains("model not found"), "{}", error);
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::VecDeque;
+use tokio::sync::mpsc;
+use crate::domain::entities::ai::AiError;
+use crate::shared::errors::{AppError, AppResult};
+
+/// Découpe en lignes un corps de réponse reçu par morceaux ; un morceau peut couper une ligne, voire
+/// un caractère UTF-8, n'importe où
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lignes complètes (sans fin de ligne) après l'ajout de `chunk`
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+
+        let mut lines = Vec::new();
+        while let Some(end) = self.pending.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']).to_string());
+        }
+        lines
+    }
+
+    /// Dernière ligne, sans fin de ligne, à la fin du corps
+    pub fn finish(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&std::mem::take(&mut self.pending)).trim().to_string();
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+/// Valeur d'une ligne `data:` d'un flux server-sent events
+pub fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(str::trim_start)
+}
+
+/// Lignes d'un corps de réponse HTTP, lues au fur et à mesure de leur arrivée
+pub struct ResponseLines {
+    response: reqwest::Response,
+    buffer: LineBuffer,
+    ready: VecDeque<String>,
+    finished: bool,
+}
+
+impl ResponseLines {
+    pub fn new(response: reqwest::Response) -> Self {
+        Self { response, buffer: LineBuffer::new(), ready: VecDeque::new(), finished: false }
+    }
+
+    pub async fn next_line(&mut self) -> AppResult<Option<String>> {
+        loop {
+            if let Some(line) = self.ready.pop_front() {
+                return Ok(Some(line));
+            }
+            if self.finished {
+                return Ok(None);
+            }
+
+            let chunk = self.response
+                .chunk()
+                .await
+                .map_err(|e| AiError::RequestFailed(format!("Failed to read response stream: {}", e)))?;
+            match chunk {
+                Some(bytes) => self.ready.extend(self.buffer.push(&bytes)),
+                None => {
+                    self.finished = true;
+                    self.ready.extend(self.buffer.finish());
+                }
+            }
+        }
+    }
+}
+
+/// Transmet un fragment de la réponse ; un récepteur fermé interrompt la génération
+pub async fn forward_token(tokens: &mpsc::Sender<String>, token: String) -> AppResult<()> {
+    tokens.send(token).await
+        .map_err(|_| AppError::Cancelled("Génération interrompue : réponse sans destinataire".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_split_across_chunks() {
+        let body = "data: {\"t\":\"é\"}\r\n\r\n: commentaire\ndata: [DONE]".as_bytes();
+        // Coupure au milieu du « é » (2 octets)
+        let split = body.iter().position(|byte| *byte == 0xC3).unwrap() + 1;
+
+        let mut buffer = LineBuffer::new();
+        assert!(buffer.push(&body[..split]).is_empty());
+        let lines = buffer.push(&body[split..]);
+        assert_eq!(lines, vec!["data: {\"t\":\"é\"}", "", ": commentaire"]);
+        assert_eq!(buffer.finish().as_deref(), Some("data: [DONE]"));
+        assert_eq!(buffer.finish(), None);
+
+        let data: Vec<&str> = lines.iter().filter_map(|line| sse_data(line)).collect();
+        assert_eq!(data, vec!["{\"t\":\"é\"}"]);
+    }
+}
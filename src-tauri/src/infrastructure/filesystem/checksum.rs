@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use rayon::prelude::*;
+use xxhash_rust::xxh3::Xxh3;
+use crate::domain::entities::duplicate::ChecksumCandidate;
+
+// Octets lus au début et à la fin du fichier pour l'empreinte partielle
+const PARTIAL_BLOCK_SIZE: u64 = 64 * 1024;
+// Fichiers hachés entre deux points d'arrêt de la tâche
+const CHECKSUM_BATCH_SIZE: usize = 256;
+
+/// Empreinte rapide (xxh3) du début et de la fin du fichier, pour écarter la plupart des faux doublons
+pub fn partial_hash(path: &Path, size: u64) -> io::Result<u64> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0u8; PARTIAL_BLOCK_SIZE as usize];
+
+    let read = read_block(&mut file, &mut buffer)?;
+    hasher.update(&buffer[..read]);
+
+    if size > PARTIAL_BLOCK_SIZE * 2 {
+        file.seek(SeekFrom::End(-(PARTIAL_BLOCK_SIZE as i64)))?;
+        let read = read_block(&mut file, &mut buffer)?;
+        hasher.update(&buffer[..read]);
+    }
+
+    hasher.update(&size.to_le_bytes());
+    Ok(hasher.digest())
+}
+
+/// Empreinte complète (BLAKE3, hexadécimal) stockée dans la colonne `checksum`
+pub fn full_hash(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+fn read_block(file: &mut fs::File, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read(&mut buffer[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Calcule les empreintes manquantes des candidats en trois étapes : regroupement par taille,
+/// empreinte partielle, puis empreinte complète seulement pour les fichiers encore en collision.
+/// `checkpoint` peut bloquer (pause) : il n'est appelé qu'entre deux lots, hors des threads rayon ;
+/// `keep_going`, appelé pour chaque fichier, ne doit pas bloquer. Retourne les couples (chemin, empreinte).
+pub fn compute_checksums<P, C>(candidates: &[ChecksumCandidate], mut checkpoint: P, keep_going: C) -> Vec<(String, String)>
+where
+    P: FnMut() -> bool,
+    C: Fn() -> bool + Sync,
+{
+    let mut by_size: HashMap<u64, Vec<&ChecksumCandidate>> = HashMap::new();
+    for candidate in candidates {
+        by_size.entry(candidate.size).or_default().push(candidate);
+    }
+
+    // Les groupes dont toutes les empreintes sont déjà connues n'ont rien à calculer
+    let to_probe: Vec<&ChecksumCandidate> = by_size.into_values()
+        .filter(|group| group.len() > 1 && group.iter().any(|c| c.checksum.is_none()))
+        .flatten()
+        .collect();
+
+    let partials = in_batches(&to_probe, &mut checkpoint, |candidate| {
+        if !keep_going() {
+            return None;
+        }
+        match partial_hash(Path::new(&candidate.path), candidate.size) {
+            Ok(hash) => Some(((candidate.size, hash), candidate)),
+            Err(e) => {
+                tracing::warn!("Empreinte partielle impossible pour {}: {}", candidate.path, e);
+                None
+            }
+        }
+    });
+
+    let mut by_partial: HashMap<(u64, u64), Vec<&ChecksumCandidate>> = HashMap::new();
+    for (key, candidate) in partials {
+        by_partial.entry(key).or_default().push(candidate);
+    }
+
+    let to_hash: Vec<&ChecksumCandidate> = by_partial.into_values()
+        .filter(|group| group.len() > 1)
+        .flatten()
+        .filter(|candidate| candidate.checksum.is_none())
+        .collect();
+
+    tracing::info!("Empreintes: {} fichiers sondés, {} hachés entièrement", to_probe.len(), to_hash.len());
+
+    in_batches(&to_hash, &mut checkpoint, |candidate| {
+        if !keep_going() {
+            return None;
+        }
+        match full_hash(Path::new(&candidate.path)) {
+            Ok(hash) => Some((candidate.path.clone(), hash)),
+            Err(e) => {
+                tracing::warn!("Empreinte impossible pour {}: {}", candidate.path, e);
+                None
+            }
+        }
+    })
+}
+
+/// Applique `hash` en parallèle, lot par lot, tant que `checkpoint` l'autorise
+fn in_batches<'a, T, P, H>(candidates: &[&'a ChecksumCandidate], checkpoint: &mut P, hash: H) -> Vec<T>
+where
+    T: Send,
+    P: FnMut() -> bool,
+    H: Fn(&'a ChecksumCandidate) -> Option<T> + Sync,
+{
+    let mut results = Vec::new();
+    for batch in candidates.chunks(CHECKSUM_BATCH_SIZE) {
+        if !checkpoint() {
+            break;
+        }
+        results.par_extend(batch.par_iter().filter_map(|candidate| hash(candidate)));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_colliding_files_are_fully_hashed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, content: &[u8]| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            ChecksumCandidate { path: path.to_string_lossy().to_string(), size: content.len() as u64, checksum: None }
+        };
+
+        let big = vec![7u8; 300 * 1024];
+        let mut big_tail = big.clone();
+        *big_tail.last_mut().unwrap() = 8;
+        let mut big_middle = big.clone();
+        big_middle[150 * 1024] = 9;
+
+        let candidates = vec![
+            write("a.bin", &big),
+            write("b.bin", &big),
+            write("tail.bin", &big_tail),
+            write("middle.bin", &big_middle),
+            write("small.txt", b"bonjour"),
+            write("other.txt", b"bonsoir"),
+        ];
+
+        let mut checksums = compute_checksums(&candidates, || true, || true);
+        checksums.sort();
+
+        // Le milieu n'est pas couvert par l'empreinte partielle : seul le hachage complet les distingue
+        let hashed: Vec<&str> = checksums.iter()
+            .map(|(path, _)| Path::new(path).file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(hashed, vec!["a.bin", "b.bin", "middle.bin"]);
+        assert_eq!(checksums[0].1, checksums[1].1);
+        assert_ne!(checksums[0].1, checksums[2].1);
+
+        assert!(compute_checksums(&candidates, || false, || true).is_empty());
+        assert!(compute_checksums(&candidates, || true, || false).is_empty());
+    }
+}
@@ -1,7 +1,9 @@
-use std::path::{Path};
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use walkdir::WalkDir;
 use crate::domain::entities::file::File;
+use crate::infrastructure::filesystem::ownership::owner_and_group;
+use crate::infrastructure::filesystem::path_filter::PathFilter;
 use rayon::prelude::*;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -10,26 +12,43 @@ use std::collections::HashMap;
 
 const MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
 
-pub fn collect_files_and_folders<F>(base_path: &Path, progress_callback: F) -> Vec<File>
-where F: Fn(usize, &str) + Send + Sync + Clone
+/// Fichiers trouvés et chemins illisibles : ces derniers ne doivent pas être pris pour des suppressions
+#[derive(Debug, Default)]
+pub struct Collected {
+    pub files: Vec<File>,
+    /// Dossiers ou fichiers dont la lecture a échoué (droits insuffisants, volume démonté...)
+    pub unreadable: Vec<PathBuf>,
+}
+
+/// `is_cancelled` est consulté à chaque entrée sans bloquer : en cas d'annulation la collecte s'arrête
+/// et retourne ce qui a déjà été traité. La pause est gérée par l'appelant, avant la collecte
+pub fn collect_files_and_folders<F, C>(base_path: &Path, filter: &PathFilter, is_cancelled: C, progress_callback: F) -> Collected
+where
+    F: Fn(usize, &str) + Send + Sync + Clone,
+    C: Fn() -> bool + Sync,
 {
     if !base_path.exists() || !base_path.is_dir() {
         tracing::error!("Le chemin n'existe pas ou n'est pas un dossier: {}", base_path.display());
-        return Vec::new();
+        return Collected::default();
     }
 
+    let mut unreadable = Vec::new();
+
+    // Les dossiers exclus ne sont pas parcourus
     let entries: Vec<_> = WalkDir::new(base_path)
         .follow_links(true)
         .max_depth(100)
         .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !filter.is_excluded_entry(e.path(), e.file_type().is_dir()))
+        .take_while(|_| !is_cancelled())
         .filter_map(|e| match e {
             Ok(entry) => Some(entry),
             Err(err) => {
                 tracing::warn!("Erreur d'accès ignorée: {}", err);
+                unreadable.extend(err.path().map(Path::to_path_buf));
                 None
             }
         })
-        .filter(|e| !should_skip_entry(e))
         .collect();
 
     let total = entries.len();
@@ -38,7 +57,7 @@ where F: Fn(usize, &str) + Send + Sync + Clone
 
     if total == 0 {
         progress_callback(0, "Aucun fichier trouvé dans ce répertoire");
-        return Vec::new();
+        return Collected { files: Vec::new(), unreadable };
     }
 
     let processed = Arc::new(AtomicUsize::new(0));
@@ -47,9 +66,13 @@ where F: Fn(usize, &str) + Send + Sync + Clone
     progress_callback(0, &format!("Début du traitement: {} éléments", total));
 
     // Deuxième phase: traitement parallèle avec gestion d'erreur améliorée
-    let files: Vec<File> = entries
+    let results: Vec<Result<File, PathBuf>> = entries
         .par_iter()
         .filter_map(|entry| {
+            if is_cancelled() {
+                return None;
+            }
+
             let result = process_entry_safe(entry);
 
             let current = processed.fetch_add(1, Ordering::Relaxed);
@@ -58,12 +81,20 @@ where F: Fn(usize, &str) + Send + Sync + Clone
                 progress_callback(current, &format!("Traitement: {} / {}", current, total));
             }
 
-            result
+            result.transpose()
         })
         .collect();
 
+    let mut files = Vec::with_capacity(results.len());
+    for result in results {
+        match result {
+            Ok(file) => files.push(file),
+            Err(path) => unreadable.push(path),
+        }
+    }
+
     progress_callback(total, &format!("Indexation terminée: {} fichiers traités", files.len()));
-    files
+    Collected { files, unreadable }
 }
 
 fn extract_file_type(path: &Path) -> String {
@@ -73,9 +104,36 @@ fn extract_file_type(path: &Path) -> String {
         .unwrap_or_else(|| "no_extension".to_string())
 }
 
-fn process_entry_safe(entry: &walkdir::DirEntry) -> Option<File> {
+/// Construit un `File` pour un chemin isolé (utilisé par le watcher)
+pub fn collect_single_path(path: &Path) -> Option<File> {
+    let metadata = match fs::metadata(path) {
+        Ok(meta) => meta,
+        Err(e) => {
+            tracing::warn!("Impossible de lire les métadonnées pour {}: {}", path.display(), e);
+            return None;
+        }
+    };
+
+    build_file(path, &metadata)
+}
+
+/// `Err` si les métadonnées sont illisibles : le chemin est signalé plutôt qu'ignoré
+fn process_entry_safe(entry: &walkdir::DirEntry) -> Result<Option<File>, PathBuf> {
     let path = entry.path();
 
+    // Récupération des métadonnées avec gestion d'erreur
+    let metadata = match entry.metadata() {
+        Ok(meta) => meta,
+        Err(e) => {
+            tracing::warn!("Impossible de lire les métadonnées pour {}: {}", path.display(), e);
+            return Err(path.to_path_buf());
+        }
+    };
+
+    Ok(build_file(path, &metadata))
+}
+
+fn build_file(path: &Path, metadata: &fs::Metadata) -> Option<File> {
     // Vérification de sécurité basique
     if path.to_string_lossy().len() > 4096 {
         tracing::warn!("Chemin trop long ignoré: {}", path.display());
@@ -90,15 +148,6 @@ fn process_entry_safe(entry: &walkdir::DirEntry) -> Option<File> {
         }
     };
 
-    // Récupération des métadonnées avec gestion d'erreur
-    let metadata = match entry.metadata() {
-        Ok(meta) => meta,
-        Err(e) => {
-            tracing::warn!("Impossible de lire les métadonnées pour {}: {}", path.display(), e);
-            return None;
-        }
-    };
-
     let last_modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
     let created_at = metadata.created().unwrap_or_else(|_| SystemTime::now());
     let accessed_at = metadata.accessed().unwrap_or_else(|_| SystemTime::now());
@@ -124,7 +173,7 @@ fn process_entry_safe(entry: &walkdir::DirEntry) -> Option<File> {
     #[cfg(not(unix))]
     let is_executable = false;
     let is_symlink = path.is_symlink();
-    let (owner, group) = (None, None);
+    let (owner, group) = owner_and_group(metadata);
 
     // Détermination du type MIME
     let mime_type = determine_mime_type(path);
@@ -135,7 +184,7 @@ fn process_entry_safe(entry: &walkdir::DirEntry) -> Option<File> {
     // Pas de comptage des lignes et mots pendant la collecte - sera fait lors de l'indexation du contenu
     let (line_count, word_count) = (None, None);
 
-    if path.is_dir() {
+    if metadata.is_dir() {
         Some(File {
             path: path.to_path_buf(),
             name: file_name.to_string(),
@@ -203,53 +252,6 @@ fn process_entry_safe(entry: &walkdir::DirEntry) -> Option<File> {
     }
 }
 
-fn should_skip_entry(entry: &walkdir::DirEntry) -> bool {
-    let path_str = entry.path().to_string_lossy();
-    let file_name = entry.file_name().to_string_lossy();
-
-    // Filtrage plus permissif - seulement les dossiers système critiques
-    let should_skip =
-        // Dossiers système macOS critiques uniquement
-        path_str.contains("/System/") ||
-            path_str.contains("/private/") ||
-            path_str.contains(".Trashes") ||
-            path_str.contains(".fseventsd") ||
-            path_str.contains(".TemporaryItems") ||
-            path_str.contains(".db") ||
-
-            // Dossiers système Windows critiques uniquement
-            path_str.contains("$RECYCLE.BIN") ||
-            path_str.contains("System Volume Information") ||
-            path_str.contains("Windows\\System32\\") ||
-            path_str.contains("AppData\\Local\\Temp\\") ||
-
-            // Dossiers système Unix/Linux critiques uniquement
-            path_str.contains("/proc/") ||
-            path_str.contains("/sys/") ||
-
-            // Dossiers de développement volumineux
-            path_str.contains("/node_modules/") ||
-            path_str.contains(".git/") ||
-            path_str.contains(".vscode/") ||
-            path_str.contains(".idea/") ||
-            path_str.contains("/dist/") ||
-            path_str.contains("/build/") ||
-            path_str.contains("/target/") ||
-            path_str.contains("/tmp/") ||
-            path_str.contains("/var/") ||
-            path_str.contains("/private/") ||
-
-            // Fichiers temporaires système uniquement
-            file_name.ends_with(".tmp") ||
-            file_name.ends_with(".temp") ||
-            file_name.starts_with("~$") ||
-            file_name.ends_with(".DS_Store");
-
-
-    should_skip
-}
-
-
 fn is_hidden_file(path: &Path) -> bool {
     #[cfg(target_os = "windows")]
     {
@@ -311,6 +313,7 @@ fn determine_mime_type(path: &Path) -> Option<String> {
         ("pdf", "application/pdf"),
         ("doc", "application/msword"),
         ("docx", "application/vnd.openxmlformats-officedocument.wordprocessingml.document"),
+        ("odt", "application/vnd.oasis.opendocument.text"),
         ("xls", "application/vnd.ms-excel"),
         ("xlsx", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
         ("ppt", "application/vnd.ms-powerpoint"),
@@ -345,6 +348,7 @@ fn determine_mime_type(path: &Path) -> Option<String> {
         ("7z", "application/x-7z-compressed"),
         ("tar", "application/x-tar"),
         ("gz", "application/gzip"),
+        ("tgz", "application/gzip"),
 
         // Audio/Video
         ("mp3", "audio/mpeg"),
@@ -1,3 +1,6 @@
 pub mod scanner;
 pub mod collect;
-pub mod open_file;
\ No newline at end of file
+pub mod open_file;
+pub mod path_filter;
+pub mod checksum;
+pub mod ownership;
\ No newline at end of file
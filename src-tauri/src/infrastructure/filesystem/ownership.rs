@@ -0,0 +1,51 @@
+use std::fs;
+
+/// Noms du propriétaire et du groupe d'un fichier (Unix uniquement)
+#[cfg(unix)]
+pub fn owner_and_group(metadata: &fs::Metadata) -> (Option<String>, Option<String>) {
+    use std::os::unix::fs::MetadataExt;
+    (Some(unix::user_name(metadata.uid())), Some(unix::group_name(metadata.gid())))
+}
+
+#[cfg(not(unix))]
+pub fn owner_and_group(_metadata: &fs::Metadata) -> (Option<String>, Option<String>) {
+    (None, None)
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::collections::HashMap;
+    use std::sync::{Mutex, OnceLock};
+
+    // Un scan ne rencontre que quelques uid/gid : chaque nom n'est résolu qu'une fois
+    static USER_NAMES: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    static GROUP_NAMES: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+
+    pub fn user_name(uid: u32) -> String {
+        cached(&USER_NAMES, uid, || {
+            uzers::get_user_by_uid(uid).map(|user| user.name().to_string_lossy().into_owned())
+        })
+    }
+
+    pub fn group_name(gid: u32) -> String {
+        cached(&GROUP_NAMES, gid, || {
+            uzers::get_group_by_gid(gid).map(|group| group.name().to_string_lossy().into_owned())
+        })
+    }
+
+    /// Sans entrée dans la base des comptes, l'identifiant numérique sert de nom
+    fn cached<F>(cache: &OnceLock<Mutex<HashMap<u32, String>>>, id: u32, resolve: F) -> String
+    where F: FnOnce() -> Option<String>
+    {
+        let cache = cache.get_or_init(|| Mutex::new(HashMap::new()));
+        if let Some(name) = cache.lock().ok().and_then(|names| names.get(&id).cloned()) {
+            return name;
+        }
+
+        let name = resolve().unwrap_or_else(|| id.to_string());
+        if let Ok(mut names) = cache.lock() {
+            names.insert(id, name.clone());
+        }
+        name
+    }
+}
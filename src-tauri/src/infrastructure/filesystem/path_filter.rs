@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use crate::domain::entities::path_rule::{IndexingRules, PathRule, RuleKind};
+use crate::shared::errors::{AppError, AppResult};
+
+const IGNORE_FILES: &[&str] = &[".gitignore", ".ignore"];
+
+/// Motifs compilés, répartis selon ce sur quoi ils portent
+struct CompiledRules {
+    by_name: GlobSet,
+    by_relative: GlobSet,
+    by_absolute: GlobSet,
+    is_empty: bool,
+}
+
+impl CompiledRules {
+    fn new<'a>(rules: impl Iterator<Item = &'a PathRule>) -> AppResult<Self> {
+        let mut by_name = GlobSetBuilder::new();
+        let mut by_relative = GlobSetBuilder::new();
+        let mut by_absolute = GlobSetBuilder::new();
+        let mut is_empty = true;
+
+        for rule in rules {
+            let pattern = rule.pattern.trim();
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| AppError::Validation(format!("Motif invalide '{}': {}", rule.pattern, e)))?;
+
+            if pattern.starts_with('/') {
+                by_absolute.add(glob);
+            } else if pattern.contains('/') {
+                by_relative.add(glob);
+            } else {
+                by_name.add(glob);
+            }
+            is_empty = false;
+        }
+
+        let build = |builder: GlobSetBuilder| builder.build()
+            .map_err(|e| AppError::Validation(format!("Règles invalides: {}", e)));
+
+        Ok(Self {
+            by_name: build(by_name)?,
+            by_relative: build(by_relative)?,
+            by_absolute: build(by_absolute)?,
+            is_empty,
+        })
+    }
+
+    fn matches(&self, name: &str, relative: &str, absolute: &str) -> bool {
+        !self.is_empty
+            && (self.by_name.is_match(name) || self.by_relative.is_match(relative) || self.by_absolute.is_match(absolute))
+    }
+}
+
+struct RootRules {
+    root: PathBuf,
+    includes: CompiledRules,
+    excludes: CompiledRules,
+}
+
+/// Filtre partagé par le scanner et le watcher : règles globales, règles par racine
+/// et, si demandé, fichiers `.gitignore` / `.ignore`
+pub struct PathFilter {
+    global_includes: CompiledRules,
+    global_excludes: CompiledRules,
+    /// Triées de la plus profonde à la moins profonde
+    roots: Vec<RootRules>,
+    respect_ignore_files: bool,
+    ignore_cache: Mutex<HashMap<PathBuf, Option<Arc<Gitignore>>>>,
+}
+
+impl PathFilter {
+    pub fn new(rules: &IndexingRules, roots: &[String]) -> AppResult<Self> {
+        let mut all_roots: Vec<&str> = roots.iter().map(String::as_str).collect();
+        all_roots.extend(rules.rules.iter().filter_map(|rule| rule.root.as_deref()));
+        all_roots.sort();
+        all_roots.dedup();
+
+        let mut root_rules = Vec::with_capacity(all_roots.len());
+        for root in all_roots {
+            root_rules.push(RootRules {
+                root: PathBuf::from(root),
+                includes: CompiledRules::new(select(rules, RuleKind::Include, Some(root)))?,
+                excludes: CompiledRules::new(select(rules, RuleKind::Exclude, Some(root)))?,
+            });
+        }
+        root_rules.sort_by_key(|rules| std::cmp::Reverse(rules.root.components().count()));
+
+        Ok(Self {
+            global_includes: CompiledRules::new(select(rules, RuleKind::Include, None))?,
+            global_excludes: CompiledRules::new(select(rules, RuleKind::Exclude, None))?,
+            roots: root_rules,
+            respect_ignore_files: rules.respect_ignore_files,
+            ignore_cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Vérifie uniquement l'entrée elle-même : le parcours a déjà écarté ses dossiers parents
+    pub fn is_excluded_entry(&self, path: &Path, is_dir: bool) -> bool {
+        let root = self.root_for(path);
+
+        if self.matches_excludes(path, root) {
+            return true;
+        }
+
+        if let Some(root) = root {
+            if self.is_ignored_by_files(path, &root.root, is_dir) {
+                return true;
+            }
+        }
+
+        !is_dir && !self.matches_includes(path, root)
+    }
+
+    /// Vérifie l'entrée et chacun de ses dossiers parents sous la racine (événements du watcher)
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let root = self.root_for(path);
+
+        let mut ancestor = path.parent();
+        while let Some(dir) = ancestor {
+            if root.map(|r| !dir.starts_with(&r.root) || dir == r.root).unwrap_or(false) {
+                break;
+            }
+            if self.matches_excludes(dir, root) {
+                return true;
+            }
+            ancestor = dir.parent();
+        }
+
+        self.is_excluded_entry(path, is_dir)
+    }
+
+    fn root_for(&self, path: &Path) -> Option<&RootRules> {
+        self.roots.iter().find(|rules| path.starts_with(&rules.root))
+    }
+
+    fn match_keys(path: &Path, root: Option<&RootRules>) -> (String, String, String) {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let absolute = path.to_string_lossy().replace('\\', "/");
+        let relative = root
+            .and_then(|r| path.strip_prefix(&r.root).ok())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|| absolute.clone());
+        (name, relative, absolute)
+    }
+
+    fn matches_excludes(&self, path: &Path, root: Option<&RootRules>) -> bool {
+        let (name, relative, absolute) = Self::match_keys(path, root);
+        self.global_excludes.matches(&name, &relative, &absolute)
+            || root.map(|r| r.excludes.matches(&name, &relative, &absolute)).unwrap_or(false)
+    }
+
+    /// Sans règle d'inclusion applicable, tout fichier est inclus
+    fn matches_includes(&self, path: &Path, root: Option<&RootRules>) -> bool {
+        let root_includes = root.map(|r| &r.includes).filter(|rules| !rules.is_empty);
+        if self.global_includes.is_empty && root_includes.is_none() {
+            return true;
+        }
+
+        let (name, relative, absolute) = Self::match_keys(path, root);
+        self.global_includes.matches(&name, &relative, &absolute)
+            || root_includes.map(|rules| rules.matches(&name, &relative, &absolute)).unwrap_or(false)
+    }
+
+    /// Le fichier d'ignore le plus proche l'emporte, comme pour git
+    fn is_ignored_by_files(&self, path: &Path, root: &Path, is_dir: bool) -> bool {
+        if !self.respect_ignore_files {
+            return false;
+        }
+
+        let mut ancestor = path.parent();
+        while let Some(dir) = ancestor {
+            if !dir.starts_with(root) {
+                break;
+            }
+            if let Some(matcher) = self.ignore_matcher(dir) {
+                let matched = matcher.matched_path_or_any_parents(path, is_dir);
+                if matched.is_ignore() {
+                    return true;
+                }
+                if matched.is_whitelist() {
+                    return false;
+                }
+            }
+            ancestor = dir.parent();
+        }
+
+        false
+    }
+
+    fn ignore_matcher(&self, dir: &Path) -> Option<Arc<Gitignore>> {
+        let mut cache = self.ignore_cache.lock().ok()?;
+
+        cache.entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let mut builder = GitignoreBuilder::new(dir);
+                let mut found = false;
+                for name in IGNORE_FILES {
+                    let file = dir.join(name);
+                    if file.is_file() {
+                        if let Some(e) = builder.add(&file) {
+                            tracing::warn!("Fichier d'ignore partiellement invalide {}: {}", file.display(), e);
+                        }
+                        found = true;
+                    }
+                }
+
+                if !found {
+                    return None;
+                }
+
+                builder.build()
+                    .map_err(|e| tracing::warn!("Impossible de charger les fichiers d'ignore de {}: {}", dir.display(), e))
+                    .ok()
+                    .map(Arc::new)
+            })
+            .clone()
+    }
+}
+
+fn select<'a>(rules: &'a IndexingRules, kind: RuleKind, root: Option<&'a str>) -> impl Iterator<Item = &'a PathRule> {
+    rules.rules.iter().filter(move |rule| rule.kind == kind && rule.root.as_deref() == root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn rule(pattern: &str, kind: RuleKind, root: Option<&str>) -> PathRule {
+        PathRule { pattern: pattern.to_string(), kind, root: root.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_global_and_root_rules() {
+        let rules = IndexingRules {
+            rules: vec![
+                rule("node_modules", RuleKind::Exclude, None),
+                rule("/proc/**", RuleKind::Exclude, None),
+                rule("docs/*.md", RuleKind::Include, Some("/home/me/projet")),
+                rule("*.rs", RuleKind::Include, Some("/home/me/projet")),
+            ],
+            respect_ignore_files: false,
+        };
+        let filter = PathFilter::new(&rules, &["/home/me/projet".to_string(), "/home/me/notes".to_string()]).unwrap();
+
+        assert!(filter.is_excluded(Path::new("/home/me/notes/node_modules/pkg/index.js"), false));
+        assert!(filter.is_excluded(Path::new("/proc/1/status"), false));
+        // Plus de faux positifs sur les sous-chaînes
+        assert!(!filter.is_excluded(Path::new("/home/me/notes/my.dbt.project/build/model.sql"), false));
+
+        assert!(!filter.is_excluded(Path::new("/home/me/projet/docs/guide.md"), false));
+        assert!(!filter.is_excluded(Path::new("/home/me/projet/src/main.rs"), false));
+        assert!(!filter.is_excluded(Path::new("/home/me/projet/src"), true));
+        assert!(filter.is_excluded(Path::new("/home/me/projet/docs/deep/guide.md"), false));
+        assert!(filter.is_excluded(Path::new("/home/me/projet/image.png"), false));
+        assert!(!filter.is_excluded(Path::new("/home/me/notes/image.png"), false));
+    }
+
+    #[test]
+    fn test_ignore_files_are_optional() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join(".gitignore"), "target/\n*.log\n!keep.log\n").unwrap();
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("sub/.ignore"), "secret.txt\n").unwrap();
+
+        let roots = vec![root.to_string_lossy().to_string()];
+        let mut rules = IndexingRules::default();
+
+        let filter = PathFilter::new(&rules, &roots).unwrap();
+        assert!(!filter.is_excluded(&root.join("target/debug/app"), false));
+
+        rules.respect_ignore_files = true;
+        let filter = PathFilter::new(&rules, &roots).unwrap();
+        assert!(filter.is_excluded(&root.join("target"), true));
+        assert!(filter.is_excluded(&root.join("target/debug/app"), false));
+        assert!(filter.is_excluded(&root.join("sub/trace.log"), false));
+        assert!(!filter.is_excluded(&root.join("sub/keep.log"), false));
+        assert!(filter.is_excluded(&root.join("sub/secret.txt"), false));
+        assert!(!filter.is_excluded(&root.join("secret.txt"), false));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        let rules = IndexingRules {
+            rules: vec![rule("[abc", RuleKind::Exclude, None)],
+            respect_ignore_files: false,
+        };
+
+        assert!(matches!(PathFilter::new(&rules, &[]), Err(AppError::Validation(_))));
+    }
+}
@@ -1,45 +1,77 @@
 use crate::application::events::emitters::{emit_event, emit_error_event, emit_started_event, emit_finished_event,
                                            EVENT_SCAN_STARTED, EVENT_SCAN_PROGRESS, EVENT_SCAN_COLLECTED,
                                            EVENT_SCAN_INSERT_PROGRESS, EVENT_SCAN_FINISHED, EVENT_SCAN_ERROR,
-                                           EVENT_STAT_UPDATED};
-use std::path::Path;
+                                           EVENT_STAT_UPDATED, EVENT_JOB_STARTED, EVENT_JOB_CANCELLED};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use tauri::WebviewWindow;
 use std::sync::{Arc, Mutex};
+use crate::application::jobs::{JobGuard, JobManager};
 use crate::domain::entities::file::File;
-use crate::domain::entities::scan::{ScanProgress, ScanCollected, InsertProgress, ScanFinished};
+use crate::domain::entities::job::{JobCancelled, JobKind};
+use crate::domain::entities::scan::{ScanProgress, ScanCollected, InsertProgress, ScanFinished, ScanMode, FileSignature};
 use crate::domain::entities::progress::ScanProgressTracker;
-use crate::infrastructure::filesystem::collect::collect_files_and_folders;
+use crate::infrastructure::filesystem::collect::{collect_files_and_folders, Collected};
+use crate::infrastructure::filesystem::path_filter::PathFilter;
 use crate::application::use_cases::index_content::index_content_async;
-use crate::domain::services::file_service::FileService;
-use crate::infrastructure::repository::sqlite::Db;
+use crate::infrastructure::repository::pool::RepositoryPool;
+use crate::infrastructure::repository::sqlite::to_unix_secs;
 
 const CHUNK_SIZE: usize = 500;
 
 struct ScanContext {
-    service_repository: Arc<Mutex<crate::domain::services::file_service::FileService<crate::infrastructure::repository::sqlite::Db>>>,
+    service_repository: Arc<RepositoryPool>,
     window: WebviewWindow,
     progress_tracker: Arc<Mutex<ScanProgressTracker>>,
+    job: JobGuard,
 }
 
 impl ScanContext {
     fn emit_stat_update(&self) {
-        if let Ok(repo) = self.service_repository.lock() {
-            if let Ok(stat) = repo.get_stat() {
-                emit_event(&self.window, EVENT_STAT_UPDATED, stat);
-            }
+        if let Ok(stat) = self.service_repository.read(|repo| repo.get_stat()) {
+            emit_event(&self.window, EVENT_STAT_UPDATED, stat);
         }
     }
 
     fn emit_scan_error(&self, message: String) {
         emit_error_event(&self.window, EVENT_SCAN_ERROR, message);
     }
+
+    /// Signale l'annulation ; les fichiers déjà écrits restent en base
+    fn emit_cancelled(&self, message: String) {
+        tracing::info!("Scan {} annulé: {}", self.job.id, message);
+        emit_event(&self.window, EVENT_JOB_CANCELLED, JobCancelled {
+            id: self.job.id,
+            kind: self.job.kind,
+            message: message.clone(),
+        });
+        emit_finished_event(&self.window, EVENT_SCAN_FINISHED, ScanFinished { message, ..Default::default() });
+        self.emit_stat_update();
+    }
 }
 
-fn collect_all_files(paths: &[String], context: &ScanContext) -> Result<Vec<File>, Vec<String>> {
+/// Règles d'inclusion/exclusion en vigueur pour les racines scannées
+fn load_path_filter(paths: &[String], context: &ScanContext) -> Result<PathFilter, String> {
+    let (rules, mut roots) = context.service_repository
+        .read(|repo| Ok((repo.get_indexing_rules()?, repo.get_all_paths()?)))
+        .map_err(|e| format!("Erreur lecture des règles d'indexation: {}", e))?;
+    roots.extend(paths.iter().cloned());
+
+    PathFilter::new(&rules, &roots).map_err(|e| e.to_string())
+}
+
+async fn collect_all_files(paths: &[String], filter: &PathFilter, context: &ScanContext) -> Result<Collected, Vec<String>> {
     let mut all_files = Vec::new();
+    let mut unreadable = Vec::new();
     let mut errors = Vec::new();
 
     for (path_index, path) in paths.iter().enumerate() {
+        // Pause et annulation prises en compte entre deux racines ; pendant la collecte, seule
+        // l'annulation est vérifiée (sans bloquer les threads de rayon)
+        if !context.job.checkpoint_async().await {
+            break;
+        }
+
         let path_obj = Path::new(path);
 
         if !path_obj.exists() {
@@ -52,7 +84,8 @@ fn collect_all_files(paths: &[String], context: &ScanContext) -> Result<Vec<File
         let window_clone = context.window.clone();
         let progress_tracker_clone = context.progress_tracker.clone();
 
-        let files_for_path = collect_files_and_folders(path_obj, move |current, message| {
+        let job = &context.job;
+        let collected = collect_files_and_folders(path_obj, filter, || job.is_cancelled(), move |current, message| {
             if let Ok(mut tracker) = progress_tracker_clone.lock() {
                 tracker.current_path_index = path_index;
 
@@ -74,7 +107,12 @@ fn collect_all_files(paths: &[String], context: &ScanContext) -> Result<Vec<File
             }
         });
 
-        all_files.extend(files_for_path);
+        all_files.extend(collected.files);
+        unreadable.extend(collected.unreadable);
+
+        if context.job.is_cancelled() {
+            break;
+        }
 
         if let Ok(mut tracker) = context.progress_tracker.lock() {
             tracker.next_path();
@@ -83,28 +121,85 @@ fn collect_all_files(paths: &[String], context: &ScanContext) -> Result<Vec<File
     }
 
     if errors.is_empty() {
-        Ok(all_files)
+        Ok(Collected { files: all_files, unreadable })
     } else {
         Err(errors)
     }
 }
 
-fn insert_files_in_chunks(files: &[File], context: &ScanContext) -> Result<usize, String> {
+/// Résultat de la comparaison entre l'arborescence parcourue et l'index
+struct SyncPlan {
+    added: Vec<File>,
+    updated: Vec<File>,
+    removed: Vec<String>,
+}
+
+fn plan_reconciliation(paths: &[String], collected: Collected, context: &ScanContext) -> Result<SyncPlan, String> {
+    let mut indexed: HashMap<String, FileSignature> = HashMap::new();
+    for path in paths {
+        let signatures = context.service_repository.read(|repo| repo.get_file_signatures(path))
+            .map_err(|e| format!("Erreur lecture de l'index pour {}: {}", path, e))?;
+        indexed.extend(signatures.into_iter().map(|sig| (sig.path.clone(), sig)));
+    }
+
+    let mut plan = SyncPlan { added: Vec::new(), updated: Vec::new(), removed: Vec::new() };
+    let mut seen: HashSet<String> = HashSet::with_capacity(collected.files.len());
+
+    for file in collected.files {
+        let path = file.path.to_string_lossy().to_string();
+
+        match indexed.get(&path) {
+            None => plan.added.push(file),
+            Some(signature) => {
+                if signature.size != file.size || signature.last_modified != to_unix_secs(file.last_modified) {
+                    plan.updated.push(file);
+                }
+            }
+        }
+
+        seen.insert(path);
+    }
+
+    // Ce qui se trouve sous un chemin illisible n'a pas disparu pour autant
+    plan.removed = indexed.into_keys()
+        .filter(|path| !seen.contains(path) && !is_under_any(path, &collected.unreadable))
+        .collect();
+
+    Ok(plan)
+}
+
+fn is_under_any(path: &str, roots: &[PathBuf]) -> bool {
+    let path = Path::new(path);
+    roots.iter().any(|root| path.starts_with(root))
+}
+
+fn remove_files_in_chunks(paths: &[String], context: &ScanContext) -> Result<usize, String> {
+    let mut removed = 0;
+
+    for chunk in paths.chunks(CHUNK_SIZE) {
+        let chunk = chunk.to_vec();
+        removed += context.service_repository.write(move |repo| repo.delete_files(&chunk))
+            .map_err(|e| format!("Erreur lors de la suppression: {}", e))?;
+    }
+
+    Ok(removed)
+}
+
+async fn insert_files_in_chunks(files: &[File], context: &ScanContext, mode: ScanMode) -> Result<usize, String> {
     let total_files = files.len();
     let total_chunks = (total_files + CHUNK_SIZE - 1) / CHUNK_SIZE;
     let mut insert_errors = 0;
 
     for (chunk_index, file_chunk) in files.chunks(CHUNK_SIZE).enumerate() {
-        let insert_result = {
-            match context.service_repository.lock() {
-                Ok(mut repo) => repo.insert(file_chunk.to_vec()),
-                Err(e) => {
-                    let error_msg = format!("Failed to lock repository: {}", e);
-                    context.emit_scan_error(error_msg.clone());
-                    return Err(error_msg);
-                }
-            }
-        };
+        if !context.job.checkpoint_async().await {
+            return Err(format!("Scan annulé après {} chunks sur {}", chunk_index, total_chunks));
+        }
+
+        let chunk = file_chunk.to_vec();
+        let insert_result = context.service_repository.write(move |repo| match mode {
+            ScanMode::Insert => repo.insert(chunk),
+            ScanMode::Reconcile => repo.upsert(chunk),
+        });
 
         if let Err(e) = insert_result {
             insert_errors += 1;
@@ -136,38 +231,68 @@ fn insert_files_in_chunks(files: &[File], context: &ScanContext) -> Result<usize
 pub fn scan_files_async(
     window: WebviewWindow,
     paths: Vec<String>,
-    service_repository: Arc<Mutex<FileService<Db>>>
+    service_repository: Arc<RepositoryPool>,
+    mode: ScanMode,
+    job_manager: Arc<JobManager>,
 ) {
     tauri::async_runtime::spawn(async move {
 
         if paths.is_empty() {
             emit_finished_event(&window, EVENT_SCAN_FINISHED, ScanFinished {
-                total: 0,
                 message: "Aucun chemin à scanner".to_string(),
+                ..Default::default()
             });
             return;
         }
 
+        let job = match job_manager.start(JobKind::Scan) {
+            Ok(job) => job,
+            Err(e) => {
+                emit_error_event(&window, EVENT_SCAN_ERROR, e.to_string());
+                return;
+            }
+        };
+        emit_event(&window, EVENT_JOB_STARTED, job.info());
+
         let progress_tracker = Arc::new(Mutex::new(ScanProgressTracker::new(paths.len())));
         let context = ScanContext {
             service_repository,
             window: window.clone(),
             progress_tracker,
+            job,
         };
 
         emit_started_event(&window, EVENT_SCAN_STARTED);
 
+        let filter = match load_path_filter(&paths, &context) {
+            Ok(filter) => filter,
+            Err(message) => {
+                context.emit_scan_error(message.clone());
+                emit_finished_event(&window, EVENT_SCAN_FINISHED, ScanFinished { message, ..Default::default() });
+                return;
+            }
+        };
+
         // Phase 1: Collecte des fichiers
-        let all_files = match collect_all_files(&paths, &context) {
-            Ok(files) => files,
+        let collected = match collect_all_files(&paths, &filter, &context).await {
+            Ok(collected) => collected,
             Err(errors) => {
                 let message = format!("Erreurs lors de la collecte: {}", errors.join(", "));
-                emit_finished_event(&window, EVENT_SCAN_FINISHED, ScanFinished { total: 0, message });
+                emit_finished_event(&window, EVENT_SCAN_FINISHED, ScanFinished { message, ..Default::default() });
                 return;
             }
         };
 
-        let total_files = all_files.len();
+        // Une collecte partielle ne doit pas être réconciliée : les fichiers non vus seraient supprimés
+        if context.job.is_cancelled() {
+            context.emit_cancelled(format!("Scan annulé pendant la collecte ({} fichiers trouvés)", collected.files.len()));
+            return;
+        }
+
+        if !collected.unreadable.is_empty() {
+            tracing::warn!("{} chemins illisibles conservés tels quels dans l'index", collected.unreadable.len());
+        }
+        let total_files = collected.files.len();
 
         // Émission de l'événement de collecte terminée
         emit_event(&window, EVENT_SCAN_COLLECTED, ScanCollected {
@@ -175,37 +300,87 @@ pub fn scan_files_async(
             message: format!("Collecte terminée: {} fichiers trouvés", total_files),
         });
 
-        if total_files == 0 {
+        if total_files == 0 && mode == ScanMode::Insert {
             emit_finished_event(&window, EVENT_SCAN_FINISHED, ScanFinished {
-                total: 0,
                 message: "Aucun fichier trouvé".to_string(),
+                ..Default::default()
             });
             return;
         }
 
-        // Phase 2: Insertion des fichiers en base
-        let success_count = match insert_files_in_chunks(&all_files, &context) {
+        // Phase 2: Comparaison avec l'index (mode réconciliation uniquement)
+        let (to_write, removed_paths, added_count) = match mode {
+            ScanMode::Insert => (collected.files, Vec::new(), total_files),
+            ScanMode::Reconcile => match plan_reconciliation(&paths, collected, &context) {
+                Ok(plan) => {
+                    tracing::info!(
+                        "Réconciliation: {} ajouts, {} mises à jour, {} suppressions",
+                        plan.added.len(), plan.updated.len(), plan.removed.len()
+                    );
+                    let added_count = plan.added.len();
+                    let mut to_write = plan.added;
+                    to_write.extend(plan.updated);
+                    (to_write, plan.removed, added_count)
+                }
+                Err(e) => {
+                    context.emit_scan_error(e.clone());
+                    emit_finished_event(&window, EVENT_SCAN_FINISHED, ScanFinished {
+                        message: format!("Erreur lors de la comparaison avec l'index: {}", e),
+                        ..Default::default()
+                    });
+                    return;
+                }
+            },
+        };
+
+        // Phase 3: Écriture des fichiers en base
+        let success_count = if to_write.is_empty() {
+            0
+        } else {
+            match insert_files_in_chunks(&to_write, &context, mode).await {
+                Ok(count) => count,
+                Err(e) if context.job.is_cancelled() => {
+                    context.emit_cancelled(e);
+                    return;
+                }
+                Err(e) => {
+                    emit_finished_event(&window, EVENT_SCAN_FINISHED, ScanFinished {
+                        message: format!("Erreur lors de l'insertion: {}", e),
+                        ..Default::default()
+                    });
+                    return;
+                }
+            }
+        };
+
+        let removed = match remove_files_in_chunks(&removed_paths, &context) {
             Ok(count) => count,
             Err(e) => {
-                emit_finished_event(&window, EVENT_SCAN_FINISHED, ScanFinished {
-                    total: 0,
-                    message: format!("Erreur lors de l'insertion: {}", e),
-                });
-                return;
+                context.emit_scan_error(e.clone());
+                0
             }
         };
 
+        let added = added_count.min(success_count);
+        let updated = success_count - added;
+
         // Finalisation
         emit_finished_event(&window, EVENT_SCAN_FINISHED, ScanFinished {
             total: success_count,
-            message: format!("Synchronisation terminée avec succès: {} fichiers traités", success_count),
+            message: format!(
+                "Synchronisation terminée avec succès: {} ajoutés, {} mis à jour, {} supprimés",
+                added, updated, removed
+            ),
+            added,
+            updated,
+            removed,
         });
 
         context.emit_stat_update();
 
-        // Phase 3: Démarrer l'indexation du contenu automatiquement
+        // Phase 4: Démarrer l'indexation du contenu automatiquement
         tracing::info!("Démarrage de l'indexation du contenu automatique");
         let service_repo = context.service_repository.clone();
-        index_content_async(window, service_repo);
+        index_content_async(window, service_repo, job_manager);
     });
 }
\ No newline at end of file
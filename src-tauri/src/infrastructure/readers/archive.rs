@@ -0,0 +1,505 @@
+use crate::domain::entities::content::ENTRY_SEPARATOR;
+use crate::infrastructure::readers::office::open_archive;
+use crate::shared::errors::{AppError, AppResult};
+use flate2::read::GzDecoder;
+use std::cell::Cell;
+use std::fs;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Éléments lus au plus par archive
+const MAX_MEMBERS: usize = 10_000;
+/// Au-delà de ce taux de compression, un élément est tenu pour une bombe de décompression
+const MAX_COMPRESSION_RATIO: u64 = 1000;
+// Les petits fichiers très répétitifs (zéros, espaces) se compressent légitimement beaucoup
+const RATIO_CHECK_MIN_SIZE: u64 = 1024 * 1024;
+
+const TAR_BLOCK: usize = 512;
+/// Taille maximale d'un nom long GNU ou d'un en-tête pax
+const MAX_TAR_EXTENSION: u64 = 64 * 1024;
+/// Durée de conservation des éléments extraits pour être ouverts
+const OPENING_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+static SCRATCH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Reçoit chaque fichier de l'archive et son contenu décompressé, `None` s'il est illisible
+type MemberVisitor<'a> = dyn FnMut(&ArchiveMember, Option<&mut dyn Read>) -> AppResult<()> + 'a;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+    /// Fichier unique compressé (`rapport.txt.gz`)
+    Gzip,
+}
+
+impl ArchiveKind {
+    /// Format d'après le nom du fichier
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".gz") {
+            Some(Self::Gzip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Fichier contenu dans une archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveMember {
+    /// Chemin dans l'archive, séparé par `/`
+    pub path: String,
+    /// Taille décompressée annoncée par l'archive
+    pub size: u64,
+    /// Date en secondes depuis l'époque Unix
+    pub modified: Option<i64>,
+}
+
+impl ArchiveMember {
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Octets qu'il reste permis de décompresser, partagés par une archive et celles qu'elle contient
+pub struct SizeBudget {
+    remaining: Cell<u64>,
+    exhausted: Cell<bool>,
+}
+
+impl SizeBudget {
+    pub fn new(limit: u64) -> Self {
+        Self { remaining: Cell::new(limit), exhausted: Cell::new(false) }
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted.get()
+    }
+
+    fn consume(&self, bytes: usize) -> io::Result<()> {
+        let bytes = bytes as u64;
+        if bytes > self.remaining.get() {
+            self.remaining.set(0);
+            self.exhausted.set(true);
+            return Err(io::Error::other("taille décompressée maximale atteinte"));
+        }
+        self.remaining.set(self.remaining.get() - bytes);
+        Ok(())
+    }
+}
+
+/// Lecture décomptée du budget : c'est le volume réellement décompressé qui compte, pas celui annoncé
+struct Budgeted<'a, R> {
+    inner: R,
+    budget: &'a SizeBudget,
+}
+
+impl<R: Read> Read for Budgeted<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.budget.consume(read)?;
+        Ok(read)
+    }
+}
+
+/// Dossier temporaire supprimé avec son contenu quand il n'est plus utilisé
+pub struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    pub fn new() -> AppResult<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "fast-search-{}-{}",
+            std::process::id(),
+            SCRATCH_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.0) {
+            tracing::debug!("Dossier temporaire non supprimé {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+/// Parcourt les fichiers de l'archive (les dossiers sont ignorés). `visit` reçoit le contenu décompressé
+/// de chacun, ou `None` s'il est illisible : chiffré, méthode non prise en charge ou taux de compression suspect.
+pub fn for_each_member<F>(path: &Path, kind: ArchiveKind, budget: &SizeBudget, mut visit: F) -> AppResult<()>
+where
+    F: FnMut(&ArchiveMember, Option<&mut dyn Read>) -> AppResult<()>,
+{
+    match kind {
+        ArchiveKind::Zip => for_each_zip_member(path, budget, &mut visit),
+        ArchiveKind::Tar => {
+            let stream = BufReader::new(fs::File::open(path)?);
+            for_each_tar_member(Budgeted { inner: stream, budget }, &mut visit)
+        }
+        ArchiveKind::TarGz => {
+            let stream = GzDecoder::new(BufReader::new(fs::File::open(path)?));
+            for_each_tar_member(Budgeted { inner: stream, budget }, &mut visit)
+        }
+        ArchiveKind::Gzip => visit_gzip_member(path, budget, &mut visit),
+    }
+}
+
+/// Extrait un élément dans `destination` et retourne son chemin. `member` est relatif à l'archive ;
+/// pour une archive imbriquée, il se poursuit après `!/` (`docs.tar!/rapport.pdf`).
+/// Si l'élément intermédiaire n'est pas une archive (boîte mbox), c'est lui qui est retourné.
+pub fn extract_member(archive: &Path, member: &str, destination: &Path, budget: &SizeBudget) -> AppResult<PathBuf> {
+    let kind = ArchiveKind::detect(archive)
+        .ok_or_else(|| AppError::Unsupported(format!("Format d'archive non pris en charge: {}", archive.display())))?;
+    let (inner, rest) = match member.split_once(ENTRY_SEPARATOR) {
+        Some((inner, rest)) => (inner, Some(rest)),
+        None => (member, None),
+    };
+
+    let mut extracted: Option<PathBuf> = None;
+    for_each_member(archive, kind, budget, |candidate, data| {
+        if extracted.is_some() || candidate.path != inner {
+            return Ok(());
+        }
+        let data = data.ok_or_else(|| AppError::Unsupported(format!("Élément illisible: {}", inner)))?;
+        fs::create_dir_all(destination)?;
+        let target = destination.join(candidate.name());
+        io::copy(data, &mut fs::File::create(&target)?)?;
+        extracted = Some(target);
+        Ok(())
+    })?;
+
+    let extracted = extracted
+        .ok_or_else(|| AppError::NotFound(format!("{} absent de l'archive {}", inner, archive.display())))?;
+    match rest {
+        Some(rest) if ArchiveKind::detect(&extracted).is_some() => {
+            extract_member(&extracted, rest, &destination.join("imbriquée"), budget)
+        }
+        _ => Ok(extracted),
+    }
+}
+
+/// Extrait un élément pour l'ouvrir avec l'application associée. Le dossier, propre à l'élément,
+/// est conservé : l'application peut encore lire le fichier après le retour de la commande.
+/// Les dossiers des ouvertures précédentes sont supprimés au-delà de `OPENING_TTL`.
+pub fn extract_for_opening(archive: &Path, member: &str, max_size: u64) -> AppResult<PathBuf> {
+    let root = std::env::temp_dir().join("fast-search-ouverture");
+    remove_stale_openings(&root, OPENING_TTL);
+
+    let key = format!("{}{}{}", archive.display(), ENTRY_SEPARATOR, member);
+    let destination = root.join(format!("{:016x}", xxh3_64(key.as_bytes())));
+    extract_member(archive, member, &destination, &SizeBudget::new(max_size))
+}
+
+fn remove_stale_openings(root: &Path, ttl: Duration) {
+    let Ok(dirs) = fs::read_dir(root) else { return };
+    for dir in dirs.flatten() {
+        let stale = dir.metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= ttl);
+        if stale {
+            if let Err(e) = fs::remove_dir_all(dir.path()) {
+                tracing::debug!("Dossier d'ouverture non supprimé {}: {}", dir.path().display(), e);
+            }
+        }
+    }
+}
+
+fn for_each_zip_member(
+    path: &Path,
+    budget: &SizeBudget,
+    visit: &mut MemberVisitor<'_>,
+) -> AppResult<()> {
+    let mut archive = open_archive(path)?;
+    if archive.len() > MAX_MEMBERS {
+        tracing::warn!("Archive {} : seuls les {} premiers éléments sont lus", path.display(), MAX_MEMBERS);
+    }
+
+    for index in 0..archive.len().min(MAX_MEMBERS) {
+        let (member, readable) = {
+            let entry = archive.by_index_raw(index)
+                .map_err(|e| AppError::Validation(format!("Archive invalide {}: {}", path.display(), e)))?;
+            let Some(member_path) = normalize_path(entry.name()).filter(|_| !entry.is_dir()) else { continue };
+            let suspicious = entry.size() >= RATIO_CHECK_MIN_SIZE
+                && entry.size() / entry.compressed_size().max(1) > MAX_COMPRESSION_RATIO;
+            if suspicious {
+                tracing::warn!("Élément ignoré, taux de compression suspect: {} dans {}", member_path, path.display());
+            }
+            let member = ArchiveMember {
+                path: member_path,
+                size: entry.size(),
+                modified: entry.last_modified().and_then(zip_time),
+            };
+            (member, !entry.encrypted() && !suspicious)
+        };
+
+        if !readable {
+            visit(&member, None)?;
+            continue;
+        }
+        match archive.by_index(index) {
+            Ok(entry) => visit(&member, Some(&mut Budgeted { inner: entry, budget }))?,
+            Err(e) => {
+                tracing::debug!("Élément illisible {} dans {}: {}", member.path, path.display(), e);
+                visit(&member, None)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn for_each_tar_member<R: Read>(
+    mut stream: R,
+    visit: &mut MemberVisitor<'_>,
+) -> AppResult<()> {
+    let mut header = [0u8; TAR_BLOCK];
+    // Nom long annoncé par l'en-tête précédent (GNU `L` ou pax `x`)
+    let mut long_path: Option<String> = None;
+    let mut count = 0;
+
+    while read_block(&mut stream, &mut header)? {
+        // Deux blocs nuls marquent la fin de l'archive
+        if header.iter().all(|byte| *byte == 0) {
+            break;
+        }
+        if tar_number(&header[148..156]) != tar_checksum(&header) {
+            return Err(AppError::Validation("En-tête tar invalide".to_string()));
+        }
+        if count == MAX_MEMBERS {
+            tracing::warn!("Archive tar : seuls les {} premiers éléments sont lus", MAX_MEMBERS);
+            break;
+        }
+
+        let size = tar_number(&header[124..136]);
+        let mut data = (&mut stream).take(size);
+        match header[156] {
+            b'L' => long_path = Some(read_tar_extension(&mut data)?),
+            b'x' => long_path = pax_path(&read_tar_extension(&mut data)?).or(long_path),
+            // Fichier ordinaire (`\0` dans les anciennes archives, `7` fichier contigu)
+            b'0' | b'\0' | b'7' => {
+                let name = long_path.take().unwrap_or_else(|| tar_path(&header));
+                if let Some(path) = normalize_path(&name) {
+                    count += 1;
+                    let modified = Some(tar_number(&header[136..148]) as i64).filter(|time| *time > 0);
+                    visit(&ArchiveMember { path, size, modified }, Some(&mut data))?;
+                }
+            }
+            _ => long_path = None,
+        }
+
+        // Reste de l'élément non lu, puis remplissage jusqu'au bloc suivant
+        io::copy(&mut data, &mut io::sink())?;
+        let padding = (TAR_BLOCK as u64 - size % TAR_BLOCK as u64) % TAR_BLOCK as u64;
+        io::copy(&mut (&mut stream).take(padding), &mut io::sink())?;
+    }
+    Ok(())
+}
+
+fn visit_gzip_member(
+    path: &Path,
+    budget: &SizeBudget,
+    visit: &mut MemberVisitor<'_>,
+) -> AppResult<()> {
+    let mut file = fs::File::open(path)?;
+    // ISIZE : taille décompressée modulo 2^32, dans les 4 derniers octets
+    let mut trailer = [0u8; 4];
+    file.seek(SeekFrom::End(-4))?;
+    file.read_exact(&mut trailer)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut decoder = GzDecoder::new(BufReader::new(file));
+    let header = decoder.header();
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let name = header.and_then(|header| header.filename())
+        .and_then(|name| normalize_path(&String::from_utf8_lossy(name)))
+        .map(|name| name.rsplit('/').next().unwrap_or_default().to_string())
+        .unwrap_or(stem);
+    let modified = header.map(|header| header.mtime() as i64).filter(|time| *time > 0);
+
+    let member = ArchiveMember { path: name, size: u32::from_le_bytes(trailer) as u64, modified };
+    visit(&member, Some(&mut Budgeted { inner: &mut decoder, budget }))
+}
+
+/// Lit un bloc de 512 octets, `false` en fin de flux
+fn read_block(stream: &mut impl Read, block: &mut [u8; TAR_BLOCK]) -> AppResult<bool> {
+    let mut filled = 0;
+    while filled < TAR_BLOCK {
+        match stream.read(&mut block[filled..])? {
+            0 => break,
+            read => filled += read,
+        }
+    }
+    match filled {
+        0 => Ok(false),
+        TAR_BLOCK => Ok(true),
+        _ => Err(AppError::Validation("Archive tar tronquée".to_string())),
+    }
+}
+
+/// Champ numérique : octal terminé par un espace ou un nul, ou base 256 pour les grandes valeurs (bit de poids fort)
+fn tar_number(field: &[u8]) -> u64 {
+    if field.first().is_some_and(|byte| byte & 0x80 != 0) {
+        return field.iter().enumerate()
+            .fold(0u64, |value, (index, byte)| {
+                let byte = if index == 0 { byte & 0x7f } else { *byte };
+                value.saturating_mul(256).saturating_add(byte as u64)
+            });
+    }
+    let digits = String::from_utf8_lossy(field);
+    u64::from_str_radix(digits.trim_matches(|c: char| c == '\0' || c == ' '), 8).unwrap_or(0)
+}
+
+/// Somme des octets de l'en-tête, le champ de contrôle compté comme des espaces
+fn tar_checksum(header: &[u8; TAR_BLOCK]) -> u64 {
+    header.iter().enumerate()
+        .map(|(index, byte)| if (148..156).contains(&index) { b' ' as u64 } else { *byte as u64 })
+        .sum()
+}
+
+/// Nom de l'en-tête, préfixé du champ `prefix` des archives ustar
+fn tar_path(header: &[u8; TAR_BLOCK]) -> String {
+    let name = nul_terminated(&header[0..100]);
+    if &header[257..262] == b"ustar" {
+        let prefix = nul_terminated(&header[345..500]);
+        if !prefix.is_empty() {
+            return format!("{}/{}", prefix, name);
+        }
+    }
+    name
+}
+
+fn read_tar_extension(data: &mut impl Read) -> AppResult<String> {
+    let mut bytes = Vec::new();
+    data.take(MAX_TAR_EXTENSION).read_to_end(&mut bytes)?;
+    Ok(nul_terminated(&bytes))
+}
+
+/// Enregistrements pax `<longueur> <clé>=<valeur>\n`
+fn pax_path(records: &str) -> Option<String> {
+    records.lines()
+        .filter_map(|record| record.split_once(' ').map(|(_, pair)| pair))
+        .find_map(|pair| pair.strip_prefix("path=").map(str::to_string))
+}
+
+fn nul_terminated(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|byte| *byte == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Chemin relatif sans `.` ni `..` : les chemins absolus ou remontants restent dans l'archive
+fn normalize_path(path: &str) -> Option<String> {
+    let parts: Vec<&str> = path.split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Les dates zip sont en heure locale sans fuseau : elles sont prises telles quelles
+fn zip_time(time: zip::DateTime) -> Option<i64> {
+    chrono::NaiveDate::from_ymd_opt(time.year().into(), time.month().into(), time.day().into())?
+        .and_hms_opt(time.hour().into(), time.minute().into(), time.second().into())
+        .map(|time| time.and_utc().timestamp())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    /// Archive tar ustar ; les noms de plus de 100 caractères passent par un en-tête GNU `L`
+    pub fn tar_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut tar = Vec::new();
+        for (name, content) in entries {
+            if name.len() > 100 {
+                let long_name = format!("{}\0", name);
+                push_tar_entry(&mut tar, "././@LongLink", b'L', long_name.as_bytes());
+            }
+            push_tar_entry(&mut tar, &name[..name.len().min(100)], b'0', content);
+        }
+        tar.extend([0u8; TAR_BLOCK * 2]);
+        tar
+    }
+
+    fn push_tar_entry(tar: &mut Vec<u8>, name: &str, kind: u8, content: &[u8]) {
+        let mut header = [0u8; TAR_BLOCK];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..108].copy_from_slice(b"0000644\0");
+        header[108..116].copy_from_slice(b"0000000\0");
+        header[116..124].copy_from_slice(b"0000000\0");
+        header[124..136].copy_from_slice(format!("{:011o}\0", content.len()).as_bytes());
+        header[136..148].copy_from_slice(format!("{:011o}\0", 1_772_445_600).as_bytes());
+        header[156] = kind;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        let checksum = format!("{:06o}\0 ", tar_checksum(&header));
+        header[148..156].copy_from_slice(checksum.as_bytes());
+
+        tar.extend(header);
+        tar.extend(content);
+        tar.resize(tar.len().div_ceil(TAR_BLOCK) * TAR_BLOCK, 0);
+    }
+
+    fn members(path: &Path, budget: &SizeBudget) -> AppResult<Vec<(ArchiveMember, String)>> {
+        let mut members = Vec::new();
+        for_each_member(path, ArchiveKind::detect(path).unwrap(), budget, |member, data| {
+            let mut content = String::new();
+            if let Some(data) = data {
+                data.read_to_string(&mut content)?;
+            }
+            members.push((member.clone(), content));
+            Ok(())
+        })?;
+        Ok(members)
+    }
+
+    #[test]
+    fn test_tar_gz_members_and_size_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let long_path = format!("projets/{}/notes.txt", "sous-dossier-au-nom-tres-long".repeat(4));
+        let tar = tar_bytes(&[("../../etc/rapport.txt", b"bilan annuel"), (&long_path, b"notes")]);
+        let path = dir.path().join("sauvegarde.tar.gz");
+        let mut encoder = GzEncoder::new(fs::File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(&tar).unwrap();
+        encoder.finish().unwrap();
+
+        let listed = members(&path, &SizeBudget::new(1024 * 1024)).unwrap();
+
+        // Les composants `..` ne sortent pas de l'archive
+        assert_eq!(listed[0].0, ArchiveMember { path: "etc/rapport.txt".to_string(), size: 12, modified: Some(1_772_445_600) });
+        assert_eq!(listed[0].1, "bilan annuel");
+        assert_eq!((listed[1].0.path.as_str(), listed[1].1.as_str()), (long_path.as_str(), "notes"));
+
+        let extracted = extract_member(&path, "etc/rapport.txt", &dir.path().join("ouverture"), &SizeBudget::new(1024 * 1024)).unwrap();
+        assert_eq!(fs::read_to_string(extracted).unwrap(), "bilan annuel");
+
+        // Le volume décompressé est compté, quelle que soit la taille annoncée
+        let budget = SizeBudget::new(1024);
+        assert!(members(&path, &budget).is_err());
+        assert!(budget.is_exhausted());
+
+        // Les dossiers d'ouverture ne sont supprimés qu'une fois expirés
+        remove_stale_openings(dir.path(), OPENING_TTL);
+        assert!(dir.path().join("ouverture").exists());
+        remove_stale_openings(dir.path(), Duration::ZERO);
+        assert!(!dir.path().join("ouverture").exists());
+    }
+}
@@ -1,9 +1,10 @@
 use crate::domain::ports::reader::Reader;
+use crate::domain::entities::content::ExtractedContent;
 use crate::domain::entities::file::File;
-use crate::shared::errors::{AppError, AppResult};
-use std::fs;
-use std::io::Read;
-use std::path::Path;
+use crate::infrastructure::readers::encoding::{read_text_file, DecodedText};
+use crate::shared::errors::AppResult;
+
+const MAX_CODE_SIZE: u64 = 5 * 1024 * 1024;
 
 pub struct CodeReader;
 
@@ -30,34 +31,21 @@ impl CodeReader {
             .collect::<Vec<_>>()
             .join(" ")
     }
+
+    fn read_source(&self, file: &File) -> AppResult<DecodedText> {
+        read_text_file(&file.path, MAX_CODE_SIZE)
+    }
 }
 
 impl Reader for CodeReader {
     fn read(&self, file: &File) -> AppResult<String> {
-        let file_path = Path::new(&file.path);
-        
-        if !file_path.exists() || !file_path.is_file() {
-            return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", file)));
-        }
-
-        // Vérifier la taille du fichier (limite à 5MB pour les fichiers de code)
-        let metadata = fs::metadata(file_path)
-            .map_err(|e| AppError::NotFound(format!("Erreur lors de la lecture du fichier: {}", e)))?;
-        
-        if metadata.len() > 5 * 1024 * 1024 {
-            return Err(AppError::NotFound(format!("Fichier trop volumineux: {} bytes", metadata.len())));
-        }
-
-        let mut file = fs::File::open(file_path)
-            .map_err(|e| AppError::NotFound(format!("Erreur lors de la lecture du fichier: {}", e)))?;
-        
-        let mut content = String::new();
-        file.read_to_string(&mut content)
-            .map_err(|e| AppError::NotFound(format!("Erreur lors de la lecture du fichier: {}", e)))?;
+        let source = self.read_source(file)?;
+        Ok(self.clean_code_content(&source.text))
+    }
 
-        let cleaned_content = self.clean_code_content(&content);
-        
-        Ok(cleaned_content)
+    fn extract(&self, file: &File) -> AppResult<ExtractedContent> {
+        let source = self.read_source(file)?;
+        Ok(ExtractedContent::from_source(self.clean_code_content(&source.text), &source.text, Some(source.encoding.to_string())))
     }
 }
 
@@ -1,9 +1,10 @@
 use crate::domain::ports::reader::Reader;
-use std::fs;
-use std::io::Read;
-use std::path::Path;
+use crate::domain::entities::content::ExtractedContent;
 use crate::domain::entities::file::File;
-use crate::shared::errors::{AppError, AppResult};
+use crate::infrastructure::readers::encoding::{read_text_file, DecodedText};
+use crate::shared::errors::AppResult;
+
+const MAX_CSV_SIZE: u64 = 10 * 1024 * 1024;
 
 pub struct CsvReader;
 
@@ -28,35 +29,21 @@ impl CsvReader {
             .collect::<Vec<_>>()
             .join(" ")
     }
+
+    fn read_source(&self, file: &File) -> AppResult<DecodedText> {
+        read_text_file(&file.path, MAX_CSV_SIZE)
+    }
 }
 
 impl Reader for CsvReader {
     fn read(&self, file: &File) -> AppResult<String> {
-        let file_path = Path::new(&file.path);
-        
-        if !file_path.exists() || !file_path.is_file() {
-            return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", file)));
-        }
-
-        // Vérifier la taille du fichier (limite à 10MB pour les fichiers CSV)
-        let metadata = fs::metadata(file_path)
-            .map_err(|e| AppError::FileSystem(e))?;
-        
-        if metadata.len() > 10 * 1024 * 1024 {
-            return Err(AppError::Validation(format!("Le fichier est trop volumineux: {} bytes", metadata.len())));
-        }
-
-        let mut file = fs::File::open(file_path)
-            .map_err(|e| AppError::FileSystem(e))?;
-        
-        let mut content = String::new();
-        file.read_to_string(&mut content)
-            .map_err(|e| AppError::FileSystem(e))?;
+        let source = self.read_source(file)?;
+        Ok(self.parse_csv_content(&source.text))
+    }
 
-        // Parser le contenu CSV
-        let parsed_content = self.parse_csv_content(&content);
-        
-        Ok(parsed_content)
+    fn extract(&self, file: &File) -> AppResult<ExtractedContent> {
+        let source = self.read_source(file)?;
+        Ok(ExtractedContent::from_source(self.parse_csv_content(&source.text), &source.text, Some(source.encoding.to_string())))
     }
 }
 
@@ -0,0 +1,149 @@
+use crate::shared::errors::{AppError, AppResult};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::fs;
+use std::path::Path;
+
+// Octets examinés pour les heuristiques (binaire, UTF-16 sans BOM)
+const SNIFF_SIZE: usize = 8 * 1024;
+// Au-delà de cette proportion de caractères de contrôle, le contenu est considéré binaire
+const MAX_CONTROL_RATIO: f64 = 0.1;
+
+/// Texte transcodé en UTF-8 et encodage d'origine détecté
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: &'static str,
+}
+
+/// Lit un fichier texte quel que soit son encodage
+pub fn read_text_file(path: &Path, max_size: u64) -> AppResult<DecodedText> {
+    if !path.exists() || !path.is_file() {
+        return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", path.display())));
+    }
+
+    let metadata = fs::metadata(path)?;
+    if metadata.len() > max_size {
+        return Err(AppError::Validation(format!("Fichier trop volumineux: {} bytes", metadata.len())));
+    }
+
+    let bytes = fs::read(path)?;
+    decode(&bytes).map_err(|e| match e {
+        AppError::Unsupported(reason) => AppError::Unsupported(format!("{}: {}", path.display(), reason)),
+        e => e,
+    })
+}
+
+/// BOM d'abord, puis UTF-16 sans BOM, rejet des binaires, UTF-8 strict et enfin détection statistique
+pub fn decode(bytes: &[u8]) -> AppResult<DecodedText> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        return Ok(decode_with(encoding, &bytes[bom_length..]));
+    }
+
+    let sample = &bytes[..bytes.len().min(SNIFF_SIZE)];
+
+    if let Some(encoding) = sniff_utf16(sample) {
+        return Ok(decode_with(encoding, bytes));
+    }
+
+    if looks_binary(sample) {
+        return Err(AppError::Unsupported("contenu binaire".to_string()));
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(DecodedText { text: text.to_string(), encoding: UTF_8.name() });
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, true);
+    Ok(decode_with(encoding, bytes))
+}
+
+fn decode_with(encoding: &'static Encoding, bytes: &[u8]) -> DecodedText {
+    let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+    if had_errors {
+        tracing::debug!("Caractères invalides remplacés lors du décodage {}", encoding.name());
+    }
+    DecodedText { text: text.into_owned(), encoding: encoding.name() }
+}
+
+/// Texte UTF-16 sans BOM : un octet sur deux est nul pour les caractères latins
+fn sniff_utf16(sample: &[u8]) -> Option<&'static Encoding> {
+    if sample.len() < 4 {
+        return None;
+    }
+
+    let pairs = sample.len() / 2;
+    let even_zeros = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+    let odd_zeros = sample.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+
+    let mostly = |count: usize| count as f64 > pairs as f64 * 0.6;
+    let rarely = |count: usize| (count as f64) < pairs as f64 * 0.05;
+
+    if mostly(odd_zeros) && rarely(even_zeros) {
+        Some(UTF_16LE)
+    } else if mostly(even_zeros) && rarely(odd_zeros) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Octet nul ou trop de caractères de contrôle : exécutable, image, archive...
+fn looks_binary(sample: &[u8]) -> bool {
+    if sample.is_empty() {
+        return false;
+    }
+    if sample.contains(&0) {
+        return true;
+    }
+
+    let controls = sample.iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    controls as f64 / sample.len() as f64 > MAX_CONTROL_RATIO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str, with_bom: bool) -> Vec<u8> {
+        let mut bytes = if with_bom { vec![0xFF, 0xFE] } else { Vec::new() };
+        bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        bytes
+    }
+
+    #[test]
+    fn test_legacy_encodings_are_transcoded() {
+        let french = "Le garçon a mangé une crème brûlée à la fête de Noël, c'était très réussi.";
+
+        let (windows_1252, _, _) = encoding_rs::WINDOWS_1252.encode(french);
+        let decoded = decode(&windows_1252).unwrap();
+        assert_eq!(decoded.text, french);
+        assert_eq!(decoded.encoding, "windows-1252");
+
+        let decoded = decode(&utf16le(french, true)).unwrap();
+        assert_eq!((decoded.text.as_str(), decoded.encoding), (french, "UTF-16LE"));
+
+        let decoded = decode(&utf16le(french, false)).unwrap();
+        assert_eq!((decoded.text.as_str(), decoded.encoding), (french, "UTF-16LE"));
+
+        let mut with_bom = vec![0xEF, 0xBB, 0xBF];
+        with_bom.extend_from_slice(french.as_bytes());
+        let decoded = decode(&with_bom).unwrap();
+        assert_eq!((decoded.text.as_str(), decoded.encoding), (french, "UTF-8"));
+    }
+
+    #[test]
+    fn test_binary_content_is_unsupported() {
+        let elf = [0x7F, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0x3E, 0];
+        assert!(matches!(decode(&elf), Err(AppError::Unsupported(_))));
+
+        let controls: Vec<u8> = (1u8..0x20).cycle().take(200).collect();
+        assert!(matches!(decode(&controls), Err(AppError::Unsupported(_))));
+
+        assert_eq!(decode(b"").unwrap().text, "");
+    }
+}
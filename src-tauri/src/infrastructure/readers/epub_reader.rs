@@ -0,0 +1,240 @@
+use crate::domain::ports::reader::Reader;
+use crate::domain::entities::file::File;
+use crate::infrastructure::readers::html_reader::html_to_text;
+use crate::infrastructure::readers::office::{attribute, clean_extracted_text, open_archive, push_text, read_entry, read_xml_event, resolve_target};
+use crate::shared::errors::{AppError, AppResult};
+use quick_xml::events::Event;
+use quick_xml::Reader as XmlReader;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const MAX_LINES: usize = 20000;
+const MAX_CHARS: usize = 200000;
+const MAX_FILE_SIZE: u64 = 200 * 1024 * 1024;
+
+/// Description du livre (fichier OPF) : métadonnées et chapitres dans l'ordre de lecture
+#[derive(Default)]
+struct Package {
+    title: String,
+    creators: Vec<String>,
+    /// Chemins dans l'archive des documents du `spine`
+    chapters: Vec<String>,
+}
+
+/// Livres numériques EPUB : titre, auteurs, puis texte des chapitres dans l'ordre du `spine`
+pub struct EpubReader;
+
+impl EpubReader {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Reader for EpubReader {
+    fn read(&self, file: &File) -> AppResult<String> {
+        let file_path = Path::new(&file.path);
+
+        if !file_path.exists() || !file_path.is_file() {
+            return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", file)));
+        }
+
+        let metadata = fs::metadata(file_path)?;
+        if metadata.len() > MAX_FILE_SIZE {
+            return Err(AppError::Validation(format!("Livre EPUB trop volumineux: {} bytes", metadata.len())));
+        }
+
+        let mut archive = open_archive(file_path)?;
+        let container = read_entry(&mut archive, "META-INF/container.xml")?
+            .ok_or_else(|| AppError::Validation(format!("META-INF/container.xml absent: {}", file.path.display())))?;
+        let package_path = rootfile(&container)?
+            .ok_or_else(|| AppError::Validation(format!("Aucun fichier OPF déclaré: {}", file.path.display())))?;
+        let opf = read_entry(&mut archive, &package_path)?
+            .ok_or_else(|| AppError::Validation(format!("{} absent: {}", package_path, file.path.display())))?;
+        let folder = package_path.rsplit_once('/').map(|(folder, _)| folder).unwrap_or_default();
+        let package = read_package(&opf, folder)?;
+
+        // Les chapitres protégés par DRM sont illisibles ; l'obfuscation des polices n'est pas concernée
+        let encrypted = match read_entry(&mut archive, "META-INF/encryption.xml")? {
+            Some(xml) => encrypted_entries(&xml)?,
+            None => Vec::new(),
+        };
+        if !package.chapters.is_empty() && package.chapters.iter().all(|chapter| encrypted.contains(chapter)) {
+            return Err(AppError::Validation(format!("Livre EPUB chiffré: {}", file.path.display())));
+        }
+
+        let mut text = format!("{}\n{}\n", package.title, package.creators.join(", "));
+        for chapter in package.chapters.iter().filter(|chapter| !encrypted.contains(chapter)) {
+            if text.len() > MAX_CHARS * 2 {
+                break;
+            }
+            let Some(xhtml) = read_entry(&mut archive, chapter)? else { continue };
+            text.push_str(&html_to_text(&xhtml).body);
+            text.push('\n');
+        }
+
+        Ok(clean_extracted_text(&text, MAX_LINES, MAX_CHARS))
+    }
+}
+
+impl Default for EpubReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Chemin du fichier OPF déclaré dans `META-INF/container.xml`
+fn rootfile(xml: &str) -> AppResult<Option<String>> {
+    let mut reader = XmlReader::from_str(xml);
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"rootfile" => {
+                if let Some(path) = attribute(&e, "full-path") {
+                    return Ok(Some(path));
+                }
+            }
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+fn read_package(xml: &str, folder: &str) -> AppResult<Package> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut package = Package::default();
+    let mut manifest: HashMap<String, String> = HashMap::new();
+    let mut spine: Vec<String> = Vec::new();
+    let mut current: Option<String> = None;
+
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"title" if package.title.is_empty() => current = Some(String::new()),
+                b"creator" => current = Some(String::new()),
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"item" => {
+                    if let (Some(id), Some(href)) = (attribute(&e, "id"), attribute(&e, "href")) {
+                        manifest.insert(id, href);
+                    }
+                }
+                b"itemref" => spine.extend(attribute(&e, "idref")),
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"title" => {
+                    if let Some(title) = current.take() {
+                        package.title = title.trim().to_string();
+                    }
+                }
+                b"creator" => package.creators.extend(current.take().map(|creator| creator.trim().to_string())),
+                _ => {}
+            },
+            event @ (Event::Text(_) | Event::CData(_) | Event::GeneralRef(_)) => {
+                if let Some(text) = current.as_mut() {
+                    push_text(text, &event)?;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    package.chapters = spine.iter()
+        .filter_map(|id| manifest.get(id))
+        .map(|href| resolve_target(folder, &percent_decode(href.split('#').next().unwrap_or_default())))
+        .collect();
+    Ok(package)
+}
+
+/// Entrées chiffrées listées dans `META-INF/encryption.xml`, hors polices obfusquées
+fn encrypted_entries(xml: &str) -> AppResult<Vec<String>> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut entries = Vec::new();
+    let mut obfuscated = false;
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"EncryptedData" => obfuscated = false,
+                b"EncryptionMethod" => {
+                    obfuscated = attribute(&e, "Algorithm").is_some_and(|algorithm| {
+                        algorithm.contains("idpf.org/2008/embedding") || algorithm.contains("ns.adobe.com/pdf/enc")
+                    });
+                }
+                b"CipherReference" if !obfuscated => {
+                    entries.extend(attribute(&e, "URI").map(|uri| resolve_target("", &percent_decode(&uri))));
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// Les `href` du manifeste sont des URL : `Chapitre%201.xhtml` → `Chapitre 1.xhtml`
+fn percent_decode(href: &str) -> String {
+    let bytes = href.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| href.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::readers::office::tests::{file_for, write_zip};
+
+    #[test]
+    fn test_epub_follows_spine_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let container = r#"<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container" version="1.0">
+            <rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+        </container>"#;
+        let opf = r#"<package xmlns="http://www.idpf.org/2007/opf" xmlns:dc="http://purl.org/dc/elements/1.1/" version="3.0">
+            <metadata><dc:title>Voyage &amp; retour</dc:title><dc:creator>Jeanne Martin</dc:creator></metadata>
+            <manifest>
+                <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+                <item id="c1" href="Text/Chapitre%201.xhtml" media-type="application/xhtml+xml"/>
+                <item id="c2" href="Text/chapitre2.xhtml" media-type="application/xhtml+xml"/>
+                <item id="css" href="Styles/style.css" media-type="text/css"/>
+            </manifest>
+            <spine><itemref idref="c2"/><itemref idref="c1"/></spine>
+        </package>"#;
+        let chapter = |title: &str, body: &str| format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><html xmlns="http://www.w3.org/1999/xhtml"><head><title>{title}</title><link rel="stylesheet" href="../Styles/style.css"/></head><body><h1>{title}</h1><p>{body}</p></body></html>"#
+        );
+
+        let path = write_zip(&dir, "voyage.epub", &[
+            ("mimetype", "application/epub+zip"),
+            ("META-INF/container.xml", container),
+            ("OEBPS/content.opf", opf),
+            ("OEBPS/nav.xhtml", "<html><body><nav>Sommaire</nav></body></html>"),
+            ("OEBPS/Text/Chapitre 1.xhtml", &chapter("Départ", "Le train quitte la gare&#160;à l'aube.")),
+            ("OEBPS/Text/chapitre2.xhtml", &chapter("Arrivée", "La mer, enfin.")),
+            ("OEBPS/Styles/style.css", "h1 { color: navy; }"),
+        ]);
+
+        let text = EpubReader::new().read(&file_for(path)).unwrap();
+
+        assert_eq!(text, "Voyage & retour Jeanne Martin Arrivée La mer, enfin. Départ Le train quitte la gare à l'aube.");
+    }
+}
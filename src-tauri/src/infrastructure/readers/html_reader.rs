@@ -0,0 +1,325 @@
+use crate::domain::ports::reader::Reader;
+use crate::domain::entities::content::ExtractedContent;
+use crate::domain::entities::file::File;
+use crate::infrastructure::readers::encoding::{read_text_file, DecodedText};
+use crate::infrastructure::readers::office::clean_extracted_text;
+use crate::shared::errors::AppResult;
+
+const MAX_HTML_SIZE: u64 = 10 * 1024 * 1024;
+const MAX_LINES: usize = 5000;
+const MAX_CHARS: usize = 50000;
+
+// Éléments dont le contenu n'est jamais affiché comme texte
+const RAW_TEXT_TAGS: [&str; 4] = ["script", "style", "template", "noscript"];
+// Éléments sans balise fermante
+const VOID_TAGS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+const BLOCK_TAGS: [&str; 30] = [
+    "address", "article", "aside", "blockquote", "br", "caption", "dd", "div", "dl", "dt", "figcaption", "figure",
+    "footer", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "li", "main", "nav", "ol", "p", "pre", "section",
+    "tr", "ul",
+];
+const CELL_TAGS: [&str; 2] = ["td", "th"];
+
+const NAMED_ENTITIES: [(&str, &str); 40] = [
+    ("amp", "&"), ("lt", "<"), ("gt", ">"), ("quot", "\""), ("apos", "'"), ("nbsp", " "),
+    ("eacute", "é"), ("egrave", "è"), ("ecirc", "ê"), ("euml", "ë"), ("agrave", "à"), ("acirc", "â"),
+    ("ccedil", "ç"), ("icirc", "î"), ("iuml", "ï"), ("ocirc", "ô"), ("ucirc", "û"), ("ugrave", "ù"),
+    ("uuml", "ü"), ("ouml", "ö"), ("auml", "ä"), ("szlig", "ß"), ("oelig", "œ"), ("aelig", "æ"),
+    ("Eacute", "É"), ("Egrave", "È"), ("Agrave", "À"), ("Ccedil", "Ç"),
+    ("laquo", "«"), ("raquo", "»"), ("lsquo", "‘"), ("rsquo", "’"), ("ldquo", "“"), ("rdquo", "”"),
+    ("hellip", "…"), ("mdash", "—"), ("ndash", "–"), ("euro", "€"), ("copy", "©"), ("deg", "°"),
+];
+
+/// Texte visible d'une page HTML, avec son titre et sa description (`<meta name="description">`)
+#[derive(Debug, Default, PartialEq)]
+pub struct HtmlText {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub body: String,
+}
+
+struct Tag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attributes: Vec<(String, String)>,
+    /// Longueur de la balise dans le source, chevrons compris
+    length: usize,
+}
+
+impl Tag {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.attribute("hidden").is_some() || self.attribute("aria-hidden") == Some("true")
+    }
+}
+
+/// Analyse tolérante, sans arbre : balises non fermées et attributs sans guillemets sont admis.
+/// Les scripts, styles, commentaires et éléments masqués (`hidden`, `aria-hidden`) sont ignorés.
+pub fn html_to_text(html: &str) -> HtmlText {
+    let lower = html.to_ascii_lowercase();
+    let mut page = HtmlText::default();
+    let mut title = String::new();
+    let mut in_title = false;
+    // Élément masqué en cours et profondeur des éléments de même nom
+    let mut hidden: Option<(String, usize)> = None;
+
+    let mut position = 0;
+    let mut text_start = 0;
+    while let Some(offset) = html[position..].find('<') {
+        let start = position + offset;
+        let rest = &html[start..];
+
+        let markup_end = if rest.starts_with("<!--") {
+            Some(html[start..].find("-->").map_or(html.len(), |end| start + end + 3))
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            Some(html[start..].find('>').map_or(html.len(), |end| start + end + 1))
+        } else {
+            None
+        };
+        let tag = if markup_end.is_none() { parse_tag(rest) } else { None };
+        if markup_end.is_none() && tag.is_none() {
+            // « < » isolé : du texte
+            position = start + 1;
+            continue;
+        }
+
+        if hidden.is_none() {
+            let text = decode_entities(&html[text_start..start]);
+            if in_title { title.push_str(&text) } else { page.body.push_str(&text) }
+        }
+        let mut end = markup_end.unwrap_or(start);
+
+        if let Some(tag) = tag {
+            end = start + tag.length;
+            if let Some((name, depth)) = hidden.as_mut() {
+                if *name == tag.name && !VOID_TAGS.contains(&tag.name.as_str()) {
+                    if tag.closing { *depth -= 1 } else if !tag.self_closing { *depth += 1 }
+                    if *depth == 0 {
+                        hidden = None;
+                    }
+                }
+            } else if !tag.closing && RAW_TEXT_TAGS.contains(&tag.name.as_str()) {
+                // Contenu brut jusqu'à la balise fermante
+                let closing = format!("</{}", tag.name);
+                end = lower[end..].find(&closing)
+                    .and_then(|offset| html[end + offset..].find('>').map(|close| end + offset + close + 1))
+                    .unwrap_or(html.len());
+            } else if !tag.closing && !tag.self_closing && tag.is_hidden() && !VOID_TAGS.contains(&tag.name.as_str()) {
+                hidden = Some((tag.name.clone(), 1));
+            } else {
+                match tag.name.as_str() {
+                    "title" => in_title = !tag.closing && !tag.self_closing,
+                    "meta" if page.description.is_none() => {
+                        let name = tag.attribute("name").or(tag.attribute("property")).unwrap_or_default().to_ascii_lowercase();
+                        if name == "description" || name == "og:description" {
+                            page.description = tag.attribute("content").map(|content| decode_entities(content).trim().to_string());
+                        }
+                    }
+                    name if BLOCK_TAGS.contains(&name) => page.body.push('\n'),
+                    name if CELL_TAGS.contains(&name) && tag.closing => page.body.push('\t'),
+                    _ => {}
+                }
+            }
+        }
+
+        text_start = end;
+        position = end;
+    }
+
+    if hidden.is_none() {
+        page.body.push_str(&decode_entities(&html[text_start..]));
+    }
+    page.title = Some(title.split_whitespace().collect::<Vec<_>>().join(" ")).filter(|title| !title.is_empty());
+    page
+}
+
+fn parse_tag(source: &str) -> Option<Tag> {
+    let bytes = source.as_bytes();
+    let mut index = 1;
+    let closing = bytes.get(index) == Some(&b'/');
+    if closing {
+        index += 1;
+    }
+    if !bytes.get(index)?.is_ascii_alphabetic() {
+        return None;
+    }
+
+    let name_start = index;
+    while bytes.get(index).is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'-' || *b == b':') {
+        index += 1;
+    }
+    let name = source[name_start..index].to_ascii_lowercase();
+
+    let mut attributes = Vec::new();
+    let mut self_closing = false;
+    loop {
+        while bytes.get(index)?.is_ascii_whitespace() {
+            index += 1;
+        }
+        match bytes[index] {
+            b'>' => break,
+            b'/' if bytes.get(index + 1) == Some(&b'>') => {
+                self_closing = true;
+                index += 1;
+                break;
+            }
+            b'/' => {
+                index += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let key_start = index;
+        while bytes.get(index).is_some_and(|b| !b.is_ascii_whitespace() && !matches!(b, b'=' | b'>' | b'/')) {
+            index += 1;
+        }
+        let key = source[key_start..index].to_string();
+        while bytes.get(index)?.is_ascii_whitespace() {
+            index += 1;
+        }
+
+        let mut value = String::new();
+        if bytes.get(index) == Some(&b'=') {
+            index += 1;
+            while bytes.get(index)?.is_ascii_whitespace() {
+                index += 1;
+            }
+            match bytes[index] {
+                quote @ (b'"' | b'\'') => {
+                    let end = index + 1 + source[index + 1..].find(quote as char)?;
+                    value = source[index + 1..end].to_string();
+                    index = end + 1;
+                }
+                _ => {
+                    let value_start = index;
+                    while bytes.get(index).is_some_and(|b| !b.is_ascii_whitespace() && *b != b'>') {
+                        index += 1;
+                    }
+                    value = source[value_start..index].to_string();
+                }
+            }
+        }
+        attributes.push((key, value));
+    }
+
+    Some(Tag { name, closing, self_closing, attributes, length: index + 1 })
+}
+
+/// Remplace les références de caractères (`&eacute;`, `&#233;`, `&#xE9;`) ; les inconnues restent telles quelles
+pub fn decode_entities(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let resolved = rest[1..].find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| {
+                let name = &rest[1..1 + end];
+                let character = match name.strip_prefix('#') {
+                    Some(number) => {
+                        let code = match number.strip_prefix(['x', 'X']) {
+                            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                            None => number.parse().ok(),
+                        };
+                        code.and_then(char::from_u32).map(String::from)
+                    }
+                    None => NAMED_ENTITIES.iter().find(|(entity, _)| *entity == name).map(|(_, value)| value.to_string()),
+                };
+                character.map(|character| (character, end + 2))
+            });
+
+        match resolved {
+            Some((character, length)) => {
+                output.push_str(&character);
+                rest = &rest[length..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+pub struct HtmlReader;
+
+impl HtmlReader {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn read_source(&self, file: &File) -> AppResult<DecodedText> {
+        read_text_file(&file.path, MAX_HTML_SIZE)
+    }
+
+    fn page_text(&self, source: &str) -> String {
+        let page = html_to_text(source);
+        let text = [page.title.unwrap_or_default(), page.description.unwrap_or_default(), page.body].join("\n");
+        clean_extracted_text(&text, MAX_LINES, MAX_CHARS)
+    }
+}
+
+impl Reader for HtmlReader {
+    fn read(&self, file: &File) -> AppResult<String> {
+        let source = self.read_source(file)?;
+        Ok(self.page_text(&source.text))
+    }
+
+    fn extract(&self, file: &File) -> AppResult<ExtractedContent> {
+        let source = self.read_source(file)?;
+        Ok(ExtractedContent {
+            encoding: Some(source.encoding.to_string()),
+            ..ExtractedContent::from_text(self.page_text(&source.text))
+        })
+    }
+}
+
+impl Default for HtmlReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_keeps_visible_text_title_and_description() {
+        let html = r#"<!DOCTYPE html>
+            <html><head>
+                <title>Rapport &amp; bilan</title>
+                <meta charset=utf-8>
+                <meta name="Description" content="Synthèse de l&#39;année">
+                <style>body { color: red; }</style>
+                <script>if (a < b) { document.write("<p>caché</p>"); }</script>
+            </head>
+            <body class=main>
+                <!-- commentaire <p>ignoré</p> -->
+                <h1>Résultats</h1><p>Chiffre d&eacute;affaires&nbsp;: 3 < 4</p>
+                <div hidden><div>masqué</div><p>aussi</p></div>
+                <table><tr><td>Nord</td><td>42</td></tr></table>
+                <img src="x.png" alt="image"><br/>Fin
+            </body></html>"#;
+
+        let page = html_to_text(html);
+
+        assert_eq!(page.title.as_deref(), Some("Rapport & bilan"));
+        assert_eq!(page.description.as_deref(), Some("Synthèse de l'année"));
+        let body = clean_extracted_text(&page.body, MAX_LINES, MAX_CHARS);
+        assert_eq!(body, "Résultats Chiffre déaffaires : 3 < 4 Nord 42 Fin");
+    }
+}
@@ -0,0 +1,556 @@
+use crate::domain::ports::reader::Reader;
+use crate::domain::entities::content::{ExtractedContent, ExtractedEntry, MailHeaders};
+use crate::domain::entities::file::File;
+use crate::infrastructure::readers::encoding::decode;
+use crate::infrastructure::readers::html_reader::html_to_text;
+use crate::infrastructure::readers::office::clean_extracted_text;
+use crate::shared::errors::{AppError, AppResult};
+use encoding_rs::{Encoding, WINDOWS_1252};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+const MAX_LINES: usize = 5000;
+const MAX_CHARS: usize = 50000;
+// Au-delà, le reste du message (pièces jointes le plus souvent) est ignoré
+const MAX_MESSAGE_SIZE: usize = 25 * 1024 * 1024;
+// Une boîte mbox est lue message par message, mais tous ses messages restent en mémoire jusqu'à
+// leur écriture (une transaction) : le nombre de messages et le texte extrait au total sont bornés
+const MAX_MAILBOX_MESSAGES: usize = 10_000;
+const MAX_MAILBOX_TEXT: usize = 32 * 1024 * 1024;
+const MAX_MIME_DEPTH: usize = 8;
+
+const MBOX_SEPARATOR: &[u8] = b"From ";
+
+type Headers = Vec<(String, String)>;
+
+/// Message décodé : en-têtes utiles, texte du corps et noms des pièces jointes
+struct Message {
+    headers: MailHeaders,
+    body: String,
+    attachments: Vec<String>,
+}
+
+impl Message {
+    fn parse(raw: &[u8], depth: usize) -> Self {
+        let (headers, body) = split_headers(raw);
+        let mut attachments = Vec::new();
+        let body = part_text(&headers, body, depth, &mut attachments).map(|(_, text)| text).unwrap_or_default();
+
+        Self {
+            headers: MailHeaders {
+                subject: header(&headers, "subject").map(decode_words),
+                from: header(&headers, "from").map(decode_words),
+                to: header(&headers, "to").map(decode_words),
+                date: header(&headers, "date").and_then(parse_date),
+            },
+            body,
+            attachments,
+        }
+    }
+
+    /// Objet, expéditeur et destinataires en tête du texte pour qu'ils soient cherchables comme le corps
+    fn text(&self) -> String {
+        let mut text = String::new();
+        for value in [&self.headers.subject, &self.headers.from, &self.headers.to].into_iter().flatten() {
+            text.push_str(value);
+            text.push('\n');
+        }
+        text.push_str(&self.body);
+        if !self.attachments.is_empty() {
+            text.push_str(&format!("\nPièces jointes : {}", self.attachments.join(", ")));
+        }
+        text
+    }
+
+    fn content(&self) -> ExtractedContent {
+        ExtractedContent {
+            mail: Some(self.headers.clone()),
+            ..ExtractedContent::from_text(clean_extracted_text(&self.text(), MAX_LINES, MAX_CHARS))
+        }
+    }
+}
+
+/// Courriers électroniques : un message (.eml) ou une boîte mbox, dont chaque message devient un
+/// élément indexé séparément
+pub struct MailReader;
+
+impl MailReader {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn is_mailbox(file: &File) -> bool {
+        file.path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("mbox"))
+    }
+
+    fn read_message(&self, path: &Path) -> AppResult<Message> {
+        let metadata = fs::metadata(path)?;
+        if metadata.len() > MAX_MESSAGE_SIZE as u64 {
+            return Err(AppError::Validation(format!("Message trop volumineux: {} bytes", metadata.len())));
+        }
+        Ok(Message::parse(&fs::read(path)?, 0))
+    }
+
+    fn read_mailbox(&self, path: &Path) -> AppResult<Vec<ExtractedEntry>> {
+        let mut reader = BufReader::new(fs::File::open(path)?);
+        let mut entries = Vec::new();
+        let mut extracted = 0;
+        let mut raw: Vec<u8> = Vec::new();
+        let mut line = Vec::new();
+        let mut started = false;
+
+        loop {
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)?;
+
+            if read == 0 || line.starts_with(MBOX_SEPARATOR) {
+                if started {
+                    let message = Message::parse(&raw, 0);
+                    let index = entries.len() + 1;
+                    let content = message.content();
+                    extracted += content.text.len();
+                    entries.push(ExtractedEntry {
+                        path: format!("{:05}.eml", index),
+                        name: message.headers.subject.clone()
+                            .filter(|subject| !subject.is_empty())
+                            .unwrap_or_else(|| format!("Message {}", index)),
+                        file_type: Some("eml".to_string()),
+                        size: raw.len() as u64,
+                        modified: message.headers.date,
+                        content,
+                    });
+                    raw.clear();
+                }
+                if read == 0 {
+                    break;
+                }
+                if entries.len() >= MAX_MAILBOX_MESSAGES || extracted >= MAX_MAILBOX_TEXT {
+                    tracing::info!("Boîte mbox tronquée après {} messages: {}", entries.len(), path.display());
+                    break;
+                }
+                started = true;
+                continue;
+            }
+
+            if !started {
+                return Err(AppError::Validation(format!("Boîte mbox invalide: {}", path.display())));
+            }
+            if raw.len() < MAX_MESSAGE_SIZE {
+                // mboxrd : les lignes « From » du corps sont échappées par un « > » de plus
+                let quoted = line.iter().take_while(|byte| **byte == b'>').count();
+                let unescaped = if quoted > 0 && line[quoted..].starts_with(MBOX_SEPARATOR) { &line[1..] } else { &line[..] };
+                raw.extend_from_slice(unescaped);
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+impl Reader for MailReader {
+    fn read(&self, file: &File) -> AppResult<String> {
+        let content = self.extract(file)?;
+        if content.entries.is_empty() {
+            return Ok(content.text);
+        }
+        let texts: Vec<&str> = content.entries.iter().map(|entry| entry.content.text.as_str()).collect();
+        Ok(clean_extracted_text(&texts.join("\n"), MAX_LINES, MAX_CHARS))
+    }
+
+    fn extract(&self, file: &File) -> AppResult<ExtractedContent> {
+        let file_path = Path::new(&file.path);
+
+        if !file_path.exists() || !file_path.is_file() {
+            return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", file)));
+        }
+
+        if Self::is_mailbox(file) {
+            // La boîte elle-même n'a pas de texte : ses messages sont indexés un par un
+            let entries = self.read_mailbox(file_path)?;
+            Ok(ExtractedContent { entries, ..ExtractedContent::from_text(String::new()) })
+        } else {
+            Ok(self.read_message(file_path)?.content())
+        }
+    }
+}
+
+impl Default for MailReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// En-têtes (noms en minuscules, lignes de continuation dépliées) et corps du message ou de la partie
+fn split_headers(raw: &[u8]) -> (Headers, &[u8]) {
+    let mut headers: Headers = Vec::new();
+    let mut position = 0;
+    while position < raw.len() {
+        let end = line_end(raw, position);
+        let line = trim_line_end(&raw[position..end]);
+        position = end;
+        if line.is_empty() {
+            break;
+        }
+
+        let line = header_text(line);
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    (headers, &raw[position..])
+}
+
+fn header<'a>(headers: &'a Headers, name: &str) -> Option<&'a str> {
+    headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+/// Les en-têtes devraient être en ASCII ; les octets 8 bits sont lus en UTF-8, sinon en Windows-1252
+fn header_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => WINDOWS_1252.decode_without_bom_handling(bytes).0.into_owned(),
+    }
+}
+
+fn line_end(bytes: &[u8], start: usize) -> usize {
+    bytes[start..].iter().position(|byte| *byte == b'\n').map_or(bytes.len(), |offset| start + offset + 1)
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Texte d'une partie MIME et son type ; `None` pour les pièces jointes et les contenus non textuels
+fn part_text(headers: &Headers, body: &[u8], depth: usize, attachments: &mut Vec<String>) -> Option<(String, String)> {
+    let (mime, parameters) = header(headers, "content-type")
+        .map(parse_parameters)
+        .unwrap_or_else(|| ("text/plain".to_string(), Vec::new()));
+    let (disposition, disposition_parameters) = header(headers, "content-disposition")
+        .map(parse_parameters)
+        .unwrap_or_default();
+
+    let textual = mime.starts_with("text/") || mime.starts_with("multipart/") || mime == "message/rfc822";
+    if disposition == "attachment" || !textual {
+        let name = parameter(&disposition_parameters, "filename").or_else(|| parameter(&parameters, "name"));
+        attachments.extend(name.map(decode_words));
+        return None;
+    }
+    if depth > MAX_MIME_DEPTH {
+        return None;
+    }
+
+    if let Some(kind) = mime.strip_prefix("multipart/") {
+        let boundary = parameter(&parameters, "boundary")?;
+        let parts: Vec<(String, String)> = split_multipart(body, boundary)
+            .into_iter()
+            .filter_map(|part| {
+                let (headers, body) = split_headers(part);
+                part_text(&headers, body, depth + 1, attachments)
+            })
+            .filter(|(_, text)| !text.trim().is_empty())
+            .collect();
+
+        let text = if kind == "alternative" {
+            // Versions d'un même contenu : le texte brut d'abord, sinon la plus riche (la dernière)
+            parts.iter().find(|(mime, _)| mime == "text/plain").or(parts.last())
+                .map(|(_, text)| text.clone())
+                .unwrap_or_default()
+        } else {
+            parts.into_iter().map(|(_, text)| text).collect::<Vec<_>>().join("\n")
+        };
+        return Some((mime, text));
+    }
+
+    let bytes = match header(headers, "content-transfer-encoding").map(|encoding| encoding.trim().to_ascii_lowercase()).as_deref() {
+        Some("base64") => decode_base64(body),
+        Some("quoted-printable") => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    };
+
+    let text = match mime.as_str() {
+        "message/rfc822" => Message::parse(&bytes, depth + 1).text(),
+        "text/html" => html_to_text(&decode_charset(&bytes, parameter(&parameters, "charset"))).body,
+        _ => decode_charset(&bytes, parameter(&parameters, "charset")),
+    };
+    Some((mime, text))
+}
+
+/// Parties d'un corps multipart, préambule et épilogue exclus
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut position = 0;
+
+    while position < body.len() {
+        let end = line_end(body, position);
+        let line = trim_line_end(&body[position..end]);
+
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes()) {
+            let closing = rest.starts_with(b"--");
+            if closing || rest.iter().all(u8::is_ascii_whitespace) {
+                // Le saut de ligne qui précède le délimiteur lui appartient
+                if let Some(start) = start {
+                    parts.push(trim_line_end(&body[start..position]));
+                }
+                if closing {
+                    return parts;
+                }
+                start = Some(end);
+            }
+        }
+        position = end;
+    }
+
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+/// `text/plain; charset="utf-8"` → type en minuscules et paramètres (noms en minuscules)
+fn parse_parameters(value: &str) -> (String, Vec<(String, String)>) {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for ch in value.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ';' if !quoted => fields.push(std::mem::take(&mut current)),
+            ch => current.push(ch),
+        }
+    }
+    fields.push(current);
+
+    let mut fields = fields.into_iter();
+    let kind = fields.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let parameters = fields
+        .filter_map(|field| {
+            let (name, value) = field.split_once('=')?;
+            Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect();
+    (kind, parameters)
+}
+
+fn parameter<'a>(parameters: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    parameters.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+/// Jeu de caractères déclaré, sinon détection comme pour les fichiers texte
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    match charset.and_then(|charset| Encoding::for_label(charset.trim().as_bytes())) {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => decode(bytes)
+            .map(|decoded| decoded.text)
+            .unwrap_or_else(|_| String::from_utf8_lossy(bytes).into_owned()),
+    }
+}
+
+/// Mots encodés des en-têtes (RFC 2047) : `=?utf-8?Q?R=C3=A9union?=`, `=?iso-8859-1?B?...?=`
+fn decode_words(value: &str) -> String {
+    let mut output = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let (before, candidate) = rest.split_at(start);
+        match encoded_word(candidate) {
+            Some((decoded, length)) => {
+                // Les blancs entre deux mots encodés ne font pas partie du texte
+                if !(after_word && before.trim().is_empty()) {
+                    output.push_str(before);
+                }
+                output.push_str(&decoded);
+                rest = &candidate[length..];
+                after_word = true;
+            }
+            None => {
+                output.push_str(before);
+                output.push_str("=?");
+                rest = &candidate[2..];
+                after_word = false;
+            }
+        }
+    }
+    output.push_str(rest);
+    output.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Mot encodé décodé et sa longueur dans la source
+fn encoded_word(word: &str) -> Option<(String, usize)> {
+    let (charset, rest) = word[2..].split_once('?')?;
+    let (encoding, rest) = rest.split_once('?')?;
+    let end = rest.find("?=")?;
+    let text = &rest[..end];
+    if charset.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let bytes = match encoding {
+        "B" | "b" => decode_base64(text.as_bytes()),
+        "Q" | "q" => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+    // RFC 2231 : `utf-8*fr` précise la langue
+    let charset = charset.split('*').next().unwrap_or(charset);
+    Some((decode_charset(&bytes, Some(charset)), word.len() - rest.len() + end + 2))
+}
+
+fn decode_base64(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in input {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => {
+                // Fin de bloc : les bits restants ne sont que du remplissage
+                buffer = 0;
+                bits = 0;
+                continue;
+            }
+            _ => continue,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    output
+}
+
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut index = 0;
+    while index < input.len() {
+        if input[index] != b'=' {
+            output.push(input[index]);
+            index += 1;
+            continue;
+        }
+
+        let next = &input[index + 1..];
+        if next.starts_with(b"\r\n") {
+            // Saut de ligne « doux » : la ligne continue
+            index += 3;
+        } else if next.starts_with(b"\n") {
+            index += 2;
+        } else if let Some(byte) = next.get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            output.push(byte);
+            index += 3;
+        } else {
+            output.push(b'=');
+            index += 1;
+        }
+    }
+    output
+}
+
+/// Date RFC 2822, commentaire final `(CET)` toléré
+fn parse_date(value: &str) -> Option<i64> {
+    let value = value.split('(').next()?.trim();
+    chrono::DateTime::parse_from_rfc2822(value).ok().map(|date| date.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::readers::office::tests::file_for;
+
+    #[test]
+    fn test_mime_message_is_decoded() {
+        let raw = b"From: =?utf-8?Q?H=C3=A9l=C3=A8ne?= Dupont <helene@exemple.fr>\r\n\
+To: equipe@exemple.fr\r\n\
+Subject: =?iso-8859-1?B?Uul1bmlvbg==?= =?utf-8?Q?_budg=C3=A9taire?=\r\n\
+Date: Tue, 3 Mar 2026 09:15:00 +0100 (CET)\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"ext\"\r\n\
+\r\n\
+Preambule ignore\r\n\
+--ext\r\n\
+Content-Type: multipart/alternative; boundary=alt\r\n\
+\r\n\
+--alt\r\n\
+Content-Type: text/plain; charset=iso-8859-1\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Ordre du jour : pr=E9visions, d=\r\n\
+=E9penses.\r\n\
+--alt\r\n\
+Content-Type: text/html; charset=utf-8\r\n\
+\r\n\
+<p>Version <b>HTML</b></p>\r\n\
+--alt--\r\n\
+--ext\r\n\
+Content-Type: application/pdf; name=\"budget.pdf\"\r\n\
+Content-Disposition: attachment; filename=\"budget.pdf\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+JVBERi0xLjQK\r\n\
+--ext--\r\n";
+
+        let message = Message::parse(raw, 0);
+
+        assert_eq!(message.headers, MailHeaders {
+            subject: Some("Réunion budgétaire".to_string()),
+            from: Some("Hélène Dupont <helene@exemple.fr>".to_string()),
+            to: Some("equipe@exemple.fr".to_string()),
+            date: Some(1772525700),
+        });
+        assert_eq!(
+            clean_extracted_text(&message.text(), MAX_LINES, MAX_CHARS),
+            "Réunion budgétaire Hélène Dupont <helene@exemple.fr> equipe@exemple.fr \
+             Ordre du jour : prévisions, dépenses. Pièces jointes : budget.pdf"
+        );
+    }
+
+    #[test]
+    fn test_mbox_messages_become_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archives.mbox");
+        fs::write(&path, "From alice@exemple.fr Mon Mar  2 10:00:00 2026\n\
+From: Alice <alice@exemple.fr>\n\
+Subject: Premier\n\
+Date: Mon, 2 Mar 2026 10:00:00 +0000\n\
+\n\
+Bonjour,\n\
+>From the start.\n\
+\n\
+From bob@exemple.fr Tue Mar  3 11:00:00 2026\n\
+From: Bob <bob@exemple.fr>\n\
+Content-Type: text/html\n\
+\n\
+<html><body><p>Sans objet</p></body></html>\n").unwrap();
+
+        let content = MailReader::new().extract(&file_for(path)).unwrap();
+
+        assert!(content.text.is_empty());
+        let entries: Vec<(&str, &str, &str)> = content.entries.iter()
+            .map(|entry| (entry.path.as_str(), entry.name.as_str(), entry.content.text.as_str()))
+            .collect();
+        assert_eq!(entries, vec![
+            ("00001.eml", "Premier", "Premier Alice <alice@exemple.fr> Bonjour, From the start."),
+            ("00002.eml", "Message 2", "Bob <bob@exemple.fr> Sans objet"),
+        ]);
+        assert_eq!(content.entries[0].modified, Some(1772445600));
+        assert_eq!(content.entries[1].content.mail.as_ref().and_then(|mail| mail.from.as_deref()), Some("Bob <bob@exemple.fr>"));
+    }
+}
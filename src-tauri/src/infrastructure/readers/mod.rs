@@ -1,11 +1,26 @@
 pub mod text_reader;
 pub mod pdf_reader;
 pub mod word_reader;
+pub mod office;
+pub mod encoding;
 pub mod csv_reader;
 pub mod code_reader;
+pub mod spreadsheet_reader;
+pub mod presentation_reader;
+pub mod html_reader;
+pub mod epub_reader;
+pub mod rtf_reader;
+pub mod mail_reader;
+pub mod archive;
 
 pub use text_reader::TextReader;
 pub use pdf_reader::PdfReader;
 pub use word_reader::WordReader;
 pub use csv_reader::CsvReader;
-pub use code_reader::CodeReader; 
\ No newline at end of file
+pub use code_reader::CodeReader;
+pub use spreadsheet_reader::SpreadsheetReader;
+pub use presentation_reader::PresentationReader;
+pub use html_reader::HtmlReader;
+pub use epub_reader::EpubReader;
+pub use rtf_reader::RtfReader;
+pub use mail_reader::MailReader;
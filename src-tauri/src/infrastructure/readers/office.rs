@@ -0,0 +1,308 @@
+use crate::shared::errors::{AppError, AppResult};
+use quick_xml::events::{BytesRef, BytesStart, Event};
+use quick_xml::Reader as XmlReader;
+use std::fs;
+use std::io::{Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+// Taille maximale décompressée d'une entrée XML (protection contre les archives piégées)
+const MAX_ENTRY_SIZE: u64 = 50 * 1024 * 1024;
+
+/// Règles de conversion d'un document XML bureautique en texte brut.
+/// Les noms de balises sont comparés sans préfixe d'espace de noms.
+#[derive(Default)]
+pub struct XmlTextRules<'a> {
+    /// Seul le texte contenu dans ces balises est conservé (vide = tout le texte)
+    pub text_tags: &'a [&'a str],
+    /// Fin de bloc : retour à la ligne
+    pub block_tags: &'a [&'a str],
+    /// Fin de cellule de tableau : tabulation
+    pub cell_tags: &'a [&'a str],
+    /// Saut de ligne explicite
+    pub break_tags: &'a [&'a str],
+    /// Espace ou tabulation explicite
+    pub space_tags: &'a [&'a str],
+    /// Sous-arbres entièrement ignorés
+    pub skip_tags: &'a [&'a str],
+}
+
+pub fn open_archive(path: &Path) -> AppResult<ZipArchive<fs::File>> {
+    let file = fs::File::open(path)?;
+    ZipArchive::new(file)
+        .map_err(|e| AppError::Validation(format!("Archive invalide {}: {}", path.display(), e)))
+}
+
+/// Lit une entrée texte de l'archive, `None` si elle n'existe pas
+pub fn read_entry(archive: &mut ZipArchive<fs::File>, name: &str) -> AppResult<Option<String>> {
+    let entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(AppError::Internal(format!("Erreur de lecture de {}: {}", name, e))),
+    };
+
+    if entry.size() > MAX_ENTRY_SIZE {
+        return Err(AppError::Validation(format!("Entrée {} trop volumineuse: {} bytes", name, entry.size())));
+    }
+
+    let mut content = String::new();
+    entry.take(MAX_ENTRY_SIZE).read_to_string(&mut content)?;
+    Ok(Some(content))
+}
+
+/// Noms des entrées de l'archive satisfaisant le prédicat, triés
+pub fn entry_names<F>(archive: &ZipArchive<fs::File>, predicate: F) -> Vec<String>
+where F: Fn(&str) -> bool
+{
+    let mut names: Vec<String> = archive.file_names()
+        .filter(|name| predicate(name))
+        .map(|name| name.to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+pub fn xml_to_text(xml: &str, rules: &XmlTextRules) -> AppResult<String> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut output = String::new();
+    let mut text_depth = 0usize;
+    let mut skip_depth = 0usize;
+
+    let matches = |tags: &[&str], name: &[u8]| tags.iter().any(|tag| tag.as_bytes() == name);
+
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) => {
+                let local = e.local_name();
+                let name = local.as_ref();
+                if skip_depth > 0 || matches(rules.skip_tags, name) {
+                    skip_depth += 1;
+                    continue;
+                }
+                if matches(rules.text_tags, name) {
+                    text_depth += 1;
+                }
+                apply_marker(&mut output, rules, name);
+            }
+            Event::End(e) => {
+                let local = e.local_name();
+                let name = local.as_ref();
+                if skip_depth > 0 {
+                    skip_depth -= 1;
+                    continue;
+                }
+                if matches(rules.text_tags, name) {
+                    text_depth = text_depth.saturating_sub(1);
+                }
+                if matches(rules.block_tags, name) {
+                    output.push('\n');
+                } else if matches(rules.cell_tags, name) {
+                    output.push('\t');
+                }
+            }
+            Event::Empty(e) if skip_depth == 0 => {
+                let local = e.local_name();
+                let name = local.as_ref();
+                apply_marker(&mut output, rules, name);
+                if matches(rules.block_tags, name) {
+                    output.push('\n');
+                }
+            }
+            event @ (Event::Text(_) | Event::CData(_) | Event::GeneralRef(_))
+                if skip_depth == 0 && (rules.text_tags.is_empty() || text_depth > 0) =>
+            {
+                push_text(&mut output, &event)?;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(output)
+}
+
+pub fn read_xml_event<'a>(reader: &mut XmlReader<&'a [u8]>) -> AppResult<Event<'a>> {
+    reader.read_event()
+        .map_err(|e| AppError::Internal(format!("XML invalide à la position {}: {}", reader.error_position(), e)))
+}
+
+/// Ajoute le texte d'un événement (texte, CDATA ou référence) ; les autres sont ignorés
+pub fn push_text(output: &mut String, event: &Event) -> AppResult<()> {
+    match event {
+        Event::Text(e) => {
+            let text = e.decode()
+                .map_err(|e| AppError::Internal(format!("Texte XML invalide: {}", e)))?;
+            output.push_str(&text);
+        }
+        Event::CData(e) => output.push_str(&String::from_utf8_lossy(e)),
+        Event::GeneralRef(e) => {
+            if let Some(ch) = resolve_reference(e) {
+                output.push(ch);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Caractère d'une référence (`&#233;`, `&amp;`…), `None` pour une entité inconnue
+pub fn resolve_reference(reference: &BytesRef) -> Option<char> {
+    if let Ok(Some(ch)) = reference.resolve_char_ref() {
+        return Some(ch);
+    }
+    let name: &[u8] = reference.as_ref();
+    match name {
+        b"amp" => Some('&'),
+        b"lt" => Some('<'),
+        b"gt" => Some('>'),
+        b"quot" => Some('"'),
+        b"apos" => Some('\''),
+        _ => None,
+    }
+}
+
+/// Valeur d'un attribut, nom comparé sans préfixe d'espace de noms
+pub fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    element.attributes()
+        .flatten()
+        .find(|attribute| attribute.key.local_name().as_ref() == name.as_bytes())
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+fn apply_marker(output: &mut String, rules: &XmlTextRules, name: &[u8]) {
+    if rules.break_tags.iter().any(|tag| tag.as_bytes() == name) {
+        output.push('\n');
+    } else if rules.space_tags.iter().any(|tag| tag.as_bytes() == name) {
+        output.push(' ');
+    }
+}
+
+/// Relation d'une partie OOXML vers une autre (fichiers `_rels/*.rels`)
+pub struct Relationship {
+    pub id: String,
+    /// Type complet, par exemple `…/relationships/notesSlide`
+    pub kind: String,
+    pub target: String,
+}
+
+/// Fichier des relations d'une partie : `ppt/slides/slide1.xml` → `ppt/slides/_rels/slide1.xml.rels`
+pub fn relationships_path(part: &str) -> String {
+    match part.rsplit_once('/') {
+        Some((folder, name)) => format!("{}/_rels/{}.rels", folder, name),
+        None => format!("_rels/{}.rels", part),
+    }
+}
+
+pub fn read_relationships(xml: &str) -> AppResult<Vec<Relationship>> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut relationships = Vec::new();
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+                if let (Some(id), Some(target)) = (attribute(&e, "Id"), attribute(&e, "Target")) {
+                    relationships.push(Relationship { id, kind: attribute(&e, "Type").unwrap_or_default(), target });
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(relationships)
+}
+
+/// Chemin dans l'archive d'une cible de relation, relative au dossier `folder` de la partie source
+pub fn resolve_target(folder: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = match target.strip_prefix('/') {
+        Some(_) => Vec::new(),
+        None => folder.split('/').filter(|part| !part.is_empty()).collect(),
+    };
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// Lit un flux d'un document OLE (formats Office 97-2003)
+pub fn read_stream<F: Read + Seek>(compound: &mut cfb::CompoundFile<F>, name: &str) -> AppResult<Vec<u8>> {
+    let mut stream = compound.open_stream(name)
+        .map_err(|e| AppError::Validation(format!("Flux {} absent: {}", name, e)))?;
+    let mut buffer = Vec::new();
+    stream.read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Nettoie le texte extrait comme pour les PDF : lignes non vides jointes, taille limitée
+pub fn clean_extracted_text(text: &str, max_lines: usize, max_chars: usize) -> String {
+    let cleaned = text
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .take(max_lines)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if cleaned.len() > max_chars {
+        cleaned.chars().take(max_chars).collect()
+    } else {
+        cleaned
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::domain::entities::file::File;
+    use std::fs;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    pub fn file_for(path: PathBuf) -> File {
+        File {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            path,
+            is_dir: false,
+            file_type: None,
+            size: None,
+            last_modified: SystemTime::now(),
+            created_at: SystemTime::now(),
+            accessed_at: SystemTime::now(),
+            is_indexed: true,
+            content_indexed: false,
+            is_indexable: true,
+            is_hidden: false,
+            is_readonly: false,
+            is_system: false,
+            is_executable: false,
+            is_symlink: false,
+            permissions: None,
+            owner: None,
+            group: None,
+            mime_type: None,
+            encoding: None,
+            line_count: None,
+            word_count: None,
+            checksum: None,
+            is_encrypted: false,
+        }
+    }
+
+    pub fn write_zip(dir: &TempDir, name: &str, entries: &[(&str, &str)]) -> PathBuf {
+        let path = dir.path().join(name);
+        let mut writer = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        for (entry, content) in entries {
+            writer.start_file(*entry, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+        path
+    }
+}
@@ -0,0 +1,472 @@
+use crate::domain::ports::reader::Reader;
+use crate::domain::entities::content::SLIDE_MARK;
+use crate::domain::entities::file::File;
+use crate::infrastructure::readers::office::{
+    attribute, clean_extracted_text, open_archive, push_text, read_entry, read_relationships, read_xml_event,
+    relationships_path, resolve_target,
+};
+use crate::shared::errors::{AppError, AppResult};
+use quick_xml::events::Event;
+use quick_xml::Reader as XmlReader;
+use std::fs;
+use std::path::Path;
+use zip::ZipArchive;
+
+const MAX_LINES: usize = 5000;
+const MAX_CHARS: usize = 50000;
+// Les images et vidéos gonflent les présentations ; seules les parties XML sont lues
+const MAX_FILE_SIZE: u64 = 200 * 1024 * 1024;
+
+// Espaces réservés sans contenu propre à la diapositive : numéro, date, pied de page
+const IGNORED_PLACEHOLDERS: [&str; 4] = ["sldNum", "dt", "ftr", "hdr"];
+const ODP_IGNORED_CLASSES: [&str; 4] = ["page-number", "date-time", "footer", "header"];
+
+/// Texte d'une diapositive, dans l'ordre de lecture
+#[derive(Default)]
+struct Slide {
+    title: String,
+    body: String,
+    notes: String,
+}
+
+/// Présentations PowerPoint (PPTX) et OpenDocument (ODP) : titre, texte et notes de chaque
+/// diapositive, chacune précédée de sa marque pour situer les extraits
+pub struct PresentationReader;
+
+impl PresentationReader {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn extract_text_from_pptx(&self, archive: &mut ZipArchive<fs::File>, file: &File) -> AppResult<Vec<Slide>> {
+        let presentation = read_entry(archive, "ppt/presentation.xml")?
+            .ok_or_else(|| AppError::Validation(format!("ppt/presentation.xml absent: {}", file.path.display())))?;
+        let relationships = match read_entry(archive, &relationships_path("ppt/presentation.xml"))? {
+            Some(xml) => read_relationships(&xml)?,
+            None => Vec::new(),
+        };
+
+        let mut slides = Vec::new();
+        for id in slide_ids(&presentation)? {
+            let Some(relationship) = relationships.iter().find(|relationship| relationship.id == id) else { continue };
+            let part = resolve_target("ppt", &relationship.target);
+            let Some(xml) = read_entry(archive, &part)? else { continue };
+
+            let mut slide = Slide::default();
+            for (placeholder, text) in shape_texts(&xml)? {
+                match placeholder.as_deref() {
+                    Some("title" | "ctrTitle") => slide.title.push_str(&text),
+                    Some(kind) if IGNORED_PLACEHOLDERS.contains(&kind) => {}
+                    _ => slide.body.push_str(&text),
+                }
+            }
+
+            if let Some(notes) = self.pptx_notes_part(archive, &part)? {
+                if let Some(xml) = read_entry(archive, &notes)? {
+                    // Le texte des notes est dans l'espace réservé « body », à côté de la miniature
+                    for (placeholder, text) in shape_texts(&xml)? {
+                        if placeholder.as_deref() == Some("body") {
+                            slide.notes.push_str(&text);
+                        }
+                    }
+                }
+            }
+            slides.push(slide);
+        }
+        Ok(slides)
+    }
+
+    fn pptx_notes_part(&self, archive: &mut ZipArchive<fs::File>, slide_part: &str) -> AppResult<Option<String>> {
+        let Some(xml) = read_entry(archive, &relationships_path(slide_part))? else { return Ok(None) };
+        let folder = slide_part.rsplit_once('/').map(|(folder, _)| folder).unwrap_or_default();
+        Ok(read_relationships(&xml)?
+            .into_iter()
+            .find(|relationship| relationship.kind.ends_with("/notesSlide"))
+            .map(|relationship| resolve_target(folder, &relationship.target)))
+    }
+
+    fn extract_text_from_odp(&self, archive: &mut ZipArchive<fs::File>, file: &File) -> AppResult<Vec<Slide>> {
+        let content = read_entry(archive, "content.xml")?
+            .ok_or_else(|| AppError::Validation(format!("content.xml absent: {}", file.path.display())))?;
+        odp_slides(&content)
+    }
+}
+
+impl Reader for PresentationReader {
+    fn read(&self, file: &File) -> AppResult<String> {
+        let file_path = Path::new(&file.path);
+
+        if !file_path.exists() || !file_path.is_file() {
+            return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", file)));
+        }
+
+        let metadata = fs::metadata(file_path)?;
+        if metadata.len() > MAX_FILE_SIZE {
+            return Err(AppError::Validation(format!("Présentation trop volumineuse: {} bytes", metadata.len())));
+        }
+
+        let mut archive = open_archive(file_path)?;
+        let slides = if archive.index_for_name("ppt/presentation.xml").is_some() {
+            self.extract_text_from_pptx(&mut archive, file)?
+        } else {
+            self.extract_text_from_odp(&mut archive, file)?
+        };
+
+        Ok(clean_extracted_text(&render_slides(&slides), MAX_LINES, MAX_CHARS))
+    }
+}
+
+impl Default for PresentationReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Chaque diapositive commence par sa marque (`SLIDE_MARK`), qui permet de situer les extraits
+fn render_slides(slides: &[Slide]) -> String {
+    let mut text = String::new();
+    for (index, slide) in slides.iter().enumerate() {
+        text.push_str(&format!("{}{}\n{}\n{}\n{}\n", SLIDE_MARK, index + 1, slide.title, slide.body, slide.notes));
+    }
+    text
+}
+
+/// Identifiants de relation des diapositives, dans l'ordre de la présentation (`p:sldIdLst`)
+fn slide_ids(xml: &str) -> AppResult<Vec<String>> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut ids = Vec::new();
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sldId" => {
+                // `id` numérique et `r:id` partagent le même nom local : garder la relation (préfixée)
+                let relationship = e.attributes()
+                    .flatten()
+                    .find(|attribute| attribute.key.prefix().is_some() && attribute.key.local_name().as_ref() == b"id")
+                    .and_then(|attribute| attribute.unescape_value().ok().map(|value| value.into_owned()));
+                ids.extend(relationship);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(ids)
+}
+
+/// Texte de chaque forme (zone de texte, tableau) d'une diapositive ou d'une page de notes,
+/// avec le type de son espace réservé (`p:ph`) : `None` pour une forme libre
+fn shape_texts(xml: &str) -> AppResult<Vec<(Option<String>, String)>> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut shapes = Vec::new();
+    let mut current: Option<(Option<String>, String)> = None;
+    // Les formes peuvent être imbriquées (groupes) : la plus externe reçoit le texte
+    let mut depth = 0usize;
+    let mut in_text = false;
+
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"sp" | b"graphicFrame" => {
+                    if depth == 0 {
+                        current = Some((None, String::new()));
+                    }
+                    depth += 1;
+                }
+                b"ph" => set_placeholder(&mut current, attribute(&e, "type")),
+                b"t" => in_text = true,
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"ph" => set_placeholder(&mut current, attribute(&e, "type")),
+                b"br" => {
+                    if let Some((_, text)) = current.as_mut() {
+                        text.push('\n');
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => {
+                    if let Some((_, text)) = current.as_mut() {
+                        text.push('\n');
+                    }
+                }
+                b"tc" => {
+                    if let Some((_, text)) = current.as_mut() {
+                        text.push('\t');
+                    }
+                }
+                b"sp" | b"graphicFrame" => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        shapes.extend(current.take());
+                    }
+                }
+                _ => {}
+            },
+            event @ (Event::Text(_) | Event::CData(_) | Event::GeneralRef(_)) if in_text => {
+                if let Some((_, text)) = current.as_mut() {
+                    push_text(text, &event)?;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(shapes)
+}
+
+/// Un espace réservé sans type est un « obj » (contenu)
+fn set_placeholder(current: &mut Option<(Option<String>, String)>, kind: Option<String>) {
+    if let Some((placeholder, _)) = current.as_mut() {
+        *placeholder = Some(kind.unwrap_or_else(|| "obj".to_string()));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FrameRole {
+    Title,
+    Ignored,
+    Other,
+}
+
+/// Pages (`draw:page`) de `content.xml` : cadres de titre, autres cadres, puis notes de l'orateur
+fn odp_slides(xml: &str) -> AppResult<Vec<Slide>> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut slides = Vec::new();
+    let mut slide: Option<Slide> = None;
+    let mut frames: Vec<FrameRole> = Vec::new();
+    let mut notes_depth = 0usize;
+    let mut paragraph_depth = 0usize;
+    let mut skip_depth = 0usize;
+
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) if skip_depth > 0 || e.local_name().as_ref() == b"annotation" => skip_depth += 1,
+            Event::End(_) if skip_depth > 0 => skip_depth -= 1,
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"page" => slide = Some(Slide::default()),
+                b"notes" => notes_depth += 1,
+                b"frame" => {
+                    let role = match attribute(&e, "class").as_deref() {
+                        Some("title") => FrameRole::Title,
+                        Some(class) if ODP_IGNORED_CLASSES.contains(&class) => FrameRole::Ignored,
+                        _ => FrameRole::Other,
+                    };
+                    frames.push(role);
+                }
+                b"p" | b"h" => paragraph_depth += 1,
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"s" | b"tab" | b"line-break" if paragraph_depth > 0 => {
+                    if let Some(target) = odp_target(&mut slide, &frames, notes_depth) {
+                        target.push(' ');
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"page" => slides.extend(slide.take()),
+                b"notes" => notes_depth = notes_depth.saturating_sub(1),
+                b"frame" => {
+                    frames.pop();
+                }
+                b"p" | b"h" => {
+                    paragraph_depth = paragraph_depth.saturating_sub(1);
+                    if let Some(target) = odp_target(&mut slide, &frames, notes_depth) {
+                        target.push('\n');
+                    }
+                }
+                _ => {}
+            },
+            event @ (Event::Text(_) | Event::CData(_) | Event::GeneralRef(_)) if paragraph_depth > 0 => {
+                if let Some(target) = odp_target(&mut slide, &frames, notes_depth) {
+                    push_text(target, &event)?;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(slides)
+}
+
+fn odp_target<'a>(slide: &'a mut Option<Slide>, frames: &[FrameRole], notes_depth: usize) -> Option<&'a mut String> {
+    let slide = slide.as_mut()?;
+    if frames.contains(&FrameRole::Ignored) {
+        None
+    } else if notes_depth > 0 {
+        Some(&mut slide.notes)
+    } else if frames.contains(&FrameRole::Title) {
+        Some(&mut slide.title)
+    } else {
+        Some(&mut slide.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::readers::office::tests::{file_for, write_zip};
+
+    const P_NS: &str = r#"xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships""#;
+
+    fn shape(placeholder: &str, paragraphs: &[&str]) -> String {
+        let paragraphs: String = paragraphs.iter().map(|text| format!("<a:p><a:r><a:t>{}</a:t></a:r></a:p>", text)).collect();
+        format!("<p:sp><p:nvSpPr><p:nvPr>{}</p:nvPr></p:nvSpPr><p:txBody>{}</p:txBody></p:sp>", placeholder, paragraphs)
+    }
+
+    #[test]
+    fn test_pptx_extracts_slides_in_order_with_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let presentation = format!(r#"<p:presentation {P_NS}><p:sldIdLst>
+            <p:sldId id="256" r:id="rId3"/><p:sldId id="257" r:id="rId2"/>
+        </p:sldIdLst></p:presentation>"#);
+        let rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+            <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide2.xml"/>
+            <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/>
+        </Relationships>"#;
+        let first = format!(
+            r#"<p:sld {P_NS}><p:cSld><p:spTree>{}{}{}</p:spTree></p:cSld></p:sld>"#,
+            shape(r#"<p:ph type="ctrTitle"/>"#, &["Bilan &amp; perspectives"]),
+            shape(r#"<p:ph idx="1"/>"#, &["Croissance de 12 %", "Nouveaux marchés"]),
+            shape(r#"<p:ph type="sldNum" idx="12"/>"#, &["1"]),
+        );
+        let first_rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+            <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/>
+            <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide1.xml"/>
+        </Relationships>"#;
+        let notes = format!(
+            r#"<p:notes {P_NS}><p:cSld><p:spTree>{}{}</p:spTree></p:cSld></p:notes>"#,
+            shape(r#"<p:ph type="sldImg"/>"#, &[]),
+            shape(r#"<p:ph type="body" idx="1"/>"#, &["Rappeler le contexte"]),
+        );
+        let second = format!(
+            r#"<p:sld {P_NS}><p:cSld><p:spTree>{}<p:graphicFrame><a:graphic><a:graphicData><a:tbl>
+                <a:tr><a:tc><a:txBody><a:p><a:r><a:t>Région</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>Ventes</a:t></a:r></a:p></a:txBody></a:tc></a:tr>
+            </a:tbl></a:graphicData></a:graphic></p:graphicFrame>{}</p:spTree></p:cSld></p:sld>"#,
+            shape(r#"<p:ph type="title"/>"#, &["Chiffres"]),
+            shape("", &["Zone libre"]),
+        );
+
+        let path = write_zip(&dir, "bilan.pptx", &[
+            ("[Content_Types].xml", "<Types/>"),
+            ("ppt/presentation.xml", &presentation),
+            ("ppt/_rels/presentation.xml.rels", rels),
+            ("ppt/slides/slide1.xml", &first),
+            ("ppt/slides/_rels/slide1.xml.rels", first_rels),
+            ("ppt/notesSlides/notesSlide1.xml", &notes),
+            ("ppt/slides/slide2.xml", &second),
+        ]);
+
+        let text = PresentationReader::new().read(&file_for(path)).unwrap();
+
+        assert_eq!(
+            text,
+            "\u{E002}1 Bilan & perspectives Croissance de 12 % Nouveaux marchés Rappeler le contexte \
+             \u{E002}2 Chiffres Région Ventes Zone libre"
+        );
+    }
+
+    #[test]
+    fn test_odp_extracts_titles_bodies_and_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = r#"<office:document-content
+            xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+            xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"
+            xmlns:presentation="urn:oasis:names:tc:opendocument:xmlns:presentation:1.0"
+            xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+            <office:body><office:presentation>
+                <draw:page draw:name="page1">
+                    <draw:frame presentation:class="title"><draw:text-box><text:p>Lancement</text:p></draw:text-box></draw:frame>
+                    <draw:frame presentation:class="outline"><draw:text-box>
+                        <text:list><text:list-item><text:p>Calendrier<text:s/>serré</text:p></text:list-item></text:list>
+                    </draw:text-box></draw:frame>
+                    <draw:frame presentation:class="page-number"><draw:text-box><text:p>1</text:p></draw:text-box></draw:frame>
+                    <presentation:notes>
+                        <draw:page-thumbnail presentation:class="page"/>
+                        <draw:frame presentation:class="notes"><draw:text-box><text:p>Insister sur la date</text:p></draw:text-box></draw:frame>
+                    </presentation:notes>
+                </draw:page>
+                <draw:page draw:name="page2">
+                    <draw:frame presentation:class="title"><draw:text-box><text:p>Questions</text:p></draw:text-box></draw:frame>
+                </draw:page>
+            </office:presentation></office:body>
+        </office:document-content>"#;
+
+        let path = write_zip(&dir, "lancement.odp", &[
+            ("mimetype", "application/vnd.oasis.opendocument.presentation"),
+            ("content.xml", content),
+        ]);
+
+        let text = PresentationReader::new().read(&file_for(path)).unwrap();
+
+        assert_eq!(
+            text,
+            "\u{E002}1 Lancement Calendrier serré Insister sur la date \u{E002}2 Questions"
+        );
+    }
+
+    #[test]
+    fn test_search_snippet_carries_slide_number() {
+        use crate::domain::entities::content::ExtractedContent;
+        use crate::domain::entities::search::SearchQuery;
+        use crate::infrastructure::repository::pool::RepositoryPool;
+
+        let dir = tempfile::tempdir().unwrap();
+        // La correspondance est loin du début de sa diapositive, hors de l'extrait élargi
+        let paragraphs: String = (1..=40)
+            .map(|i| format!("<text:p>{} de la présentation</text:p>", if i == 30 { "Budget trimestriel" } else { "Rappel général" }))
+            .collect();
+        let content = format!(r#"<office:document-content
+            xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+            xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"
+            xmlns:presentation="urn:oasis:names:tc:opendocument:xmlns:presentation:1.0"
+            xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+            <office:body><office:presentation>
+                <draw:page draw:name="page1">
+                    <draw:frame presentation:class="title"><draw:text-box><text:p>Introduction</text:p></draw:text-box></draw:frame>
+                </draw:page>
+                <draw:page draw:name="page2">
+                    <draw:frame presentation:class="outline"><draw:text-box>{}</draw:text-box></draw:frame>
+                </draw:page>
+                <draw:page draw:name="page3">
+                    <draw:frame presentation:class="title"><draw:text-box><text:p>Conclusion</text:p></draw:text-box></draw:frame>
+                </draw:page>
+            </office:presentation></office:body>
+        </office:document-content>"#, paragraphs);
+        let path = write_zip(&dir, "revue.odp", &[
+            ("mimetype", "application/vnd.oasis.opendocument.presentation"),
+            ("content.xml", &content),
+        ]);
+
+        let file = file_for(path);
+        let text = PresentationReader::new().read(&file).unwrap();
+        // Les marques ne comptent pas comme des mots
+        let content = ExtractedContent::from_text(text.clone());
+        assert_eq!(content.word_count, Some(text.split_whitespace().count() as u32 - 3));
+        let pool = RepositoryPool::open(dir.path().join("slides.db").to_str().unwrap(), 1).unwrap();
+        pool.write(move |repo| {
+            repo.insert(vec![file.clone()])?;
+            repo.update_file_index_status(&file, content, true)
+        }).unwrap();
+
+        let hits = |text: &str| {
+            let query = SearchQuery { text: text.to_string(), search_in_content: true, ..Default::default() };
+            pool.read(|repo| repo.search_hits(&query)).unwrap()
+        };
+        let found = hits("trimestriel");
+        assert_eq!(found.len(), 1);
+        assert!(!found[0].snippets.is_empty());
+        assert!(found[0].snippets.iter().all(|snippet| snippet.starts_with("[Diapositive 2] ")), "{:?}", found[0].snippets);
+        // Marque déjà visible dans l'extrait : elle n'est pas répétée
+        let conclusion = &hits("conclusion")[0].snippets;
+        assert_eq!(conclusion.len(), 1);
+        assert!(conclusion[0].starts_with('…') && conclusion[0].ends_with(" [Diapositive 3] <mark>Conclusion</mark>"), "{:?}", conclusion);
+
+        // Ni le mot « diapositive » ni les numéros ne sont indexés
+        assert!(hits("diapositive").is_empty());
+        assert!(hits("3").is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::domain::ports::reader::Reader;
+use crate::domain::entities::file::File;
+use crate::infrastructure::readers::office::clean_extracted_text;
+use crate::shared::errors::{AppError, AppResult};
+use encoding_rs::{Encoding, WINDOWS_1252};
+use std::fs;
+use std::path::Path;
+
+const MAX_LINES: usize = 5000;
+const MAX_CHARS: usize = 50000;
+// Les images sont stockées en hexadécimal dans le document
+const MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
+
+const RTF_MAGIC: &[u8] = b"{\\rtf";
+
+// Destinations sans texte lisible : tables, métadonnées, images, instructions de champ
+const SKIPPED_DESTINATIONS: [&str; 16] = [
+    "fonttbl", "colortbl", "stylesheet", "info", "pict", "object", "fldinst", "themedata", "colorschememapping",
+    "datastore", "latentstyles", "listtable", "listoverridetable", "rsidtbl", "xmlnstbl", "generator",
+];
+
+/// État hérité par les groupes `{…}` imbriqués
+#[derive(Clone, Copy)]
+struct Group {
+    skip: bool,
+    /// Nombre de caractères de repli suivant un `\uN` (`\ucN`)
+    unicode_skip: usize,
+}
+
+/// Texte produit : les octets de la page de code du document sont décodés par paquets
+struct Output {
+    text: String,
+    pending: Vec<u8>,
+    encoding: &'static Encoding,
+}
+
+impl Output {
+    fn push_byte(&mut self, byte: u8) {
+        self.pending.push(byte);
+    }
+
+    fn push_char(&mut self, ch: char) {
+        self.flush();
+        self.text.push(ch);
+    }
+
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            let (decoded, _, _) = self.encoding.decode(&self.pending);
+            self.text.push_str(&decoded);
+            self.pending.clear();
+        }
+    }
+}
+
+/// Documents RTF : texte du corps, sans mots de contrôle ni destinations techniques
+pub struct RtfReader;
+
+impl RtfReader {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Reader for RtfReader {
+    fn read(&self, file: &File) -> AppResult<String> {
+        let file_path = Path::new(&file.path);
+
+        if !file_path.exists() || !file_path.is_file() {
+            return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", file)));
+        }
+
+        let metadata = fs::metadata(file_path)?;
+        if metadata.len() > MAX_FILE_SIZE {
+            return Err(AppError::Validation(format!("Fichier RTF trop volumineux: {} bytes", metadata.len())));
+        }
+
+        let bytes = fs::read(file_path)?;
+        if !bytes.starts_with(RTF_MAGIC) {
+            return Err(AppError::Validation(format!("Fichier RTF invalide: {}", file.path.display())));
+        }
+
+        Ok(clean_extracted_text(&rtf_to_text(&bytes), MAX_LINES, MAX_CHARS))
+    }
+}
+
+impl Default for RtfReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn rtf_to_text(bytes: &[u8]) -> String {
+    let mut output = Output { text: String::new(), pending: Vec::new(), encoding: WINDOWS_1252 };
+    let mut stack: Vec<Group> = Vec::new();
+    let mut group = Group { skip: false, unicode_skip: 1 };
+    // Caractères de repli restant à ignorer après un `\uN`
+    let mut fallback = 0usize;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'{' => {
+                stack.push(group);
+                index += 1;
+            }
+            b'}' => {
+                group = stack.pop().unwrap_or(group);
+                fallback = 0;
+                index += 1;
+            }
+            b'\r' | b'\n' => index += 1,
+            b'\\' => {
+                let Some(&next) = bytes.get(index + 1) else { break };
+                index += 2;
+                let literal = match next {
+                    b'\\' | b'{' | b'}' => Some(next),
+                    b'\'' => {
+                        let hex = bytes.get(index..index + 2).and_then(|hex| std::str::from_utf8(hex).ok());
+                        index += 2;
+                        hex.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    }
+                    b'~' => Some(b' '),
+                    b'_' => Some(b'-'),
+                    b'*' => {
+                        // Destination facultative inconnue des lecteurs simples
+                        group.skip = true;
+                        None
+                    }
+                    b'\r' | b'\n' => {
+                        if !group.skip {
+                            output.push_char('\n');
+                        }
+                        None
+                    }
+                    letter if letter.is_ascii_alphabetic() => {
+                        let start = index - 1;
+                        while bytes.get(index).is_some_and(u8::is_ascii_alphabetic) {
+                            index += 1;
+                        }
+                        let word = std::str::from_utf8(&bytes[start..index]).unwrap_or_default();
+                        let number_start = index;
+                        if bytes.get(index) == Some(&b'-') {
+                            index += 1;
+                        }
+                        while bytes.get(index).is_some_and(u8::is_ascii_digit) {
+                            index += 1;
+                        }
+                        let parameter: Option<i32> = std::str::from_utf8(&bytes[number_start..index]).ok()
+                            .and_then(|number| number.parse().ok());
+                        // L'espace qui suit un mot de contrôle lui sert de délimiteur
+                        if bytes.get(index) == Some(&b' ') {
+                            index += 1;
+                        }
+
+                        match word {
+                            "bin" => index += parameter.unwrap_or(0).max(0) as usize,
+                            "uc" => group.unicode_skip = parameter.unwrap_or(1).max(0) as usize,
+                            "ansicpg" => output.encoding = codepage(parameter.unwrap_or(1252)),
+                            word if SKIPPED_DESTINATIONS.contains(&word) => group.skip = true,
+                            _ if group.skip => {}
+                            "u" => {
+                                // Paramètre signé sur 16 bits
+                                let code = parameter.unwrap_or(0);
+                                let code = if code < 0 { code + 65536 } else { code };
+                                if let Some(ch) = char::from_u32(code as u32) {
+                                    output.push_char(ch);
+                                }
+                                fallback = group.unicode_skip;
+                            }
+                            "par" | "line" | "sect" | "page" | "row" => output.push_char('\n'),
+                            "tab" | "cell" => output.push_char('\t'),
+                            "emdash" => output.push_char('—'),
+                            "endash" => output.push_char('–'),
+                            "lquote" => output.push_char('‘'),
+                            "rquote" => output.push_char('’'),
+                            "ldblquote" => output.push_char('“'),
+                            "rdblquote" => output.push_char('”'),
+                            "bullet" => output.push_char('•'),
+                            _ => {}
+                        }
+                        None
+                    }
+                    _ => None,
+                };
+
+                if let Some(byte) = literal {
+                    if fallback > 0 {
+                        fallback -= 1;
+                    } else if !group.skip {
+                        output.push_byte(byte);
+                    }
+                }
+            }
+            byte => {
+                if fallback > 0 {
+                    fallback -= 1;
+                } else if !group.skip {
+                    output.push_byte(byte);
+                }
+                index += 1;
+            }
+        }
+    }
+
+    output.flush();
+    output.text
+}
+
+/// Encodage d'une page de code Windows (`\ansicpgN`)
+fn codepage(number: i32) -> &'static Encoding {
+    let label = match number {
+        65001 => "utf-8".to_string(),
+        932 => "shift_jis".to_string(),
+        936 => "gbk".to_string(),
+        949 => "euc-kr".to_string(),
+        950 => "big5".to_string(),
+        10000 => "macintosh".to_string(),
+        number => format!("windows-{}", number),
+    };
+    Encoding::for_label(label.as_bytes()).unwrap_or(WINDOWS_1252)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtf_strips_control_words_and_destinations() {
+        let rtf = br#"{\rtf1\ansi\ansicpg1252\deff0{\fonttbl{\f0\fswiss Arial;}}{\colortbl;\red255\green0\blue0;}
+{\*\generator Riched20 10.0;}{\info{\title Compte rendu}{\author Paul}}
+\viewkind4\uc1\pard\b R\'e9union\b0  du lundi\par
+Budget\tab 12\u8364?\par
+{\field{\*\fldinst HYPERLINK "http://exemple.fr"}{\fldrslt Lien}}\par
+Accolades \{ok\} et barre \\\line fin}"#;
+
+        let text = clean_extracted_text(&rtf_to_text(rtf), MAX_LINES, MAX_CHARS);
+
+        assert_eq!(text, "Réunion du lundi Budget 12€ Lien Accolades {ok} et barre \\ fin");
+    }
+}
@@ -0,0 +1,794 @@
+use crate::domain::ports::reader::Reader;
+use crate::domain::entities::content::ReaderSettings;
+use crate::domain::entities::file::File;
+use crate::infrastructure::readers::office::{
+    attribute, clean_extracted_text, open_archive, push_text, read_entry, read_relationships, read_stream, read_xml_event, resolve_target,
+};
+use crate::shared::errors::{AppError, AppResult};
+use quick_xml::events::Event;
+use quick_xml::Reader as XmlReader;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+const MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
+const MAX_LINES: usize = 20000;
+const MAX_CHARS: usize = 100000;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const OLE_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+// Enregistrements BIFF8 (Excel 97-2003) utiles à l'extraction du texte
+const BIFF_FORMULA: u16 = 0x0006;
+const BIFF_EOF: u16 = 0x000A;
+const BIFF_FILEPASS: u16 = 0x002F;
+const BIFF_CONTINUE: u16 = 0x003C;
+const BIFF_BOUNDSHEET: u16 = 0x0085;
+const BIFF_MULRK: u16 = 0x00BD;
+const BIFF_SST: u16 = 0x00FC;
+const BIFF_LABELSST: u16 = 0x00FD;
+const BIFF_NUMBER: u16 = 0x0203;
+const BIFF_LABEL: u16 = 0x0204;
+const BIFF_BOOLERR: u16 = 0x0205;
+const BIFF_STRING: u16 = 0x0207;
+const BIFF_RK: u16 = 0x027E;
+const BIFF_BOF: u16 = 0x0809;
+
+/// Classeurs Excel (XLSX, XLS) et OpenDocument (ODS) : nom de chaque feuille, puis ses lignes
+pub struct SpreadsheetReader {
+    max_cells: usize,
+}
+
+impl SpreadsheetReader {
+    pub fn new() -> Self {
+        Self::with_max_cells(ReaderSettings::default().spreadsheet_max_cells)
+    }
+
+    /// Limite le nombre de cellules lues ; la ligne d'en-tête de chaque feuille est toujours gardée
+    pub fn with_max_cells(max_cells: usize) -> Self {
+        Self { max_cells }
+    }
+
+    fn extract_text_from_xlsx(&self, archive: &mut ZipArchive<fs::File>, file: &File) -> AppResult<Workbook> {
+        let workbook_xml = read_entry(archive, "xl/workbook.xml")?
+            .ok_or_else(|| AppError::Validation(format!("xl/workbook.xml absent: {}", file.path.display())))?;
+        let targets: HashMap<String, String> = match read_entry(archive, "xl/_rels/workbook.xml.rels")? {
+            Some(xml) => read_relationships(&xml)?.into_iter()
+                .map(|relationship| (relationship.id, resolve_target("xl", &relationship.target)))
+                .collect(),
+            None => HashMap::new(),
+        };
+        let shared_strings = match read_entry(archive, "xl/sharedStrings.xml")? {
+            Some(xml) => shared_strings(&xml)?,
+            None => Vec::new(),
+        };
+
+        let mut workbook = Workbook::new(self.max_cells);
+        for (index, (name, relationship)) in sheet_entries(&workbook_xml)?.into_iter().enumerate() {
+            let part = targets.get(&relationship)
+                .cloned()
+                .unwrap_or_else(|| format!("xl/worksheets/sheet{}.xml", index + 1));
+            workbook.start_sheet(name);
+            if let Some(xml) = read_entry(archive, &part)? {
+                read_xlsx_sheet(&xml, &shared_strings, &mut workbook)?;
+            }
+        }
+        Ok(workbook)
+    }
+
+    fn extract_text_from_ods(&self, archive: &mut ZipArchive<fs::File>, file: &File) -> AppResult<Workbook> {
+        let content = read_entry(archive, "content.xml")?
+            .ok_or_else(|| AppError::Validation(format!("content.xml absent: {}", file.path.display())))?;
+
+        let mut workbook = Workbook::new(self.max_cells);
+        read_ods_content(&content, &mut workbook)?;
+        Ok(workbook)
+    }
+
+    fn extract_text_from_xls(&self, file: &File) -> AppResult<Workbook> {
+        let mut compound = cfb::open(&file.path)
+            .map_err(|e| AppError::Validation(format!("Classeur Excel invalide {}: {}", file.path.display(), e)))?;
+        let stream = read_stream(&mut compound, "/Workbook")
+            .or_else(|_| read_stream(&mut compound, "/Book"))?;
+
+        let mut workbook = Workbook::new(self.max_cells);
+        read_biff(&stream, &mut workbook).map_err(|e| match e {
+            AppError::Validation(message) => AppError::Validation(format!("{}: {}", message, file.path.display())),
+            e => e,
+        })?;
+        Ok(workbook)
+    }
+}
+
+impl Reader for SpreadsheetReader {
+    fn read(&self, file: &File) -> AppResult<String> {
+        let file_path = Path::new(&file.path);
+
+        if !file_path.exists() || !file_path.is_file() {
+            return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", file)));
+        }
+
+        let metadata = fs::metadata(file_path)?;
+        if metadata.len() > MAX_FILE_SIZE {
+            return Err(AppError::Validation(format!("Classeur trop volumineux: {} bytes", metadata.len())));
+        }
+
+        // Le format réel est déterminé par la signature, l'extension pouvant mentir
+        let mut magic = [0u8; 8];
+        let read = fs::File::open(file_path)?.read(&mut magic)?;
+        let magic = &magic[..read];
+
+        let workbook = if magic.starts_with(ZIP_MAGIC) {
+            let mut archive = open_archive(file_path)?;
+            if archive.index_for_name("xl/workbook.xml").is_some() {
+                self.extract_text_from_xlsx(&mut archive, file)?
+            } else {
+                self.extract_text_from_ods(&mut archive, file)?
+            }
+        } else if magic.starts_with(OLE_MAGIC) {
+            self.extract_text_from_xls(file)?
+        } else {
+            return Err(AppError::Validation(format!("Format de classeur non reconnu: {}", file.path.display())));
+        };
+
+        Ok(clean_extracted_text(&workbook.into_text(), MAX_LINES, MAX_CHARS))
+    }
+}
+
+impl Default for SpreadsheetReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Sheet {
+    name: String,
+    rows: Vec<Vec<String>>,
+}
+
+/// Feuilles lues jusqu'ici. Passé `max_cells`, les lignes suivantes sont refusées, sauf la
+/// première ligne non vide (l'en-tête) de chaque feuille.
+struct Workbook {
+    sheets: Vec<Sheet>,
+    max_cells: usize,
+    cells: usize,
+    sheet_full: bool,
+    /// Ligne en cours d'assemblage cellule par cellule (XLS)
+    pending: Option<(u16, Vec<String>)>,
+}
+
+impl Workbook {
+    fn new(max_cells: usize) -> Self {
+        Self { sheets: Vec::new(), max_cells, cells: 0, sheet_full: false, pending: None }
+    }
+
+    fn start_sheet(&mut self, name: String) {
+        self.flush_pending();
+        self.sheets.push(Sheet { name, rows: Vec::new() });
+        self.sheet_full = false;
+    }
+
+    /// Ajoute une ligne à la feuille en cours ; `false` une fois la limite atteinte
+    fn push_row(&mut self, row: Vec<String>) -> bool {
+        if self.sheet_full {
+            return false;
+        }
+        let Some(sheet) = self.sheets.last_mut() else { return false };
+        if row.is_empty() {
+            return true;
+        }
+        if !sheet.rows.is_empty() && self.cells + row.len() > self.max_cells {
+            self.sheet_full = true;
+            return false;
+        }
+        self.cells += row.len();
+        sheet.rows.push(row);
+        true
+    }
+
+    /// Ajoute une cellule ; les cellules arrivent ligne par ligne, dans l'ordre
+    fn push_cell(&mut self, row: u16, text: String) {
+        if self.sheet_full || text.trim().is_empty() {
+            return;
+        }
+        if self.pending.as_ref().is_some_and(|(number, _)| *number != row) {
+            self.flush_pending();
+        }
+        self.pending.get_or_insert_with(|| (row, Vec::new())).1.push(text);
+    }
+
+    fn flush_pending(&mut self) {
+        if let Some((_, row)) = self.pending.take() {
+            self.push_row(row);
+        }
+    }
+
+    fn into_text(mut self) -> String {
+        self.flush_pending();
+        let mut text = String::new();
+        for sheet in &self.sheets {
+            text.push_str(&sheet.name);
+            text.push('\n');
+            for row in &sheet.rows {
+                text.push_str(&row.join("\t"));
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+/// Nombre tel qu'affiché : sans décimale s'il est entier
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Feuilles du classeur XLSX dans l'ordre : (nom, identifiant de relation)
+fn sheet_entries(xml: &str) -> AppResult<Vec<(String, String)>> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut sheets = Vec::new();
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+                sheets.push((attribute(&e, "name").unwrap_or_default(), attribute(&e, "id").unwrap_or_default()));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(sheets)
+}
+
+/// Table des chaînes partagées, sans les indications phonétiques (`rPh`)
+fn shared_strings(xml: &str) -> AppResult<Vec<String>> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    let mut phonetic_depth = 0usize;
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"si" => current.clear(),
+                b"t" => in_text = true,
+                b"rPh" => phonetic_depth += 1,
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"si" => strings.push(std::mem::take(&mut current)),
+                b"t" => in_text = false,
+                b"rPh" => phonetic_depth = phonetic_depth.saturating_sub(1),
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            event @ (Event::Text(_) | Event::CData(_) | Event::GeneralRef(_)) if in_text && phonetic_depth == 0 => {
+                push_text(&mut current, &event)?;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+fn read_xlsx_sheet(xml: &str, shared_strings: &[String], workbook: &mut Workbook) -> AppResult<()> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut row = Vec::new();
+    // Type (attribut `t`) et valeur de la cellule en cours
+    let mut cell: Option<(Option<String>, String)> = None;
+    let mut in_value = false;
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"c" => cell = Some((attribute(&e, "t"), String::new())),
+                b"v" | b"t" => in_value = true,
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"v" | b"t" => in_value = false,
+                b"c" => {
+                    if let Some(text) = cell.take().and_then(|(kind, value)| xlsx_cell_text(kind.as_deref(), &value, shared_strings)) {
+                        row.push(text);
+                    }
+                }
+                b"row" => {
+                    // Limite atteinte : le reste de la feuille est ignoré
+                    let accepted = workbook.push_row(std::mem::take(&mut row));
+                    if !accepted {
+                        break;
+                    }
+                }
+                _ => {}
+            },
+            event @ (Event::Text(_) | Event::CData(_) | Event::GeneralRef(_)) if in_value => {
+                if let Some((_, value)) = cell.as_mut() {
+                    push_text(value, &event)?;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn xlsx_cell_text(kind: Option<&str>, value: &str, shared_strings: &[String]) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    let text = match kind {
+        Some("s") => value.parse::<usize>().ok().and_then(|index| shared_strings.get(index)).cloned()?,
+        Some("b") => if value == "1" { "TRUE" } else { "FALSE" }.to_string(),
+        // Erreurs de formule (#DIV/0!…)
+        Some("e") => return None,
+        Some("str") | Some("inlineStr") => value.to_string(),
+        _ => value.parse::<f64>().map(format_number).unwrap_or_else(|_| value.to_string()),
+    };
+    Some(text).filter(|text| !text.trim().is_empty())
+}
+
+/// Tableaux de `content.xml` : texte affiché des cellules, sans les commentaires
+fn read_ods_content(xml: &str, workbook: &mut Workbook) -> AppResult<()> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut row = Vec::new();
+    // Texte et valeur brute (`office:value`) de la cellule en cours
+    let mut cell: Option<(String, Option<String>)> = None;
+    let mut skip_depth = 0usize;
+    loop {
+        match read_xml_event(&mut reader)? {
+            Event::Start(e) if skip_depth > 0 || e.local_name().as_ref() == b"annotation" => skip_depth += 1,
+            Event::End(_) if skip_depth > 0 => skip_depth -= 1,
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"table" => workbook.start_sheet(attribute(&e, "name").unwrap_or_default()),
+                b"table-row" => row.clear(),
+                b"table-cell" | b"covered-table-cell" => cell = Some((String::new(), attribute(&e, "value"))),
+                b"p" => {
+                    // Paragraphes d'une même cellule
+                    if let Some((text, _)) = cell.as_mut().filter(|(text, _)| !text.is_empty()) {
+                        text.push(' ');
+                    }
+                }
+                _ => {}
+            },
+            Event::Empty(e) if skip_depth == 0 => match e.local_name().as_ref() {
+                b"table-cell" => row.extend(attribute(&e, "value")),
+                b"s" | b"tab" | b"line-break" => {
+                    if let Some((text, _)) = cell.as_mut() {
+                        text.push(' ');
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"table-cell" | b"covered-table-cell" => {
+                    if let Some((text, value)) = cell.take() {
+                        let text = if text.trim().is_empty() { value.unwrap_or_default() } else { text };
+                        if !text.trim().is_empty() {
+                            row.push(text.trim().to_string());
+                        }
+                    }
+                }
+                b"table-row" => {
+                    workbook.push_row(std::mem::take(&mut row));
+                }
+                _ => {}
+            },
+            event @ (Event::Text(_) | Event::CData(_) | Event::GeneralRef(_)) if skip_depth == 0 => {
+                if let Some((text, _)) = cell.as_mut() {
+                    push_text(text, &event)?;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+struct BiffRecord<'a> {
+    /// Position dans le flux, référencée par BOUNDSHEET
+    offset: usize,
+    kind: u16,
+    data: &'a [u8],
+}
+
+fn biff_records(stream: &[u8]) -> Vec<BiffRecord<'_>> {
+    let mut records = Vec::new();
+    let mut offset = 0;
+    while let (Some(kind), Some(length)) = (read_u16(stream, offset), read_u16(stream, offset + 2)) {
+        let Some(data) = stream.get(offset + 4..offset + 4 + length as usize) else { break };
+        records.push(BiffRecord { offset, kind, data });
+        offset += 4 + length as usize;
+    }
+    records
+}
+
+/// Classeur BIFF8 : noms des feuilles (BOUNDSHEET), chaînes partagées (SST), puis les cellules
+/// de chaque feuille, valeurs en cache des formules comprises
+fn read_biff(stream: &[u8], workbook: &mut Workbook) -> AppResult<()> {
+    let records = biff_records(stream);
+
+    let mut sheet_names = HashMap::new();
+    let mut shared_strings = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        match record.kind {
+            BIFF_FILEPASS => return Err(AppError::Validation("Classeur Excel chiffré".to_string())),
+            // Feuilles de calcul uniquement (pas de graphiques ni de macros)
+            BIFF_BOUNDSHEET if record.data.get(5) == Some(&0) => {
+                if let (Some(position), Some(name)) = (read_u32(record.data, 0), short_string(record.data.get(6..).unwrap_or_default())) {
+                    sheet_names.insert(position as usize, name);
+                }
+            }
+            BIFF_SST if record.data.len() >= 8 => {
+                let parts = std::iter::once(&record.data[8..])
+                    .chain(records[index + 1..].iter().take_while(|next| next.kind == BIFF_CONTINUE).map(|next| next.data))
+                    .collect();
+                shared_strings = read_shared_strings(Fragments::new(parts), read_u32(record.data, 4).unwrap_or(0));
+            }
+            _ => {}
+        }
+    }
+
+    let mut in_sheet = false;
+    // Cellule dont la formule renvoie une chaîne, donnée par l'enregistrement STRING suivant
+    let mut string_formula = None;
+    for record in &records {
+        let data = record.data;
+        let row = read_u16(data, 0).unwrap_or(0);
+        match record.kind {
+            BIFF_BOF => {
+                in_sheet = match sheet_names.get(&record.offset) {
+                    Some(name) => {
+                        workbook.start_sheet(name.clone());
+                        true
+                    }
+                    None => false,
+                };
+            }
+            BIFF_EOF => in_sheet = false,
+            _ if !in_sheet => {}
+            BIFF_LABELSST => {
+                if let Some(text) = read_u32(data, 6).and_then(|index| shared_strings.get(index as usize)) {
+                    workbook.push_cell(row, text.clone());
+                }
+            }
+            BIFF_LABEL => {
+                if let Some(text) = unicode_string(data.get(6..).unwrap_or_default()) {
+                    workbook.push_cell(row, text);
+                }
+            }
+            BIFF_NUMBER => {
+                if let Some(value) = read_f64(data, 6) {
+                    workbook.push_cell(row, format_number(value));
+                }
+            }
+            BIFF_RK => {
+                if let Some(rk) = read_u32(data, 6) {
+                    workbook.push_cell(row, format_number(rk_value(rk)));
+                }
+            }
+            BIFF_MULRK => {
+                // Ligne, première colonne, puis (format, RK) pour chaque colonne, dernière colonne
+                for cell in data.get(4..data.len().saturating_sub(2)).unwrap_or_default().chunks_exact(6) {
+                    if let Some(rk) = read_u32(cell, 2) {
+                        workbook.push_cell(row, format_number(rk_value(rk)));
+                    }
+                }
+            }
+            BIFF_FORMULA => match data.get(6..14) {
+                // Résultat non numérique : 0 = chaîne, 1 = booléen, 2 = erreur
+                Some(result) if result[6..8] == [0xFF, 0xFF] => match result[0] {
+                    0 => string_formula = Some(row),
+                    1 => workbook.push_cell(row, if result[2] == 1 { "TRUE" } else { "FALSE" }.to_string()),
+                    _ => {}
+                },
+                Some(_) => {
+                    if let Some(value) = read_f64(data, 6) {
+                        workbook.push_cell(row, format_number(value));
+                    }
+                }
+                None => {}
+            },
+            BIFF_STRING => {
+                if let (Some(row), Some(text)) = (string_formula.take(), unicode_string(data)) {
+                    workbook.push_cell(row, text);
+                }
+            }
+            BIFF_BOOLERR if data.get(7) == Some(&0) => {
+                workbook.push_cell(row, if data.get(6) == Some(&1) { "TRUE" } else { "FALSE" }.to_string());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Nombre compressé RK : entier sur 30 bits ou poids forts d'un flottant, éventuellement ÷ 100
+fn rk_value(rk: u32) -> f64 {
+    let value = if rk & 0x02 != 0 {
+        ((rk as i32) >> 2) as f64
+    } else {
+        f64::from_bits(((rk & 0xFFFF_FFFC) as u64) << 32)
+    };
+    if rk & 0x01 != 0 { value / 100.0 } else { value }
+}
+
+/// Caractères d'une chaîne BIFF8 : Latin-1 compressé ou UTF-16LE
+fn decode_chars(bytes: &[u8], wide: bool) -> String {
+    if wide {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        bytes.iter().map(|&byte| byte as char).collect()
+    }
+}
+
+/// ShortXLUnicodeString : longueur sur un octet
+fn short_string(data: &[u8]) -> Option<String> {
+    let count = *data.first()? as usize;
+    let wide = data.get(1)? & 0x01 != 0;
+    let width = if wide { 2 } else { 1 };
+    data.get(2..2 + count * width).map(|bytes| decode_chars(bytes, wide))
+}
+
+/// XLUnicodeString : longueur sur deux octets
+fn unicode_string(data: &[u8]) -> Option<String> {
+    let count = read_u16(data, 0)? as usize;
+    let wide = data.get(2)? & 0x01 != 0;
+    let width = if wide { 2 } else { 1 };
+    data.get(3..3 + count * width).map(|bytes| decode_chars(bytes, wide))
+}
+
+/// Données de la SST réparties entre l'enregistrement et ses CONTINUE
+struct Fragments<'a> {
+    parts: Vec<&'a [u8]>,
+    part: usize,
+    position: usize,
+}
+
+impl<'a> Fragments<'a> {
+    fn new(parts: Vec<&'a [u8]>) -> Self {
+        Self { parts, part: 0, position: 0 }
+    }
+
+    fn available(&self) -> usize {
+        self.parts.get(self.part).map_or(0, |part| part.len() - self.position)
+    }
+
+    fn next_part(&mut self) -> bool {
+        if self.part + 1 >= self.parts.len() {
+            return false;
+        }
+        self.part += 1;
+        self.position = 0;
+        true
+    }
+
+    /// `count` octets, au besoin à cheval sur plusieurs fragments
+    fn take(&mut self, count: usize) -> Option<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(count);
+        while bytes.len() < count {
+            if self.available() == 0 && !self.next_part() {
+                return None;
+            }
+            let length = self.available().min(count - bytes.len());
+            bytes.extend_from_slice(&self.parts[self.part][self.position..self.position + length]);
+            self.position += length;
+        }
+        Some(bytes)
+    }
+}
+
+fn read_shared_strings(mut fragments: Fragments, count: u32) -> Vec<String> {
+    let mut strings = Vec::new();
+    for _ in 0..count {
+        match read_shared_string(&mut fragments) {
+            Some(text) => strings.push(text),
+            None => break,
+        }
+    }
+    strings
+}
+
+/// Chaîne de la SST ; un CONTINUE au milieu des caractères recommence par un octet d'options
+/// qui indique leur largeur pour la suite
+fn read_shared_string(fragments: &mut Fragments) -> Option<String> {
+    let header = fragments.take(3)?;
+    let mut remaining = u16::from_le_bytes([header[0], header[1]]) as usize;
+    let options = header[2];
+    let runs = if options & 0x08 != 0 { read_u16(&fragments.take(2)?, 0)? as usize } else { 0 };
+    let extension = if options & 0x04 != 0 { read_u32(&fragments.take(4)?, 0)? as usize } else { 0 };
+
+    let mut wide = options & 0x01 != 0;
+    let mut text = String::new();
+    while remaining > 0 {
+        if fragments.available() == 0 {
+            if !fragments.next_part() {
+                return None;
+            }
+            wide = fragments.take(1)?[0] & 0x01 != 0;
+        }
+        let width = if wide { 2 } else { 1 };
+        let count = remaining.min(fragments.available() / width);
+        if count == 0 {
+            return None;
+        }
+        text.push_str(&decode_chars(&fragments.take(count * width)?, wide));
+        remaining -= count;
+    }
+
+    // Mise en forme (4 octets par plage) et données phonétiques
+    fragments.take(4 * runs + extension)?;
+    Some(text)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    bytes.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    bytes.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_f64(bytes: &[u8], offset: usize) -> Option<f64> {
+    bytes.get(offset..offset + 8).map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::readers::office::tests::{file_for, write_zip};
+    use std::io::Write;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    const S_NS: &str = r#"xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main""#;
+    const R_NS: &str = r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships""#;
+
+    fn xlsx_fixture(dir: &TempDir) -> PathBuf {
+        let workbook = format!(r#"<workbook {S_NS} {R_NS}><sheets>
+            <sheet name="Budget" sheetId="1" r:id="rId2"/>
+            <sheet name="Équipe" sheetId="2" r:id="rId1"/>
+        </sheets></workbook>"#);
+        let rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+            <Relationship Id="rId1" Type="worksheet" Target="worksheets/sheet2.xml"/>
+            <Relationship Id="rId2" Type="worksheet" Target="/xl/worksheets/sheet1.xml"/>
+            <Relationship Id="rId3" Type="sharedStrings" Target="sharedStrings.xml"/>
+        </Relationships>"#;
+        let shared = format!(r#"<sst {S_NS} count="4" uniqueCount="4">
+            <si><t>Poste</t></si>
+            <si><t>Montant</t></si>
+            <si><r><t>Loyer </t></r><r><rPr><b/></rPr><t>&amp; charges</t></r><rPh><t>ろうや</t></rPh></si>
+            <si><t>Nom</t></si>
+        </sst>"#);
+        let budget = format!(r#"<worksheet {S_NS}><sheetData>
+            <row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row>
+            <row r="2"><c r="A2" t="s"><v>2</v></c><c r="B2"><v>1250.5</v></c><c r="C2" t="b"><v>1</v></c></row>
+            <row r="3"><c r="A3" t="inlineStr"><is><t>Total</t></is></c><c r="B3"><f>SUM(B2:B2)</f><v>1250.5</v></c><c r="C3" t="e"><v>#DIV/0!</v></c></row>
+            <row r="4"><c r="A4" t="str"><f>A1&amp;"!"</f><v>Poste!</v></c><c r="B4"><v>3</v></c></row>
+        </sheetData></worksheet>"#);
+        let team = format!(r#"<worksheet {S_NS}><sheetData>
+            <row r="1"><c r="A1" t="s"><v>3</v></c></row>
+            <row r="2"><c r="A2" t="inlineStr"><is><t>Alice</t></is></c></row>
+        </sheetData></worksheet>"#);
+
+        write_zip(dir, "budget.xlsx", &[
+            ("[Content_Types].xml", "<Types/>"),
+            ("xl/workbook.xml", &workbook),
+            ("xl/_rels/workbook.xml.rels", rels),
+            ("xl/sharedStrings.xml", &shared),
+            ("xl/worksheets/sheet1.xml", &budget),
+            ("xl/worksheets/sheet2.xml", &team),
+        ])
+    }
+
+    #[test]
+    fn test_xlsx_extracts_sheets_and_cells() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = xlsx_fixture(&dir);
+
+        let text = SpreadsheetReader::new().read(&file_for(path)).unwrap();
+
+        assert_eq!(
+            text,
+            "Budget Poste Montant Loyer & charges 1250.5 TRUE Total 1250.5 Poste! 3 Équipe Nom Alice"
+        );
+    }
+
+    #[test]
+    fn test_cell_cap_keeps_every_header_row() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = xlsx_fixture(&dir);
+
+        let text = SpreadsheetReader::with_max_cells(3).read(&file_for(path)).unwrap();
+
+        assert_eq!(text, "Budget Poste Montant Équipe Nom");
+    }
+
+    #[test]
+    fn test_ods_extracts_tables_without_annotations() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = r#"<office:document-content
+            xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+            xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"
+            xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+            <office:body><office:spreadsheet>
+                <table:table table:name="Ventes">
+                    <table:table-row>
+                        <table:table-cell office:value-type="string"><text:p>Région</text:p></table:table-cell>
+                        <table:table-cell office:value-type="string"><text:p>Chiffre<text:s/>d'affaires</text:p></table:table-cell>
+                    </table:table-row>
+                    <table:table-row table:number-rows-repeated="3"><table:table-cell table:number-columns-repeated="2"/></table:table-row>
+                    <table:table-row>
+                        <table:table-cell office:value-type="string">
+                            <office:annotation><text:p>À vérifier</text:p></office:annotation>
+                            <text:p>Nord</text:p>
+                        </table:table-cell>
+                        <table:table-cell office:value-type="float" office:value="1500"><text:p>1 500,00 €</text:p></table:table-cell>
+                        <table:table-cell office:value-type="float" office:value="42"/>
+                    </table:table-row>
+                </table:table>
+                <table:table table:name="Notes"><table:table-row><table:table-cell><text:p>Fin</text:p></table:table-cell></table:table-row></table:table>
+            </office:spreadsheet></office:body>
+        </office:document-content>"#;
+
+        let path = write_zip(&dir, "ventes.ods", &[
+            ("mimetype", "application/vnd.oasis.opendocument.spreadsheet"),
+            ("content.xml", content),
+        ]);
+
+        let text = SpreadsheetReader::new().read(&file_for(path)).unwrap();
+
+        assert_eq!(text, "Ventes Région Chiffre d'affaires Nord 1 500,00 € 42 Notes Fin");
+    }
+
+    fn record(kind: u16, data: &[u8]) -> Vec<u8> {
+        [&kind.to_le_bytes()[..], &(data.len() as u16).to_le_bytes(), data].concat()
+    }
+
+    fn cell(row: u16, col: u16, value: &[u8]) -> Vec<u8> {
+        [&row.to_le_bytes()[..], &col.to_le_bytes(), &0u16.to_le_bytes(), value].concat()
+    }
+
+    #[test]
+    fn test_legacy_xls_reads_shared_strings_and_numbers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ancien.xls");
+
+        let bof = |kind: u16| record(BIFF_BOF, &[&0x0600u16.to_le_bytes()[..], &kind.to_le_bytes(), &[0; 12]].concat());
+        // Deux chaînes ; la seconde, « Montant été », passe en UTF-16 dans le CONTINUE
+        let sst = record(BIFF_SST, &[&2u32.to_le_bytes()[..], &2u32.to_le_bytes(), &[3, 0, 0], b"Nom", &[11, 0, 0], b"Montant "].concat());
+        let continued = record(BIFF_CONTINUE, &[&[1u8][..], &"été".encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<_>>()].concat());
+        let boundsheet = |position: u32| record(BIFF_BOUNDSHEET, &[&position.to_le_bytes()[..], &[0, 0, 6, 0], b"Feuil1"].concat());
+
+        let globals_length = [bof(0x0005), boundsheet(0), sst.clone(), continued.clone(), record(BIFF_EOF, &[])].concat().len();
+        let globals = [bof(0x0005), boundsheet(globals_length as u32), sst, continued, record(BIFF_EOF, &[])].concat();
+        let sheet = [
+            bof(0x0010),
+            record(BIFF_LABELSST, &cell(0, 0, &0u32.to_le_bytes())),
+            record(BIFF_LABELSST, &cell(0, 1, &1u32.to_le_bytes())),
+            record(BIFF_LABEL, &cell(1, 0, &[&5u16.to_le_bytes()[..], &[0], b"Alice"].concat())),
+            record(BIFF_NUMBER, &cell(1, 1, &42.5f64.to_le_bytes())),
+            record(BIFF_RK, &cell(2, 0, &((7u32 << 2) | 0x02).to_le_bytes())),
+            record(BIFF_FORMULA, &cell(2, 1, &[&[0u8, 0, 0, 0, 0, 0, 0xFF, 0xFF][..], &[0; 6]].concat())),
+            record(BIFF_STRING, &[&5u16.to_le_bytes()[..], &[0], b"Bilan"].concat()),
+            record(BIFF_EOF, &[]),
+        ].concat();
+
+        let mut compound = cfb::create(&path).unwrap();
+        compound.create_stream("/Workbook").unwrap().write_all(&[globals, sheet].concat()).unwrap();
+        compound.flush().unwrap();
+        drop(compound);
+
+        let text = SpreadsheetReader::new().read(&file_for(path)).unwrap();
+
+        assert_eq!(text, "Feuil1 Nom Montant été Alice 42.5 7 Bilan");
+    }
+}
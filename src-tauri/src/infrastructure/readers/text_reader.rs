@@ -1,9 +1,10 @@
 use crate::domain::ports::reader::Reader;
+use crate::domain::entities::content::ExtractedContent;
 use crate::domain::entities::file::File;
-use crate::shared::errors::{AppError, AppResult};
-use std::fs;
-use std::io::Read;
-use std::path::Path;
+use crate::infrastructure::readers::encoding::{read_text_file, DecodedText};
+use crate::shared::errors::AppResult;
+
+const MAX_TEXT_SIZE: u64 = 10 * 1024 * 1024;
 
 pub struct TextReader;
 
@@ -11,31 +12,20 @@ impl TextReader {
     pub fn new() -> Self {
         Self
     }
+
+    fn read_source(&self, file: &File) -> AppResult<DecodedText> {
+        read_text_file(&file.path, MAX_TEXT_SIZE)
+    }
 }
 
 impl Reader for TextReader {
     fn read(&self, file: &File) -> AppResult<String> {
-        let file_path = Path::new(&file.path);
-        
-        if !file_path.exists() || !file_path.is_file() {
-            return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", file)));
-        }
-
-        let metadata = fs::metadata(file_path)
-            .map_err(|e| AppError::FileSystem(e))?;
-        
-        if metadata.len() > 10 * 1024 * 1024 {
-            return Err(AppError::Validation(format!("Fichier trop volumineux: {} bytes", metadata.len())));
-        }
-
-        let mut file = fs::File::open(file_path)
-            .map_err(|e| AppError::FileSystem(e))?;
-        
-        let mut content = String::new();
-        file.read_to_string(&mut content)
-            .map_err(|e| AppError::FileSystem(e))?;
+        Ok(self.read_source(file)?.text)
+    }
 
-        Ok(content)
+    fn extract(&self, file: &File) -> AppResult<ExtractedContent> {
+        let source = self.read_source(file)?;
+        Ok(ExtractedContent::from_source(source.text.clone(), &source.text, Some(source.encoding.to_string())))
     }
 }
 
@@ -43,4 +33,4 @@ impl Default for TextReader {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+}
@@ -1,9 +1,38 @@
 use crate::domain::ports::reader::Reader;
 use crate::domain::entities::file::File;
+use crate::infrastructure::readers::office::{clean_extracted_text, entry_names, open_archive, read_entry, read_stream, xml_to_text, XmlTextRules};
+use zip::ZipArchive;
 use crate::shared::errors::{AppError, AppResult};
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 
+const MAX_LINES: usize = 5000;
+const MAX_CHARS: usize = 50000;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const OLE_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+// Texte OOXML : uniquement le contenu des balises w:t
+const DOCX_RULES: XmlTextRules = XmlTextRules {
+    text_tags: &["t"],
+    block_tags: &["p", "tr"],
+    cell_tags: &["tc"],
+    break_tags: &["br", "cr"],
+    space_tags: &["tab"],
+    skip_tags: &["instrText", "delText"],
+};
+
+// Texte OpenDocument : tout le texte du corps, hors modifications suivies
+const ODT_RULES: XmlTextRules = XmlTextRules {
+    text_tags: &[],
+    block_tags: &["p", "h", "list-item", "table-row"],
+    cell_tags: &["table-cell"],
+    break_tags: &["line-break"],
+    space_tags: &["s", "tab"],
+    skip_tags: &["tracked-changes", "automatic-styles", "font-face-decls"],
+};
+
 pub struct WordReader;
 
 impl WordReader {
@@ -11,28 +40,100 @@ impl WordReader {
         Self
     }
 
-    fn extract_text_from_docx(&self, _file: &File) -> AppResult<String> {
-        Err(AppError::Internal("Lecture des fichiers Word non encore implémentée".to_string()))
+    /// `document` : contenu de `word/document.xml`, déjà lu pour reconnaître le format
+    fn extract_text_from_docx(&self, archive: &mut ZipArchive<fs::File>, document: &str) -> AppResult<String> {
+        let headers = numbered_parts(archive, "word/header");
+        let footers = numbered_parts(archive, "word/footer");
+
+        // Ordre de lecture naturel : en-têtes, corps, notes, pieds de page
+        let mut text = String::new();
+        for name in &headers {
+            if let Some(xml) = read_entry(archive, name)? {
+                text.push_str(&xml_to_text(&xml, &DOCX_RULES)?);
+            }
+        }
+
+        text.push_str(&xml_to_text(document, &DOCX_RULES)?);
+
+        for name in ["word/footnotes.xml", "word/endnotes.xml"] {
+            if let Some(xml) = read_entry(archive, name)? {
+                text.push_str(&xml_to_text(&xml, &DOCX_RULES)?);
+            }
+        }
+
+        for name in &footers {
+            if let Some(xml) = read_entry(archive, name)? {
+                text.push_str(&xml_to_text(&xml, &DOCX_RULES)?);
+            }
+        }
+
+        Ok(clean_extracted_text(&text, MAX_LINES, MAX_CHARS))
+    }
+
+    fn extract_text_from_odt(&self, archive: &mut ZipArchive<fs::File>, file: &File) -> AppResult<String> {
+        let content = read_entry(archive, "content.xml")?
+            .ok_or_else(|| AppError::Validation(format!("content.xml absent: {}", file.path.display())))?;
+
+        let text = xml_to_text(&content, &ODT_RULES)?;
+        Ok(clean_extracted_text(&text, MAX_LINES, MAX_CHARS))
+    }
+
+    fn extract_text_from_doc(&self, file: &File) -> AppResult<String> {
+        let mut compound = cfb::open(&file.path)
+            .map_err(|e| AppError::Validation(format!("Document Word invalide {}: {}", file.path.display(), e)))?;
+
+        let word_document = read_stream(&mut compound, "/WordDocument")?;
+        let fib = Fib::parse(&word_document)?;
+
+        if fib.encrypted {
+            return Err(AppError::Validation(format!("Document Word chiffré: {}", file.path.display())));
+        }
+
+        let table_name = if fib.which_table_stream { "/1Table" } else { "/0Table" };
+
+        // Lecture via la table des pièces, sinon repli sur les chaînes imprimables
+        let raw = read_stream(&mut compound, table_name)
+            .and_then(|table| extract_pieces(&word_document, &table, &fib))
+            .unwrap_or_else(|e| {
+                tracing::debug!("Table des pièces illisible pour {}: {}", file.path.display(), e);
+                printable_runs(&word_document)
+            });
+
+        Ok(clean_extracted_text(&strip_word_control_chars(&raw), MAX_LINES, MAX_CHARS))
     }
 }
 
 impl Reader for WordReader {
     fn read(&self, file: &File) -> AppResult<String> {
         let file_path = Path::new(&file.path);
-        
+
         if !file_path.exists() || !file_path.is_file() {
             return Err(AppError::NotFound(format!("Le fichier n'existe pas ou n'est pas un fichier: {}", file)));
         }
 
         let metadata = fs::metadata(file_path)
             .map_err(|e| AppError::FileSystem(e))?;
-        
+
         if metadata.len() > 20 * 1024 * 1024 {
             return Err(AppError::Validation(format!("Fichier Word trop volumineux: {} bytes", metadata.len())));
         }
 
-        // Extraire le texte du document Word
-        self.extract_text_from_docx(file)
+        // Le format réel est déterminé par la signature, l'extension pouvant mentir
+        let mut magic = [0u8; 8];
+        let read = fs::File::open(file_path)?.read(&mut magic)?;
+        let magic = &magic[..read];
+
+        if magic.starts_with(ZIP_MAGIC) {
+            let mut archive = open_archive(file_path)?;
+            match read_entry(&mut archive, "word/document.xml")? {
+                Some(document) => self.extract_text_from_docx(&mut archive, &document),
+                None => self.extract_text_from_odt(&mut archive, file),
+            }
+        } else if magic.starts_with(OLE_MAGIC) {
+            self.extract_text_from_doc(file)
+        } else {
+            Err(AppError::Validation(format!("Format Word non reconnu: {}", file.path.display())))
+        }
     }
 }
 
@@ -40,4 +141,289 @@ impl Default for WordReader {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+}
+
+/// Parties `<prefix>N.xml` dans l'ordre numérique : header2 avant header10
+fn numbered_parts(archive: &ZipArchive<fs::File>, prefix: &str) -> Vec<String> {
+    let part_number = |name: &str| -> Option<u32> {
+        name.strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(".xml"))
+            .filter(|index| index.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|index| index.parse().ok())
+    };
+    let mut names = entry_names(archive, |name| part_number(name).is_some());
+    names.sort_by_key(|name| part_number(name));
+    names
+}
+
+/// Champs utiles du File Information Block (Word 97-2003)
+struct Fib {
+    encrypted: bool,
+    which_table_stream: bool,
+    fc_clx: usize,
+    lcb_clx: usize,
+}
+
+impl Fib {
+    fn parse(word_document: &[u8]) -> AppResult<Self> {
+        if read_u16(word_document, 0x0000) != Some(0xA5EC) {
+            return Err(AppError::Validation("Signature FIB invalide".to_string()));
+        }
+
+        let flags = read_u16(word_document, 0x000A)
+            .ok_or_else(|| AppError::Validation("FIB tronqué".to_string()))?;
+
+        Ok(Self {
+            encrypted: flags & 0x0100 != 0,
+            which_table_stream: flags & 0x0200 != 0,
+            fc_clx: read_u32(word_document, 0x01A2).unwrap_or(0) as usize,
+            lcb_clx: read_u32(word_document, 0x01A6).unwrap_or(0) as usize,
+        })
+    }
+}
+
+/// Reconstitue le texte à partir de la table des pièces (CLX)
+fn extract_pieces(word_document: &[u8], table: &[u8], fib: &Fib) -> AppResult<String> {
+    let clx = table.get(fib.fc_clx..fib.fc_clx + fib.lcb_clx)
+        .filter(|clx| !clx.is_empty())
+        .ok_or_else(|| AppError::Validation("CLX hors limites".to_string()))?;
+
+    // Ignorer les blocs Prc qui précèdent le Pcdt
+    let mut pos = 0;
+    while clx.get(pos) == Some(&0x01) {
+        let size = read_u16(clx, pos + 1)
+            .ok_or_else(|| AppError::Validation("Prc tronqué".to_string()))? as usize;
+        pos += 3 + size;
+    }
+
+    if clx.get(pos) != Some(&0x02) {
+        return Err(AppError::Validation("Pcdt introuvable".to_string()));
+    }
+
+    let lcb = read_u32(clx, pos + 1)
+        .ok_or_else(|| AppError::Validation("Pcdt tronqué".to_string()))? as usize;
+    let plc = clx.get(pos + 5..pos + 5 + lcb)
+        .ok_or_else(|| AppError::Validation("PlcPcd hors limites".to_string()))?;
+
+    if lcb < 16 {
+        return Err(AppError::Validation("PlcPcd vide".to_string()));
+    }
+
+    let piece_count = (lcb - 4) / 12;
+    let mut text = String::new();
+
+    for i in 0..piece_count {
+        let (Some(cp_start), Some(cp_end)) = (read_u32(plc, i * 4), read_u32(plc, (i + 1) * 4)) else {
+            break;
+        };
+        let char_count = cp_end.saturating_sub(cp_start) as usize;
+
+        let descriptor = 4 * (piece_count + 1) + i * 8;
+        let Some(fc_value) = read_u32(plc, descriptor + 2) else {
+            break;
+        };
+
+        let compressed = fc_value & 0x4000_0000 != 0;
+        let fc = (fc_value & 0x3FFF_FFFF) as usize;
+
+        if compressed {
+            // Texte 8 bits en Windows-1252
+            let start = fc / 2;
+            if let Some(bytes) = word_document.get(start..start + char_count) {
+                let (decoded, _, _) = encoding_rs::WINDOWS_1252.decode(bytes);
+                text.push_str(&decoded);
+            }
+        } else if let Some(bytes) = word_document.get(fc..fc + char_count * 2) {
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+            text.push_str(&String::from_utf16_lossy(&units));
+        }
+    }
+
+    Ok(text)
+}
+
+/// Convertit les caractères de contrôle Word et retire les codes de champ
+fn strip_word_control_chars(raw: &str) -> String {
+    let mut output = String::with_capacity(raw.len());
+    // Pile des champs ouverts : `true` tant que l'on est dans le code du champ
+    let mut fields: Vec<bool> = Vec::new();
+
+    for ch in raw.chars() {
+        match ch {
+            '\u{13}' => fields.push(true),
+            '\u{14}' => {
+                if let Some(in_code) = fields.last_mut() {
+                    *in_code = false;
+                }
+            }
+            '\u{15}' => {
+                fields.pop();
+            }
+            _ if fields.last() == Some(&true) => {}
+            '\r' | '\u{0B}' | '\u{0C}' => output.push('\n'),
+            '\u{07}' => output.push('\t'),
+            c if c.is_control() && c != '\t' && c != '\n' => {}
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Repli : suites de caractères imprimables (ASCII ou UTF-16LE)
+fn printable_runs(bytes: &[u8]) -> String {
+    const MIN_RUN: usize = 4;
+
+    let mut runs = Vec::new();
+    let mut current = String::new();
+
+    let flush = |current: &mut String, runs: &mut Vec<String>| {
+        if current.chars().count() >= MIN_RUN {
+            runs.push(std::mem::take(current));
+        } else {
+            current.clear();
+        }
+    };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        let is_printable = byte == b' ' || byte.is_ascii_graphic();
+
+        if is_printable && bytes.get(i + 1) == Some(&0) {
+            current.push(byte as char);
+            i += 2;
+        } else if is_printable {
+            current.push(byte as char);
+            i += 1;
+        } else {
+            flush(&mut current, &mut runs);
+            i += 1;
+        }
+    }
+    flush(&mut current, &mut runs);
+
+    runs.join("\n")
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    bytes.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    bytes.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::readers::office::tests::{file_for, write_zip};
+    use std::io::Write;
+
+    const W_NS: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main""#;
+
+    #[test]
+    fn test_docx_extracts_body_tables_headers_and_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let document = format!(r#"<w:document {W_NS}><w:body>
+            <w:p><w:r><w:t>Rapport</w:t></w:r><w:r><w:tab/><w:t xml:space="preserve">annuel &amp; bilan</w:t></w:r></w:p>
+            <w:p><w:r><w:fldChar/><w:instrText>PAGEREF _Toc1</w:instrText><w:t>Chiffres</w:t></w:r></w:p>
+            <w:tbl><w:tr><w:tc><w:p><w:r><w:t>Ventes</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>42</w:t></w:r></w:p></w:tc></w:tr></w:tbl>
+        </w:body></w:document>"#);
+        let header = |text: &str| format!(r#"<w:hdr {W_NS}><w:p><w:r><w:t>{text}</w:t></w:r></w:p></w:hdr>"#);
+        let footer = format!(r#"<w:ftr {W_NS}><w:p><w:r><w:t>Page de pied</w:t></w:r></w:p></w:ftr>"#);
+        let footnotes = format!(r#"<w:footnotes {W_NS}><w:footnote><w:p><w:r><w:t>Source interne</w:t></w:r></w:p></w:footnote></w:footnotes>"#);
+
+        let path = write_zip(&dir, "rapport.docx", &[
+            ("[Content_Types].xml", "<Types/>"),
+            ("word/document.xml", &document),
+            ("word/header10.xml", &header("Annexe")),
+            ("word/header2.xml", &header("Confidentiel")),
+            ("word/footer1.xml", &footer),
+            ("word/footnotes.xml", &footnotes),
+        ]);
+
+        let text = WordReader::new().read(&file_for(path)).unwrap();
+
+        assert!(text.starts_with("Confidentiel Annexe Rapport annuel & bilan"), "{}", text);
+        assert!(text.contains("Chiffres"));
+        assert!(!text.contains("PAGEREF"));
+        assert!(text.contains("Ventes 42"));
+        assert!(text.contains("Source interne"));
+        assert!(text.ends_with("Page de pied"));
+    }
+
+    #[test]
+    fn test_odt_extracts_paragraphs_and_spaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = r#"<office:document-content
+            xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+            xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+            <office:automatic-styles><style>ignoré</style></office:automatic-styles>
+            <office:body><office:text>
+                <text:h>Compte rendu</text:h>
+                <text:p>Réunion<text:s/>du<text:tab/>lundi</text:p>
+                <text:p>Présents<text:line-break/>Alice</text:p>
+            </office:text></office:body>
+        </office:document-content>"#;
+
+        let path = write_zip(&dir, "notes.odt", &[
+            ("mimetype", "application/vnd.oasis.opendocument.text"),
+            ("content.xml", content),
+        ]);
+
+        let text = WordReader::new().read(&file_for(path)).unwrap();
+
+        assert_eq!(text, "Compte rendu Réunion du lundi Présents Alice");
+    }
+
+    #[test]
+    fn test_legacy_doc_reads_piece_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ancien.doc");
+
+        let body = b"Bonjour \x13 PAGE \x14" as &[u8];
+        let body = [body, b"3\x15 le monde\r", &[0xE9u8], b"t\xE9\r"].concat();
+        let text_offset = 0x400usize;
+
+        let mut word_document = vec![0u8; text_offset];
+        word_document[0..2].copy_from_slice(&0xA5ECu16.to_le_bytes());
+        word_document[0x0A..0x0C].copy_from_slice(&0x0200u16.to_le_bytes());
+        word_document.extend_from_slice(&body);
+
+        let mut clx = vec![0x02u8];
+        clx.extend_from_slice(&16u32.to_le_bytes());
+        clx.extend_from_slice(&0u32.to_le_bytes());
+        clx.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        clx.extend_from_slice(&[0, 0]);
+        clx.extend_from_slice(&(((text_offset * 2) as u32) | 0x4000_0000).to_le_bytes());
+        clx.extend_from_slice(&[0, 0]);
+
+        word_document[0x01A2..0x01A6].copy_from_slice(&0u32.to_le_bytes());
+        word_document[0x01A6..0x01AA].copy_from_slice(&(clx.len() as u32).to_le_bytes());
+
+        let mut compound = cfb::create(&path).unwrap();
+        compound.create_stream("/WordDocument").unwrap().write_all(&word_document).unwrap();
+        compound.create_stream("/1Table").unwrap().write_all(&clx).unwrap();
+        compound.flush().unwrap();
+        drop(compound);
+
+        let text = WordReader::new().read(&file_for(path)).unwrap();
+
+        assert_eq!(text, "Bonjour 3 le monde été");
+    }
+
+    #[test]
+    fn test_unknown_format_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faux.docx");
+        fs::write(&path, "pas un document").unwrap();
+
+        let result = WordReader::new().read(&file_for(path));
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,266 @@
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use crate::domain::entities::query_builder::{fuzzy_max_typos, FUZZY_SCORE_FUNCTION};
+
+// Paliers de score : une correspondance d'un palier l'emporte toujours sur celles du palier inférieur
+const EXACT_SCORE: u32 = 1000;
+const STEM_SCORE: u32 = 950;
+const PREFIX_SCORE: u32 = 850;
+const BOUNDARY_SCORE: u32 = 750;
+const SUBSTRING_SCORE: u32 = 650;
+const SUBSEQUENCE_SCORE: u32 = 300;
+const TYPO_SCORE: u32 = 250;
+const PATH_SCORE: u32 = 100;
+
+/// Motif de recherche approximative sur les noms de fichiers : chaque mot doit correspondre
+/// au nom (exact, préfixe, début de mot, sous-chaîne, sous-séquence ou faute de frappe) ou au chemin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyPattern {
+    words: Vec<Vec<char>>,
+}
+
+impl FuzzyPattern {
+    pub fn new(pattern: &str) -> Self {
+        let words = pattern.split_whitespace()
+            .map(|word| word.to_lowercase().chars().collect())
+            .collect();
+        Self { words }
+    }
+
+    /// Somme des scores de chaque mot, `None` si un mot ne correspond ni au nom ni au chemin
+    pub fn score(&self, name: &str, path: &str) -> Option<u32> {
+        if self.words.is_empty() {
+            return None;
+        }
+
+        let candidate = Candidate::new(name);
+        let path = path.to_lowercase();
+        self.words.iter()
+            .map(|word| word_score(word, &candidate, &path))
+            .sum()
+    }
+}
+
+/// Nom en minuscules et positions des débuts de mots (après un séparateur, une majuscule, un chiffre)
+struct Candidate {
+    chars: Vec<char>,
+    boundaries: Vec<bool>,
+    stem_len: usize,
+}
+
+impl Candidate {
+    fn new(name: &str) -> Self {
+        let original: Vec<char> = name.chars().collect();
+        let boundaries = original.iter().enumerate()
+            .map(|(i, &c)| match i.checked_sub(1).map(|p| original[p]) {
+                None => true,
+                Some(prev) => !prev.is_alphanumeric()
+                    || (prev.is_lowercase() && c.is_uppercase())
+                    || (prev.is_alphabetic() && c.is_numeric()),
+            })
+            .collect();
+        let chars: Vec<char> = original.iter().flat_map(|c| c.to_lowercase()).collect();
+        // Les minuscules d'un caractère peuvent être plusieurs : on renonce alors aux débuts de mots
+        let boundaries = if chars.len() == original.len() { boundaries } else { vec![false; chars.len()] };
+        let stem_len = chars.iter().rposition(|&c| c == '.').filter(|&dot| dot > 0).unwrap_or(chars.len());
+        Self { chars, boundaries, stem_len }
+    }
+
+    /// Mots du nom, pour la tolérance aux fautes de frappe
+    fn tokens(&self) -> Vec<&[char]> {
+        let mut tokens = Vec::new();
+        let mut start = None;
+        for (i, &c) in self.chars.iter().enumerate() {
+            if !c.is_alphanumeric() || (self.boundaries[i] && start.is_some()) {
+                if let Some(s) = start.take() {
+                    tokens.push(&self.chars[s..i]);
+                }
+            }
+            if c.is_alphanumeric() && start.is_none() {
+                start = Some(i);
+            }
+        }
+        if let Some(s) = start {
+            tokens.push(&self.chars[s..]);
+        }
+        tokens
+    }
+}
+
+fn word_score(word: &[char], candidate: &Candidate, path: &str) -> Option<u32> {
+    let name = &candidate.chars;
+    // Départage : à palier égal, les noms courts d'abord
+    let length_penalty = (name.len() as u32).min(100) / 10;
+
+    if name.as_slice() == word {
+        return Some(EXACT_SCORE);
+    }
+    if &name[..candidate.stem_len] == word {
+        return Some(STEM_SCORE);
+    }
+    if name.starts_with(word) {
+        return Some(PREFIX_SCORE - length_penalty);
+    }
+
+    let positions = find_all(name, word);
+    if positions.iter().any(|&i| candidate.boundaries[i]) {
+        return Some(BOUNDARY_SCORE - length_penalty);
+    }
+    if !positions.is_empty() {
+        return Some(SUBSTRING_SCORE - length_penalty);
+    }
+
+    if let Some(score) = subsequence_score(word, candidate) {
+        return Some(score.saturating_sub(length_penalty));
+    }
+    if let Some(distance) = typo_distance(word, candidate) {
+        return Some(TYPO_SCORE - 60 * distance - length_penalty);
+    }
+
+    let word: String = word.iter().collect();
+    path.contains(&word).then_some(PATH_SCORE)
+}
+
+fn find_all(haystack: &[char], needle: &[char]) -> Vec<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return Vec::new();
+    }
+    haystack.windows(needle.len())
+        .enumerate()
+        .filter(|(_, window)| *window == needle)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Style fzf : caractères du mot dans l'ordre, bonus pour les débuts de mots et les suites contiguës,
+/// pénalité pour les trous. Réservé aux mots d'au moins deux caractères.
+fn subsequence_score(word: &[char], candidate: &Candidate) -> Option<u32> {
+    if word.len() < 2 {
+        return None;
+    }
+
+    let mut score: i64 = 0;
+    let mut previous: Option<usize> = None;
+    let mut from = 0;
+    for &c in word {
+        let offset = candidate.chars[from..].iter().position(|&n| n == c)?;
+        let index = from + offset;
+        if candidate.boundaries[index] {
+            score += 20;
+        }
+        match previous {
+            Some(p) if index == p + 1 => score += 15,
+            Some(p) => score -= ((index - p - 1) as i64).min(10),
+            None => score -= (index as i64).min(10),
+        }
+        previous = Some(index);
+        from = index + 1;
+    }
+
+    Some((SUBSEQUENCE_SCORE as i64 + score).clamp(1, (SUBSTRING_SCORE - 1) as i64) as u32)
+}
+
+/// Plus petite distance d'édition entre le mot et un mot du nom (ou son début) : une faute
+/// tolérée dès trois caractères, deux à partir de huit
+fn typo_distance(word: &[char], candidate: &Candidate) -> Option<u32> {
+    let max_distance = match fuzzy_max_typos(word.len()) {
+        0 => return None,
+        max_distance => max_distance,
+    };
+
+    candidate.tokens().into_iter()
+        .flat_map(|token| {
+            // Le mot peut n'être que le début d'un mot plus long du nom
+            let shortest = word.len().saturating_sub(max_distance).max(1);
+            (shortest..=token.len().min(word.len() + max_distance)).map(move |len| &token[..len])
+        })
+        .map(|prefix| osa_distance(word, prefix))
+        .filter(|&distance| distance <= max_distance)
+        .min()
+        .map(|distance| distance as u32)
+}
+
+/// Distance de Damerau-Levenshtein restreinte : insertion, suppression, substitution, inversion
+fn osa_distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Enregistre `fuzzy_score` sur la connexion (NULL si un mot ne correspond pas) ; le motif n'est analysé qu'une fois par requête
+pub fn register(conn: &Connection) -> rusqlite::Result<()> {
+    conn.create_scalar_function(
+        FUZZY_SCORE_FUNCTION,
+        3,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let pattern = ctx.get_or_create_aux(0, |value: ValueRef<'_>| -> rusqlite::Result<FuzzyPattern> {
+                Ok(FuzzyPattern::new(value.as_str().unwrap_or_default()))
+            })?;
+            let name = ctx.get_raw(1).as_str().unwrap_or_default();
+            let path = ctx.get_raw(2).as_str().unwrap_or_default();
+            Ok(pattern.score(name, path))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(pattern: &str, name: &str) -> Option<u32> {
+        FuzzyPattern::new(pattern).score(name, &format!("/home/user/{}", name))
+    }
+
+    #[test]
+    fn test_match_kinds_are_ranked() {
+        let ranked = [
+            score("report", "report"),
+            score("report", "report.pdf"),
+            score("report", "reports_2024.pdf"),
+            score("report", "annual_report.pdf"),
+            score("report", "misreported.txt"),
+            score("report", "r_e_p_o_r_t.txt"),
+            score("reprot", "annual_report.pdf"),
+            score("user", "annual_report.pdf"),
+        ];
+        assert!(ranked.iter().all(Option::is_some), "{:?}", ranked);
+        assert!(ranked.windows(2).all(|pair| pair[0] > pair[1]), "{:?}", ranked);
+
+        // Début de mot en camelCase, plusieurs mots, correspondance dans un dossier parent
+        assert!(score("report", "AnnualReport.pdf") > score("report", "misreported.txt"));
+        assert!(score("annual reprot", "annual_report.pdf").unwrap() > score("reprot", "annual_report.pdf").unwrap());
+        assert_eq!(FuzzyPattern::new("user").score("notes.txt", "/home/user/notes.txt"), Some(PATH_SCORE));
+
+        assert_eq!(score("rpt", "photo.jpg"), None);
+        assert_eq!(score("report invoice", "annual_report.pdf"), None);
+        assert_eq!(score("zq", "annual_report.pdf"), None);
+    }
+
+    #[test]
+    fn test_osa_distance() {
+        let distance = |a: &str, b: &str| osa_distance(&a.chars().collect::<Vec<_>>(), &b.chars().collect::<Vec<_>>());
+        assert_eq!(distance("reprot", "report"), 1);
+        assert_eq!(distance("rport", "report"), 1);
+        assert_eq!(distance("raport", "report"), 1);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+    }
+}
@@ -0,0 +1,226 @@
+use rusqlite::Connection;
+use crate::shared::errors::{AppError, AppResult};
+
+/// Étape de migration du schéma, appliquée une seule fois selon `PRAGMA user_version`
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+    /// Reconstruit ou supprime des données existantes : une sauvegarde est faite avant
+    pub destructive: bool,
+}
+
+/// Migrations ordonnées par version croissante ; ne jamais modifier une étape publiée
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("../../../data/migrations/0001_initial_schema.sql"),
+        destructive: false,
+    },
+    Migration {
+        version: 2,
+        name: "fts_content_positions",
+        sql: include_str!("../../../data/migrations/0002_fts_content_positions.sql"),
+        destructive: true,
+    },
+    Migration {
+        version: 3,
+        name: "path_rules",
+        sql: include_str!("../../../data/migrations/0003_path_rules.sql"),
+        destructive: false,
+    },
+    Migration {
+        version: 4,
+        name: "fts_names",
+        sql: include_str!("../../../data/migrations/0004_fts_names.sql"),
+        destructive: false,
+    },
+    Migration {
+        version: 5,
+        name: "embeddings",
+        sql: include_str!("../../../data/migrations/0005_embeddings.sql"),
+        destructive: false,
+    },
+    Migration {
+        version: 6,
+        name: "file_opens",
+        sql: include_str!("../../../data/migrations/0006_file_opens.sql"),
+        destructive: false,
+    },
+    Migration {
+        version: 7,
+        name: "mail_entries",
+        sql: include_str!("../../../data/migrations/0007_mail_entries.sql"),
+        destructive: false,
+    },
+];
+
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub fn current_version(conn: &Connection) -> AppResult<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Amène la base à la dernière version connue, retourne le nombre d'étapes appliquées
+pub fn migrate(conn: &Connection) -> AppResult<usize> {
+    apply_migrations(conn, MIGRATIONS)
+}
+
+pub(crate) fn apply_migrations(conn: &Connection, migrations: &[Migration]) -> AppResult<usize> {
+    let current = current_version(conn)?;
+    let supported = migrations.last().map(|m| m.version).unwrap_or(0);
+
+    if current > supported {
+        return Err(AppError::SchemaTooNew { found: current, supported });
+    }
+
+    let pending: Vec<&Migration> = migrations.iter()
+        .filter(|m| m.version > current)
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    // Une base vide n'a rien à sauvegarder
+    if pending.iter().any(|m| m.destructive) && has_user_tables(conn)? {
+        backup_database(conn, current)?;
+    }
+
+    for migration in &pending {
+        apply_migration(conn, migration)?;
+    }
+
+    Ok(pending.len())
+}
+
+fn apply_migration(conn: &Connection, migration: &Migration) -> AppResult<()> {
+    tracing::info!("Migration {} ({}) en cours", migration.version, migration.name);
+
+    let tx = conn.unchecked_transaction()?;
+
+    // user_version est écrit dans l'en-tête de la base, donc dans la même transaction
+    let result = tx.execute_batch(migration.sql)
+        .and_then(|_| tx.pragma_update(None, "user_version", migration.version));
+
+    match result {
+        Ok(()) => {
+            tx.commit()?;
+            Ok(())
+        }
+        Err(e) => {
+            if let Err(rollback_err) = tx.rollback() {
+                tracing::error!("Failed to rollback migration {}: {}", migration.version, rollback_err);
+            }
+            Err(AppError::Internal(format!("Échec de la migration {} ({}): {}", migration.version, migration.name, e)))
+        }
+    }
+}
+
+fn has_user_tables(conn: &Connection) -> AppResult<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Copie cohérente de la base (`VACUUM INTO`) à côté du fichier d'origine
+fn backup_database(conn: &Connection, version: u32) -> AppResult<()> {
+    let Some(path) = conn.path().filter(|p| !p.is_empty()) else {
+        return Ok(());
+    };
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let backup_path = format!("{}.v{}-{}.bak", path, version, timestamp);
+
+    conn.execute("VACUUM INTO ?1", [&backup_path])?;
+    tracing::info!("Sauvegarde de la base avant migration: {}", backup_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn open_db(name: &str) -> (Connection, TempDir) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let conn = Connection::open(temp_dir.path().join(name)).unwrap();
+        (conn, temp_dir)
+    }
+
+    fn backups(temp_dir: &TempDir) -> Vec<String> {
+        std::fs::read_dir(temp_dir.path()).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".bak"))
+            .collect()
+    }
+
+    #[test]
+    fn test_fresh_database_reaches_latest_version() {
+        let (conn, temp_dir) = open_db("fresh.db");
+
+        assert_eq!(migrate(&conn).unwrap(), MIGRATIONS.len());
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert!(backups(&temp_dir).is_empty());
+
+        // Relancer ne fait rien
+        assert_eq!(migrate(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_legacy_database_is_backed_up_and_keeps_data() {
+        let (conn, temp_dir) = open_db("legacy.db");
+        conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+        conn.execute_batch(
+            "INSERT INTO files (path, name, is_dir, last_modified, created_at, accessed_at)
+                VALUES ('/docs/a.txt', 'a.txt', 0, 0, 0, 0);
+            INSERT INTO fts_content (content, file_id) VALUES ('budget prévisionnel', 1);"
+        ).unwrap();
+
+        migrate(&conn).unwrap();
+
+        assert_eq!(backups(&temp_dir).len(), 1);
+        let files: i64 = conn.query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0)).unwrap();
+        assert_eq!(files, 1);
+        let content: String = conn
+            .query_row("SELECT content FROM fts_content WHERE fts_content MATCH 'budget'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(content, "budget prévisionnel");
+    }
+
+    #[test]
+    fn test_failed_step_is_rolled_back() {
+        let (conn, _temp_dir) = open_db("broken.db");
+        let migrations = [
+            Migration { version: 1, name: "ok", sql: "CREATE TABLE a (id INTEGER);", destructive: false },
+            Migration { version: 2, name: "broken", sql: "CREATE TABLE b (id INTEGER); INSERT INTO missing VALUES (1);", destructive: false },
+        ];
+
+        assert!(apply_migrations(&conn, &migrations).is_err());
+
+        assert_eq!(current_version(&conn).unwrap(), 1);
+        let b_exists: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE name = 'b'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(b_exists, 0);
+    }
+
+    #[test]
+    fn test_newer_database_is_rejected() {
+        let (conn, _temp_dir) = open_db("future.db");
+        conn.pragma_update(None, "user_version", latest_version() + 1).unwrap();
+
+        let result = migrate(&conn);
+
+        assert!(matches!(result, Err(AppError::SchemaTooNew { found, supported }) if found == latest_version() + 1 && supported == latest_version()));
+    }
+}
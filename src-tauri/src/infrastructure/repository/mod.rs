@@ -1 +1,4 @@
-pub mod sqlite;
\ No newline at end of file
+pub mod sqlite;
+pub mod migrations;
+pub mod pool;
+pub mod fuzzy;
@@ -0,0 +1,274 @@
+use std::ops::Deref;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Condvar, Mutex};
+use std::thread;
+use crate::domain::ports::repository::FileRepository;
+use crate::domain::services::file_service::FileService;
+use crate::infrastructure::repository::sqlite::Db;
+use crate::shared::errors::{AppError, AppResult};
+
+type WriteJob = Box<dyn FnOnce(&mut FileService<Db>) + Send>;
+
+/// Accès concurrent à la base : les lectures passent par un pool de connexions en lecture seule
+/// (WAL, donc jamais bloquées par une écriture en cours), les écritures sont sérialisées
+/// sur un thread dédié qui possède l'unique connexion en écriture.
+pub struct RepositoryPool {
+    readers: Mutex<Vec<FileService<Db>>>,
+    reader_available: Condvar,
+    writer: mpsc::Sender<WriteJob>,
+}
+
+impl RepositoryPool {
+    /// Initialise la base (migrations) puis ouvre `reader_count` connexions en lecture
+    pub fn open(path: &str, reader_count: usize) -> AppResult<Self> {
+        let writer = FileService::new(Db::new(path)?);
+        writer.init()?;
+
+        let readers = (0..reader_count.max(1))
+            .map(|_| Db::open_read_only(path).map(FileService::new))
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(Self {
+            readers: Mutex::new(readers),
+            reader_available: Condvar::new(),
+            writer: spawn_writer(writer)?,
+        })
+    }
+
+    /// Exécute `f` sur une connexion en lecture, en attendant qu'une se libère si besoin
+    pub fn read<T, F>(&self, f: F) -> AppResult<T>
+    where
+        F: FnOnce(&FileService<Db>) -> AppResult<T>,
+    {
+        let reader = self.checkout()?;
+        f(&reader)
+    }
+
+    /// Met `f` en file d'attente du thread d'écriture et attend son résultat
+    pub fn write<T, F>(&self, f: F) -> AppResult<T>
+    where
+        F: FnOnce(&mut FileService<Db>) -> AppResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (result_tx, result_rx) = mpsc::sync_channel(1);
+
+        let job: WriteJob = Box::new(move |service| {
+            let _ = result_tx.send(f(service));
+        });
+
+        self.writer.send(job)
+            .map_err(|_| AppError::Internal("Le thread d'écriture de la base est arrêté".to_string()))?;
+
+        result_rx.recv()
+            .map_err(|_| AppError::Internal("L'écriture a été interrompue avant d'aboutir".to_string()))?
+    }
+
+    fn checkout(&self) -> AppResult<ReaderGuard<'_>> {
+        let mut readers = self.readers.lock()
+            .map_err(|e| AppError::Internal(format!("Pool de lecture inaccessible: {}", e)))?;
+
+        loop {
+            if let Some(service) = readers.pop() {
+                return Ok(ReaderGuard { pool: self, service: Some(service) });
+            }
+            readers = self.reader_available.wait(readers)
+                .map_err(|e| AppError::Internal(format!("Pool de lecture inaccessible: {}", e)))?;
+        }
+    }
+}
+
+fn spawn_writer(mut service: FileService<Db>) -> AppResult<mpsc::Sender<WriteJob>> {
+    let (job_tx, job_rx) = mpsc::channel::<WriteJob>();
+
+    thread::Builder::new()
+        .name("db-writer".to_string())
+        .spawn(move || {
+            for job in job_rx {
+                // Une écriture qui panique ne doit pas arrêter les suivantes
+                if panic::catch_unwind(AssertUnwindSafe(|| job(&mut service))).is_err() {
+                    tracing::error!("Une écriture en base a paniqué, la suivante est traitée");
+                }
+            }
+            tracing::debug!("Thread d'écriture de la base arrêté");
+        })?;
+
+    Ok(job_tx)
+}
+
+/// Rend la connexion au pool à la fin de la lecture, même en cas d'erreur
+struct ReaderGuard<'a> {
+    pool: &'a RepositoryPool,
+    service: Option<FileService<Db>>,
+}
+
+impl Deref for ReaderGuard<'_> {
+    type Target = FileService<Db>;
+
+    fn deref(&self) -> &Self::Target {
+        self.service.as_ref().expect("connexion rendue au pool")
+    }
+}
+
+impl Drop for ReaderGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(service) = self.service.take() {
+            if let Ok(mut readers) = self.pool.readers.lock() {
+                readers.push(service);
+                self.pool.reader_available.notify_one();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+    use crate::domain::entities::file::File;
+    use crate::domain::entities::search::SearchQuery;
+    use crate::infrastructure::readers::office::tests::file_for;
+    use tempfile::TempDir;
+
+    fn open_pool(reader_count: usize) -> (Arc<RepositoryPool>, TempDir) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("pool.db");
+        let pool = RepositoryPool::open(path.to_str().unwrap(), reader_count).unwrap();
+        (Arc::new(pool), temp_dir)
+    }
+
+    fn test_file(index: usize) -> File {
+        file_for(PathBuf::from(format!("/bench/dossier-{}/fichier-{}.txt", index % 100, index)))
+    }
+
+    fn name_query(text: &str) -> SearchQuery {
+        SearchQuery { text: text.to_string(), limit: 50, ..Default::default() }
+    }
+
+    #[test]
+    fn test_writes_are_visible_to_readers() {
+        let (pool, _temp_dir) = open_pool(2);
+
+        pool.write(|repo| repo.insert((0..10).map(test_file).collect())).unwrap();
+
+        let results = pool.read(|repo| repo.search(&name_query("fichier-"))).unwrap();
+        assert_eq!(results.len(), 10);
+    }
+
+    #[test]
+    fn test_reads_are_not_blocked_by_a_long_write() {
+        let (pool, _temp_dir) = open_pool(2);
+        pool.write(|repo| repo.insert((0..100).map(test_file).collect())).unwrap();
+
+        // Écriture qui garde la transaction ouverte longtemps (extraction lente simulée)
+        let writer_pool = pool.clone();
+        let writer = thread::spawn(move || {
+            writer_pool.write(|repo| {
+                repo.upsert((100..200).map(test_file).collect())?;
+                thread::sleep(Duration::from_millis(500));
+                Ok(())
+            })
+        });
+        thread::sleep(Duration::from_millis(50));
+
+        let start = Instant::now();
+        let results = pool.read(|repo| repo.search(&name_query("fichier-1"))).unwrap();
+        assert!(start.elapsed() < Duration::from_millis(250), "lecture bloquée {:?}", start.elapsed());
+        assert!(!results.is_empty());
+
+        writer.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_failed_or_panicking_write_keeps_writer_alive() {
+        let (pool, _temp_dir) = open_pool(1);
+
+        let result: AppResult<()> = pool.write(|_| panic!("écriture invalide"));
+        assert!(result.is_err());
+
+        pool.write(|repo| repo.insert(vec![test_file(1)])).unwrap();
+        assert_eq!(pool.read(|repo| repo.search(&name_query("fichier-1"))).unwrap().len(), 1);
+    }
+
+    /// Latence de recherche pendant une indexation continue, comparée à une base au repos.
+    /// `cargo test --release bench_search_latency_under_indexing -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_search_latency_under_indexing() {
+        let (pool, _temp_dir) = open_pool(4);
+        for chunk in (0..50_000).collect::<Vec<_>>().chunks(500) {
+            let files: Vec<File> = chunk.iter().copied().map(test_file).collect();
+            pool.write(move |repo| repo.insert(files)).unwrap();
+        }
+
+        let measure = |pool: &RepositoryPool| {
+            let mut latencies: Vec<Duration> = (0..200)
+                .map(|i| {
+                    let start = Instant::now();
+                    pool.read(|repo| repo.search(&name_query(&format!("fichier-{}", i * 37)))).unwrap();
+                    start.elapsed()
+                })
+                .collect();
+            latencies.sort();
+            (latencies[latencies.len() / 2], latencies[latencies.len() * 99 / 100])
+        };
+
+        let (idle_p50, idle_p99) = measure(&pool);
+
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let indexer = {
+            let pool = pool.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut next = 50_000;
+                while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                    let files: Vec<File> = (next..next + 500).map(test_file).collect();
+                    pool.write(move |repo| repo.upsert(files)).unwrap();
+                    next += 500;
+                }
+            })
+        };
+
+        let (load_p50, load_p99) = measure(&pool);
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        indexer.join().unwrap();
+
+        println!("Recherche au repos: p50 {:?}, p99 {:?}", idle_p50, idle_p99);
+        println!("Recherche pendant l'indexation: p50 {:?}, p99 {:?}", load_p50, load_p99);
+    }
+
+    /// Recherche par sous-chaîne du nom via l'index trigramme, comparée au parcours complet `LIKE '%motif%'`.
+    /// `cargo test --release bench_name_search_trigram_vs_like -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_name_search_trigram_vs_like() {
+        let (pool, temp_dir) = open_pool(1);
+        for chunk in (0..200_000).collect::<Vec<_>>().chunks(5_000) {
+            let files: Vec<File> = chunk.iter().copied().map(test_file).collect();
+            pool.write(move |repo| repo.insert(files)).unwrap();
+        }
+
+        let patterns: Vec<String> = (0..100).map(|i| format!("chier-{}", i * 1_997 + 11)).collect();
+        let measure = |search: &dyn Fn(&str) -> usize| {
+            let start = Instant::now();
+            let found: usize = patterns.iter().map(|pattern| search(pattern)).sum();
+            (start.elapsed() / patterns.len() as u32, found)
+        };
+
+        let (trigram, trigram_found) = measure(&|pattern| {
+            pool.read(|repo| repo.search(&name_query(pattern))).unwrap().len()
+        });
+        // Requête d'avant l'index trigramme, sur une connexion à part
+        let db = Db::open_read_only(temp_dir.path().join("pool.db").to_str().unwrap()).unwrap();
+        let (scan, scan_found) = measure(&|pattern| {
+            let mut stmt = db.conn.prepare_cached("SELECT id FROM files WHERE name LIKE ? ORDER BY name COLLATE NOCASE LIMIT 50").unwrap();
+            stmt.query_map([format!("%{}%", pattern)], |row| row.get::<_, i64>(0)).unwrap().count()
+        });
+
+        println!("Recherche trigramme: {:?} par requête ({} résultats)", trigram, trigram_found);
+        println!("Parcours LIKE: {:?} par requête ({} résultats)", scan, scan_found);
+        assert_eq!(trigram_found, scan_found);
+        assert!(trigram < scan);
+    }
+}
@@ -1,15 +1,26 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use rusqlite::{Connection, Result as SqliteResult};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Result as SqliteResult};
+use crate::domain::entities::content::{render_slide_marks, slide_at, ExtractedContent, ExtractedEntry, ENTRY_SEPARATOR, SLIDE_MARK};
 use crate::domain::entities::file::File;
 use crate::domain::entities::stat::Stat;
-use crate::domain::entities::search::{SearchQuery, DateMode, SortBy, SortOrder};
+use crate::domain::entities::search::{SearchHit, SearchQuery, DateMode, SortBy, SortOrder};
+use crate::domain::entities::scan::FileSignature;
+use crate::domain::entities::path_rule::{IndexingRules, PathRule, RuleKind};
+use crate::domain::entities::duplicate::{ChecksumCandidate, DuplicateQuery};
+use crate::domain::entities::embedding::{dot, ChunkEmbedding, PendingEmbedding, SemanticHit};
 use crate::domain::ports::repository::FileRepository;
 use std::time::{SystemTime, UNIX_EPOCH, Duration};
-use crate::domain::entities::query_builder::QueryBuilder;
+use crate::infrastructure::repository::{fuzzy, migrations};
+use crate::domain::entities::query_parser::{parse_query, TextValue};
+use crate::domain::entities::ranking::{RankingWeights, RANKING_WEIGHTS};
+use crate::domain::entities::query_builder::{NameMatch, QueryBuilder, HIGHLIGHT_END, HIGHLIGHT_START, SNIPPET_ELLIPSIS};
 use crate::shared::errors::{AppError, AppResult};
 
+// Nombre maximal d'extraits par résultat et contexte (en octets) autour d'une correspondance
+const MAX_SNIPPETS: usize = 3;
+const SNIPPET_CONTEXT: usize = 60;
 
 pub struct Db {
     pub conn: Connection,
@@ -19,6 +30,8 @@ pub struct Db {
 impl FileRepository for Db {
     fn new(path: &str) -> AppResult<Db> {
         let conn = Connection::open(path)?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        fuzzy::register(&conn)?;
 
         Ok(Self {
             conn,
@@ -27,8 +40,21 @@ impl FileRepository for Db {
     }
 
     fn init(&self) -> AppResult<()> {
-        let init_sql = include_str!("../../../data/init.sql");
-        self.conn.execute_batch(init_sql)?;
+        // Réglages propres à la connexion, à réappliquer à chaque ouverture
+        self.conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+            PRAGMA synchronous = NORMAL;
+            PRAGMA cache_size = 10000;
+            PRAGMA temp_store = MEMORY;
+            PRAGMA mmap_size = 268435456;"
+        )?;
+
+        let applied = migrations::migrate(&self.conn)?;
+        if applied > 0 {
+            tracing::info!("{} migration(s) appliquée(s), schéma en version {}", applied, migrations::latest_version());
+        }
+
+        self.conn.execute_batch("PRAGMA optimize;")?;
         tracing::info!("Database initialized successfully");
         Ok(())
     }
@@ -45,6 +71,74 @@ impl FileRepository for Db {
         Ok(())
     }
 
+    fn upsert(&mut self, files: Vec<File>) -> AppResult<()> {
+        let new_types = files.iter()
+            .filter_map(|file| file.file_type.clone())
+            .collect::<Vec<_>>();
+
+        self.insert_type(new_types)?;
+        self.upsert_file(files)?;
+
+        Ok(())
+    }
+
+    fn delete_files(&mut self, paths: &[String]) -> AppResult<usize> {
+        let tx = self.conn.transaction()?;
+
+        let result = (|| -> AppResult<usize> {
+            let mut deleted = 0;
+            for path in paths {
+                // Supprime le chemin lui-même et, s'il s'agit d'un dossier, tout son contenu
+                let (children_start, children_end) = descendant_bounds(path);
+
+                // Éléments virtuels (fichiers d'archive, messages) des conteneurs supprimés
+                tx.execute(
+                    "DELETE FROM fts_content WHERE file_id IN (
+                        SELECT id FROM files WHERE parent_id IN (
+                            SELECT id FROM files WHERE path = ?1 OR (path >= ?2 AND path < ?3)
+                        )
+                    )",
+                    rusqlite::params![path, children_start, children_end]
+                )?;
+                deleted += tx.execute(
+                    "DELETE FROM files WHERE parent_id IN (
+                        SELECT id FROM files WHERE path = ?1 OR (path >= ?2 AND path < ?3)
+                    )",
+                    rusqlite::params![path, children_start, children_end]
+                )?;
+
+                tx.execute(
+                    "DELETE FROM fts_content WHERE file_id IN (
+                        SELECT id FROM files WHERE path = ?1 OR (path >= ?2 AND path < ?3)
+                    )",
+                    rusqlite::params![path, children_start, children_end]
+                )?;
+
+                deleted += tx.execute(
+                    "DELETE FROM files WHERE path = ?1 OR (path >= ?2 AND path < ?3)",
+                    rusqlite::params![path, children_start, children_end]
+                )?;
+            }
+            Ok(deleted)
+        })();
+
+        match result {
+            Ok(deleted) => {
+                tx.commit().map_err(|e| {
+                    tracing::error!("Failed to commit delete_files transaction: {}", e);
+                    AppError::Database(e)
+                })?;
+                Ok(deleted)
+            }
+            Err(e) => {
+                if let Err(rollback_err) = tx.rollback() {
+                    tracing::error!("Failed to rollback delete_files transaction: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
     fn insert_paths(&mut self, new_paths: Vec<String>) -> AppResult<Vec<String>> {
 
         let db_paths = self.get_all_paths()?;
@@ -70,6 +164,10 @@ impl FileRepository for Db {
             for path in &new_paths {
                 tx.execute("INSERT INTO paths (path) VALUES (?)", [path])?;
             }
+            // Les règles propres à une racine retirée disparaissent avec elle
+            for path in &need_delete_paths {
+                tx.execute("DELETE FROM path_rules WHERE root = ?", [path])?;
+            }
             Ok(())
         })();
 
@@ -93,12 +191,14 @@ impl FileRepository for Db {
     }
 
     fn get_stat(&self) -> AppResult<Stat> {
+        // Nombre et taille des fichiers portent sur le disque : les entrées virtuelles (messages,
+        // membres d'archives) sont comptées dans leur conteneur
         let mut stmt = self.conn.prepare("
             WITH stats AS (
                 SELECT
-                    COUNT(CASE WHEN is_dir = 0 THEN 1 END) as nb_files,
+                    COUNT(CASE WHEN is_dir = 0 AND parent_id IS NULL THEN 1 END) as nb_files,
                     COUNT(CASE WHEN is_dir = 1 THEN 1 END) as nb_folders,
-                    COALESCE(SUM(CASE WHEN is_dir = 0 THEN size ELSE 0 END), 0) as total_size,
+                    COALESCE(SUM(CASE WHEN is_dir = 0 AND parent_id IS NULL THEN size ELSE 0 END), 0) as total_size,
                     COUNT(CASE WHEN is_indexed = 1 AND is_indexable = 1 THEN 1 END) as indexed_files,
                     COUNT(CASE WHEN is_indexed = 0 AND is_indexable = 1 THEN 1 END) as unindexed_files,
                     COUNT(CASE WHEN content_indexed = 1 AND is_indexable = 1 THEN 1 END) as content_indexed_files,
@@ -183,19 +283,453 @@ impl FileRepository for Db {
     }
 
     fn search(&self, query: &SearchQuery) -> AppResult<Vec<File>> {
-        let mut builder = QueryBuilder::new();
+        self.search_with_fallback(query, |builder, order_by, sort_order| {
+            let (sql, params) = builder.build(order_by, sort_order, query.limit, query.offset, query.cursor);
+            self.execute_search_query(&sql, &params)
+        })
+    }
 
-        if !query.text.trim().is_empty() {
-            if query.search_in_content {
-                let fts_query = query.text.replace("\"", "\"\"");
-                builder.add_fts_condition(format!("\"{}\"", fts_query));
-            } else {
-                builder.add_condition(
-                    "(LOWER(name) LIKE LOWER(?))".to_string(),
-                    Box::new(format!("%{}%", query.text))
-                );
+    fn search_hits(&self, query: &SearchQuery) -> AppResult<Vec<SearchHit>> {
+        self.search_with_fallback(query, |builder, order_by, sort_order| {
+            let (sql, params) = builder.build_hits(order_by, sort_order, query.limit, query.offset, query.cursor);
+
+            let mut stmt = self.conn.prepare(&sql)?;
+            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+            let hits = stmt.query_map(rusqlite::params_from_iter(param_refs), |row| {
+                let file = Self::map_row_to_file(row)?;
+                let score: Option<f64> = row.get("score")?;
+                let snippet: Option<String> = row.get("snippet")?;
+                let highlighted: Option<String> = row.get("highlighted")?;
+                let slides: Option<String> = row.get("slides")?;
+
+                // Plusieurs extraits si possible, sinon celui choisi par FTS5
+                let mut snippets = highlighted
+                    .map(|text| highlight_fragments(&text, MAX_SNIPPETS))
+                    .unwrap_or_default();
+                if snippets.is_empty() {
+                    snippets.extend(snippet);
+                }
+                let snippets = snippets.iter()
+                    .map(|fragment| {
+                        let rendered = render_marks(&render_slide_marks(fragment));
+                        match slides.as_deref().and_then(|content| fragment_slide(content, fragment)) {
+                            Some(slide) => format!("[Diapositive {}] {}", slide, rendered),
+                            None => rendered,
+                        }
+                    })
+                    .collect();
+
+                Ok(SearchHit { file, score, snippets })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+
+            Ok(hits)
+        })
+    }
+
+    fn reset_data(&self) -> AppResult<()> {
+        self.conn.execute("DELETE FROM files", [])?;
+        self.conn.execute("DELETE FROM types", [])?;
+        self.conn.execute("DELETE FROM paths", [])?;
+        Ok(())
+    }
+
+    fn update_file_index_status(&mut self, file: &File, content: ExtractedContent, is_indexable: bool) -> AppResult<()> {
+        let path_str = file.path.to_str()
+            .ok_or_else(|| AppError::Validation("Invalid file path encoding".to_string()))?;
+
+        let tx = match self.conn.transaction() {
+            Ok(tx) => tx,
+            Err(e) => {
+                tracing::error!("Failed to start transaction: {}", e);
+                return Err(e.into());
+            }
+        };
+
+        // Utiliser un pattern pour garantir le rollback explicite en cas d'erreur
+        let result = (|| -> AppResult<i64> {
+            let file_id: i64 = match tx.query_row(
+                "SELECT id FROM files WHERE path = ?",
+                [path_str],
+                |row| row.get(0)
+            ) {
+                Ok(id) => id,
+                Err(rusqlite::Error::QueryReturnedNoRows) => {
+                    return Err(AppError::NotFound(format!("File not found in database: {}", path_str)));
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            // FTS5 n'a pas de contrainte d'unicité sur file_id : supprimer l'ancien contenu d'abord
+            tx.execute("DELETE FROM fts_content WHERE file_id = ?", [file_id])?;
+            // Les embeddings de l'ancien contenu seront recalculés
+            tx.execute("DELETE FROM embeddings WHERE file_id = ?", [file_id])?;
+            tx.execute(
+                "INSERT INTO fts_content (content, file_id) VALUES (?, ?)",
+                rusqlite::params![content.text, file_id]
+            )?;
+
+            let mail = content.mail.clone().unwrap_or_default();
+            tx.execute(
+                "UPDATE files SET content_indexed = ?, is_indexable = ?, encoding = ?, line_count = ?, word_count = ?,
+                    mail_subject = ?, mail_from = ?, mail_to = ?, mail_date = ? WHERE path = ?",
+                rusqlite::params![
+                    true, is_indexable, content.encoding, content.line_count, content.word_count,
+                    mail.subject, mail.from, mail.to, mail.date, path_str
+                ]
+            )?;
+
+            // Les éléments contenus sont remplacés à chaque extraction
+            tx.execute("DELETE FROM fts_content WHERE file_id IN (SELECT id FROM files WHERE parent_id = ?)", [file_id])?;
+            tx.execute("DELETE FROM embeddings WHERE file_id IN (SELECT id FROM files WHERE parent_id = ?)", [file_id])?;
+            tx.execute("DELETE FROM files WHERE parent_id = ?", [file_id])?;
+            for entry in &content.entries {
+                insert_entry(&tx, file, file_id, entry)?;
+            }
+
+            Ok(file_id)
+        })();
+
+        match result {
+            Ok(_) => {
+                tx.commit().map_err(|e| {
+                    tracing::error!("Failed to commit transaction: {}", e);
+                    AppError::Database(e)
+                })?;
+                Ok(())
+            }
+            Err(e) => {
+                if let Err(rollback_err) = tx.rollback() {
+                    tracing::error!("Failed to rollback transaction: {}", rollback_err);
+                } else {
+                    tracing::debug!("Transaction rolled back successfully due to error: {}", e);
+                }
+                Err(e)
             }
         }
+    }
+
+    fn get_uncontent_indexed_files(&self) -> AppResult<Vec<File>> {
+        let mut stmt = self.conn.prepare("SELECT * FROM files WHERE content_indexed = 0 AND is_indexable = 1 ORDER BY id")?;
+        let files: Vec<File> = stmt
+            .query_map([], |row| { Self::map_row_to_file(row) })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(files)
+    }
+
+    fn get_file_signatures(&self, root: &str) -> AppResult<Vec<FileSignature>> {
+        let (children_start, children_end) = descendant_bounds(root);
+        let mut stmt = self.conn.prepare(
+            "SELECT path, size, last_modified FROM files
+             WHERE parent_id IS NULL AND (path = ?1 OR (path >= ?2 AND path < ?3))"
+        )?;
+        let signatures: Vec<FileSignature> = stmt
+            .query_map(rusqlite::params![root, children_start, children_end], |row| {
+                let size: Option<i64> = row.get(1)?;
+                Ok(FileSignature {
+                    path: row.get(0)?,
+                    size: size.map(|s| s as u64),
+                    last_modified: row.get::<_, Option<i64>>(2)?.unwrap_or_default(),
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(signatures)
+    }
+
+    fn get_indexing_rules(&self) -> AppResult<IndexingRules> {
+        let mut stmt = self.conn.prepare("SELECT pattern, kind, root FROM path_rules ORDER BY id")?;
+        let rules = stmt.query_map([], |row| {
+            let kind: String = row.get(1)?;
+            Ok(PathRule {
+                pattern: row.get(0)?,
+                kind: RuleKind::parse(&kind).unwrap_or(RuleKind::Exclude),
+                root: row.get(2)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+        let respect_ignore_files = self.get_setting("respect_ignore_files")?
+            .map(|value| value == "true")
+            .unwrap_or(false);
+
+        Ok(IndexingRules { rules, respect_ignore_files })
+    }
+
+    fn save_indexing_rules(&mut self, rules: &IndexingRules) -> AppResult<()> {
+        let tx = self.conn.transaction()?;
+
+        let result = (|| -> AppResult<()> {
+            tx.execute("DELETE FROM path_rules", [])?;
+            for rule in &rules.rules {
+                tx.execute(
+                    "INSERT INTO path_rules (pattern, kind, root) VALUES (?1, ?2, ?3)",
+                    rusqlite::params![rule.pattern.trim(), rule.kind.as_str(), rule.root]
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO settings (key, value) VALUES ('respect_ignore_files', ?1)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                [rules.respect_ignore_files.to_string()]
+            )?;
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => {
+                tx.commit().map_err(|e| {
+                    tracing::error!("Failed to commit save_indexing_rules transaction: {}", e);
+                    AppError::Database(e)
+                })?;
+                Ok(())
+            }
+            Err(e) => {
+                if let Err(rollback_err) = tx.rollback() {
+                    tracing::error!("Failed to rollback save_indexing_rules transaction: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn get_duplicate_candidates(&self, query: &DuplicateQuery) -> AppResult<Vec<ChecksumCandidate>> {
+        let (children_start, children_end) = match query.root.as_deref() {
+            Some(root) => {
+                let (start, end) = descendant_bounds(root);
+                (Some(start), Some(end))
+            }
+            None => (None, None),
+        };
+
+        // Seuls les fichiers dont la taille apparaît plusieurs fois peuvent être des doublons
+        let mut stmt = self.conn.prepare(
+            "WITH scoped AS (
+                SELECT path, size, checksum FROM files
+                WHERE is_dir = 0 AND is_symlink = 0 AND parent_id IS NULL AND size > 0 AND size >= ?1
+                  AND (?2 IS NULL OR (path >= ?2 AND path < ?3))
+            )
+            SELECT path, size, checksum FROM scoped
+            WHERE size IN (SELECT size FROM scoped GROUP BY size HAVING COUNT(*) > 1)
+            ORDER BY size DESC, path"
+        )?;
+        let candidates = stmt
+            .query_map(rusqlite::params![query.min_size as i64, children_start, children_end], |row| {
+                Ok(ChecksumCandidate {
+                    path: row.get(0)?,
+                    size: row.get::<_, i64>(1)? as u64,
+                    checksum: row.get(2)?,
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(candidates)
+    }
+
+    fn update_checksums(&mut self, checksums: &[(String, String)]) -> AppResult<usize> {
+        let tx = self.conn.transaction()?;
+
+        let result = (|| -> AppResult<usize> {
+            let mut stmt = tx.prepare("UPDATE files SET checksum = ?2 WHERE path = ?1")?;
+            let mut updated = 0;
+            for (path, checksum) in checksums {
+                updated += stmt.execute([path, checksum])?;
+            }
+            Ok(updated)
+        })();
+
+        match result {
+            Ok(updated) => {
+                tx.commit().map_err(|e| {
+                    tracing::error!("Failed to commit update_checksums transaction: {}", e);
+                    AppError::Database(e)
+                })?;
+                Ok(updated)
+            }
+            Err(e) => {
+                if let Err(rollback_err) = tx.rollback() {
+                    tracing::error!("Failed to rollback update_checksums transaction: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn get_pending_embeddings(&self, model: &str, after_id: i64, limit: u32) -> AppResult<Vec<PendingEmbedding>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT files.id, fts_content.content FROM files
+             JOIN fts_content ON fts_content.file_id = files.id
+             WHERE files.content_indexed = 1 AND files.is_indexable = 1 AND files.id > ?1
+               AND length(trim(fts_content.content, ' ' || char(9, 10, 13))) > 0
+               AND NOT EXISTS (SELECT 1 FROM embeddings WHERE embeddings.file_id = files.id AND embeddings.model = ?2)
+             ORDER BY files.id
+             LIMIT ?3"
+        )?;
+        let pending = stmt.query_map(rusqlite::params![after_id, model, limit], |row| {
+            Ok(PendingEmbedding { file_id: row.get(0)?, content: row.get(1)? })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(pending)
+    }
+
+    fn save_embeddings(&mut self, file_id: i64, model: &str, embeddings: &[ChunkEmbedding]) -> AppResult<()> {
+        let tx = self.conn.transaction()?;
+
+        let result = (|| -> AppResult<()> {
+            tx.execute("DELETE FROM embeddings WHERE file_id = ?", [file_id])?;
+            let mut stmt = tx.prepare(
+                "INSERT INTO embeddings (file_id, chunk_index, content, model, dimensions, vector) VALUES (?, ?, ?, ?, ?, ?)"
+            )?;
+            for embedding in embeddings {
+                stmt.execute(rusqlite::params![
+                    file_id,
+                    embedding.chunk_index,
+                    embedding.content,
+                    model,
+                    embedding.vector.len() as i64,
+                    vector_to_blob(&embedding.vector),
+                ])?;
+            }
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => {
+                tx.commit()?;
+                Ok(())
+            }
+            Err(e) => {
+                if let Err(rollback_err) = tx.rollback() {
+                    tracing::error!("Failed to rollback transaction: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn semantic_search(&self, model: &str, vector: &[f32], limit: u32) -> AppResult<Vec<SemanticHit>> {
+        // Parcours exhaustif : meilleur passage de chaque fichier
+        let mut best: HashMap<i64, (f32, String)> = HashMap::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT file_id, content, vector FROM embeddings WHERE model = ? AND dimensions = ?"
+        )?;
+        let mut rows = stmt.query(rusqlite::params![model, vector.len() as i64])?;
+        while let Some(row) = rows.next()? {
+            let file_id: i64 = row.get(0)?;
+            let blob: Vec<u8> = row.get(2)?;
+            let score = dot(vector, &blob_to_vector(&blob));
+            if best.get(&file_id).is_none_or(|(current, _)| score > *current) {
+                best.insert(file_id, (score, row.get(1)?));
+            }
+        }
+
+        let mut ranked: Vec<(i64, (f32, String))> = best.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.0.total_cmp(&a.1.0).then(a.0.cmp(&b.0)));
+        ranked.truncate(limit as usize);
+
+        let mut file_stmt = self.conn.prepare("SELECT * FROM files WHERE id = ?")?;
+        let mut hits = Vec::with_capacity(ranked.len());
+        for (file_id, (score, passage)) in ranked {
+            let file = file_stmt.query_row([file_id], Self::map_row_to_file).optional()?;
+            if let Some(file) = file {
+                hits.push(SemanticHit { file, score, passage });
+            }
+        }
+        Ok(hits)
+    }
+
+    fn get_content(&self, path: &str) -> AppResult<Option<String>> {
+        let content = self.conn.query_row(
+            "SELECT fts_content.content FROM files
+             JOIN fts_content ON fts_content.file_id = files.id
+             WHERE files.path = ?",
+            [path],
+            |row| row.get::<_, String>(0)
+        ).optional()?;
+        Ok(content.map(|content| render_slide_marks(&content)))
+    }
+
+    fn record_open(&mut self, path: &str) -> AppResult<()> {
+        self.conn.execute(
+            "UPDATE files SET open_count = open_count + 1, last_opened = ? WHERE path = ?",
+            rusqlite::params![to_unix_secs(SystemTime::now()), path]
+        )?;
+        Ok(())
+    }
+
+    fn get_setting(&self, key: &str) -> AppResult<Option<String>> {
+        let value = self.conn.query_row("SELECT value FROM settings WHERE key = ?", [key], |row| row.get(0))
+            .optional()?;
+        Ok(value)
+    }
+
+    fn set_setting(&mut self, key: &str, value: Option<&str>) -> AppResult<()> {
+        match value {
+            Some(value) => self.conn.execute(
+                "INSERT INTO settings (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                [key, value]
+            )?,
+            None => self.conn.execute("DELETE FROM settings WHERE key = ?", [key])?,
+        };
+        Ok(())
+    }
+}
+
+impl Db {
+
+    /// Connexion en lecture seule pour le pool de lecteurs ; la base doit déjà être initialisée (WAL)
+    pub fn open_read_only(path: &str) -> AppResult<Db> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI,
+        )?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        fuzzy::register(&conn)?;
+        conn.execute_batch(
+            "PRAGMA cache_size = 10000;
+            PRAGMA temp_store = MEMORY;
+            PRAGMA mmap_size = 268435456;"
+        )?;
+
+        Ok(Self {
+            conn,
+            types_cache: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Tri par pertinence : si les termes du nom ne trouvent aucun fichier par sous-chaîne, ils sont
+    /// cherchés approximativement (fautes de frappe). Seulement pour la première page.
+    fn search_with_fallback<T, F>(&self, query: &SearchQuery, run: F) -> AppResult<Vec<T>>
+    where F: Fn(QueryBuilder, &'static str, &'static str) -> AppResult<Vec<T>>
+    {
+        let weights = RankingWeights::from_setting(self.get_setting(RANKING_WEIGHTS)?.as_deref());
+
+        let (builder, order_by, sort_order) = Self::build_search(query, NameMatch::Substring, &weights)?;
+        let can_fall_back = matches!(query.sort(), SortBy::Relevance)
+            && builder.name_pattern.is_some()
+            && query.offset == 0
+            && query.cursor.is_none();
+
+        let results = run(builder, order_by, sort_order)?;
+        if !results.is_empty() || !can_fall_back {
+            return Ok(results);
+        }
+
+        let (builder, order_by, sort_order) = Self::build_search(query, NameMatch::Fuzzy, &weights)?;
+        run(builder, order_by, sort_order)
+    }
+
+    fn build_search(query: &SearchQuery, name_match: NameMatch, weights: &RankingWeights) -> AppResult<(QueryBuilder, &'static str, &'static str)> {
+        let mut builder = QueryBuilder::new();
+        let now = to_unix_secs(SystemTime::now());
+
+        // Syntaxe de la barre de recherche : champs, opérateurs booléens, négation, expressions exactes
+        if let Some(expr) = parse_query(&query.text)? {
+            builder.add_query(&expr, query.search_in_content, name_match, now);
+        }
+
+        if matches!(query.sort(), SortBy::Relevance) {
+            builder.set_ranking(weights.clone(), now);
+        }
 
         if query.filters.is_dir {
             builder.add_simple_condition("is_dir = 1".to_string());
@@ -256,21 +790,40 @@ impl FileRepository for Db {
             builder.params.push(Box::new(max));
         }
 
+        for (column, value) in [("owner", &query.filters.owner), ("`group`", &query.filters.group), ("encoding", &query.filters.encoding)] {
+            if let Some(value) = value.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+                builder.add_condition(format!("{} = ? COLLATE NOCASE", column), Box::new(value.to_string()));
+            }
+        }
+
+        for (column, [min, max]) in [("line_count", query.filters.line_count), ("word_count", query.filters.word_count)] {
+            if min > 0 || max > 0 {
+                let max = if max > 0 { max as i64 } else { i64::MAX };
+                builder.add_simple_condition(format!("{} >= ? AND {} <= ?", column, column));
+                builder.params.push(Box::new(min as i64));
+                builder.params.push(Box::new(max));
+            }
+        }
+
+        for (column, value) in [("mail_from", &query.filters.sender), ("mail_subject", &query.filters.subject)] {
+            if let Some(value) = value.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+                builder.add_contains_condition(column, value);
+            }
+        }
+
         if let Some(path_pattern) = &query.path_pattern {
             if !path_pattern.trim().is_empty() {
-                builder.add_condition(
-                    "path LIKE ?".to_string(),
-                    Box::new(format!("%{}%", path_pattern))
-                );
+                builder.add_substring_condition("path", &TextValue { value: path_pattern.clone(), phrase: true });
             }
         }
 
-        let order_by = match query.sort_by {
+        let order_by = match query.sort() {
             SortBy::Name => "name COLLATE NOCASE",
             SortBy::Size => "size",
             SortBy::LastModified => "last_modified",
             SortBy::CreatedAt => "created_at",
             SortBy::AccessedAt => "accessed_at",
+            SortBy::Relevance => "name COLLATE NOCASE",
         };
 
         let sort_order = match query.sort_order {
@@ -278,86 +831,9 @@ impl FileRepository for Db {
             SortOrder::Desc => "DESC",
         };
 
-        let (sql, params) = builder.build(order_by, sort_order, query.limit, query.offset, query.cursor);
-        self.execute_search_query(&sql, &params)
-    }
-
-    fn reset_data(&self) -> AppResult<()> {
-        self.conn.execute("DELETE FROM files", [])?;
-        self.conn.execute("DELETE FROM types", [])?;
-        self.conn.execute("DELETE FROM paths", [])?;
-        Ok(())
-    }
-
-    fn update_file_index_status(&mut self, file: &File, content_hash: String, is_indexable: bool) -> AppResult<()> {
-        let path_str = file.path.to_str()
-            .ok_or_else(|| AppError::Validation("Invalid file path encoding".to_string()))?;
-
-        let tx = match self.conn.transaction() {
-            Ok(tx) => tx,
-            Err(e) => {
-                tracing::error!("Failed to start transaction: {}", e);
-                return Err(e.into());
-            }
-        };
-
-        // Utiliser un pattern pour garantir le rollback explicite en cas d'erreur
-        let result = (|| -> AppResult<i64> {
-            let file_id: i64 = match tx.query_row(
-                "SELECT id FROM files WHERE path = ?",
-                [path_str],
-                |row| row.get(0)
-            ) {
-                Ok(id) => id,
-                Err(rusqlite::Error::QueryReturnedNoRows) => {
-                    return Err(AppError::NotFound(format!("File not found in database: {}", path_str)));
-                }
-                Err(e) => return Err(e.into()),
-            };
-
-            tx.execute(
-                "INSERT OR REPLACE INTO fts_content (content, file_id) VALUES (?, ?)",
-                rusqlite::params![content_hash, file_id]
-            )?;
-
-            tx.execute(
-                "UPDATE files SET content_indexed = ?, is_indexable = ? WHERE path = ?",
-                rusqlite::params![true, is_indexable, path_str]
-            )?;
-
-            Ok(file_id)
-        })();
-
-        match result {
-            Ok(_) => {
-                tx.commit().map_err(|e| {
-                    tracing::error!("Failed to commit transaction: {}", e);
-                    AppError::Database(e)
-                })?;
-                Ok(())
-            }
-            Err(e) => {
-                if let Err(rollback_err) = tx.rollback() {
-                    tracing::error!("Failed to rollback transaction: {}", rollback_err);
-                } else {
-                    tracing::debug!("Transaction rolled back successfully due to error: {}", e);
-                }
-                Err(e)
-            }
-        }
+        Ok((builder, order_by, sort_order))
     }
 
-    fn get_uncontent_indexed_files(&self) -> AppResult<Vec<File>> {
-        let mut stmt = self.conn.prepare("SELECT * FROM files WHERE content_indexed = 0 AND is_indexable = 1")?;
-        let files: Vec<File> = stmt
-            .query_map([], |row| { Self::map_row_to_file(row) })?
-            .collect::<SqliteResult<Vec<_>>>()?;
-        Ok(files)
-    }
-}
-
-impl Db {
-
     fn execute_search_query(&self, sql: &str, params: &[Box<dyn rusqlite::ToSql>]) -> AppResult<Vec<File>> {
         use std::time::Duration;
         use std::time::Instant;
@@ -594,6 +1070,102 @@ impl Db {
         })
     }
 
+    fn upsert_file(&mut self, files: Vec<File>) -> AppResult<()> {
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let tx = match self.conn.transaction() {
+            Ok(tx) => tx,
+            Err(e) => {
+                tracing::error!("Failed to start upsert transaction: {}", e);
+                return Err(e.into());
+            }
+        };
+
+        let result = (|| -> AppResult<()> {
+            // Le contenu n'est invalidé que si la taille ou la date de modification a changé
+            let mut stmt = tx.prepare("INSERT INTO files (path, name, is_dir, file_type, size, last_modified, created_at, accessed_at, is_indexed, content_indexed, is_indexable, is_hidden, is_readonly, is_system, is_executable, is_symlink, permissions, owner, `group`, mime_type, encoding, line_count, word_count, checksum, is_encrypted) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(path) DO UPDATE SET
+                    name = excluded.name,
+                    is_dir = excluded.is_dir,
+                    file_type = excluded.file_type,
+                    size = excluded.size,
+                    last_modified = excluded.last_modified,
+                    created_at = excluded.created_at,
+                    accessed_at = excluded.accessed_at,
+                    is_indexed = excluded.is_indexed,
+                    is_hidden = excluded.is_hidden,
+                    is_readonly = excluded.is_readonly,
+                    is_system = excluded.is_system,
+                    is_executable = excluded.is_executable,
+                    is_symlink = excluded.is_symlink,
+                    permissions = excluded.permissions,
+                    owner = excluded.owner,
+                    `group` = excluded.`group`,
+                    mime_type = excluded.mime_type,
+                    is_encrypted = excluded.is_encrypted,
+                    checksum = CASE
+                        WHEN files.size IS excluded.size AND files.last_modified IS excluded.last_modified
+                        THEN files.checksum ELSE excluded.checksum END,
+                    content_indexed = CASE
+                        WHEN files.size IS excluded.size AND files.last_modified IS excluded.last_modified
+                        THEN files.content_indexed ELSE 0 END,
+                    is_indexable = CASE
+                        WHEN files.size IS excluded.size AND files.last_modified IS excluded.last_modified
+                        THEN files.is_indexable ELSE excluded.is_indexable END")?;
+
+            for file in &files {
+                let path = file.path.to_str()
+                    .ok_or_else(|| AppError::Validation("Invalid file path encoding".to_string()))?;
+
+                stmt.execute(rusqlite::params![
+                    path,
+                    file.name,
+                    file.is_dir,
+                    file.file_type,
+                    file.size.map(|s| s as i64),
+                    to_unix_secs(file.last_modified),
+                    to_unix_secs(file.created_at),
+                    to_unix_secs(file.accessed_at),
+                    file.is_indexed,
+                    file.content_indexed,
+                    file.is_indexable,
+                    file.is_hidden,
+                    file.is_readonly,
+                    file.is_system,
+                    file.is_executable,
+                    file.is_symlink,
+                    file.permissions,
+                    file.owner,
+                    file.group,
+                    file.mime_type,
+                    file.encoding,
+                    file.line_count,
+                    file.word_count,
+                    file.checksum,
+                    file.is_encrypted
+                ])?;
+            }
+            Ok(())
+        })();
+
+        match result {
+            Ok(_) => {
+                tx.commit().map_err(|e| {
+                    tracing::error!("Failed to commit upsert transaction: {}", e);
+                    AppError::Database(e)
+                })
+            }
+            Err(e) => {
+                if let Err(rollback_err) = tx.rollback() {
+                    tracing::error!("Failed to rollback upsert transaction: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
     fn map_row_to_file(row: &rusqlite::Row) -> rusqlite::Result<File> {
         let size: Option<i64> = row.get(5)?;
         let last_modified_secs: i64 = row.get(6)?;
@@ -630,14 +1202,156 @@ impl Db {
     }
 }
 
+/// Élément virtuel d'un conteneur : chemin `<conteneur>!/<élément>`, propriétaire et visibilité du conteneur
+fn insert_entry(tx: &rusqlite::Transaction, parent: &File, parent_id: i64, entry: &ExtractedEntry) -> AppResult<()> {
+    let path = PathBuf::from(format!("{}{}{}", parent.path.display(), ENTRY_SEPARATOR, entry.path));
+    let modified = entry.modified.unwrap_or_else(|| to_unix_secs(parent.last_modified));
+    let mail = entry.content.mail.clone().unwrap_or_default();
+
+    if let Some(file_type) = &entry.file_type {
+        tx.execute("INSERT OR IGNORE INTO types (name) VALUES (?)", [file_type])?;
+    }
+
+    // Deux éléments de même chemin : seul le premier est gardé
+    let inserted = tx.execute(
+        "INSERT OR IGNORE INTO files (
+            path, name, is_dir, file_type, size, last_modified, created_at, accessed_at,
+            is_indexed, content_indexed, is_indexable, is_hidden, owner, \"group\", encoding, line_count, word_count,
+            parent_id, mail_subject, mail_from, mail_to, mail_date
+        ) VALUES (?1, ?2, 0, ?3, ?4, ?5, ?5, ?6, 1, 1, 1, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        rusqlite::params![
+            path.to_string_lossy(), entry.name, entry.file_type, entry.size as i64, modified,
+            to_unix_secs(parent.accessed_at), parent.is_hidden, parent.owner, parent.group,
+            entry.content.encoding, entry.content.line_count, entry.content.word_count,
+            parent_id, mail.subject, mail.from, mail.to, mail.date,
+        ]
+    )?;
+
+    if inserted > 0 {
+        tx.execute(
+            "INSERT INTO fts_content (content, file_id) VALUES (?, ?)",
+            rusqlite::params![entry.content.text, tx.last_insert_rowid()]
+        )?;
+    }
+    Ok(())
+}
+
+pub fn to_unix_secs(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Découpe l'extrait élargi de `snippet()` en extraits autour des correspondances
+fn highlight_fragments(highlighted: &str, max_snippets: usize) -> Vec<String> {
+    let mut windows: Vec<(usize, usize)> = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(offset) = highlighted[search_from..].find(HIGHLIGHT_START) {
+        let match_start = search_from + offset;
+        let match_end = highlighted[match_start..].find(HIGHLIGHT_END)
+            .map(|end| match_start + end + HIGHLIGHT_END.len())
+            .unwrap_or(highlighted.len());
+        search_from = match_end;
+
+        // Fenêtre de contexte, recalée sur des frontières de mots
+        let mut start = match_start.saturating_sub(SNIPPET_CONTEXT);
+        while !highlighted.is_char_boundary(start) {
+            start += 1;
+        }
+        if start > 0 {
+            if let Some(space) = highlighted[start..match_start].find(char::is_whitespace) {
+                start += space + 1;
+            }
+        }
+
+        let mut end = (match_end + SNIPPET_CONTEXT).min(highlighted.len());
+        while !highlighted.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end < highlighted.len() {
+            if let Some(space) = highlighted[match_end..end].rfind(char::is_whitespace) {
+                end = match_end + space;
+            }
+        }
+
+        // Les fenêtres qui se chevauchent sont fusionnées pour ne jamais couper un marqueur
+        if let Some(last) = windows.last_mut().filter(|last| start <= last.1) {
+            last.1 = last.1.max(end);
+        } else if windows.len() == max_snippets {
+            break;
+        } else {
+            windows.push((start, end));
+        }
+    }
+
+    windows.into_iter()
+        .map(|(start, end)| {
+            let fragment = highlighted[start..end].split_whitespace().collect::<Vec<_>>().join(" ");
+            let prefix = if start > 0 { SNIPPET_ELLIPSIS } else { "" };
+            let suffix = if end < highlighted.len() { SNIPPET_ELLIPSIS } else { "" };
+            format!("{}{}{}", prefix, fragment, suffix)
+        })
+        .collect()
+}
+
+/// Diapositive de la première correspondance d'un extrait, retrouvé dans le contenu de la présentation.
+/// `None` si l'extrait montre déjà la marque de la diapositive avant la correspondance.
+fn fragment_slide(content: &str, fragment: &str) -> Option<u32> {
+    let fragment = fragment.trim_start_matches(SNIPPET_ELLIPSIS).trim_end_matches(SNIPPET_ELLIPSIS);
+    let before_match = fragment.find(HIGHLIGHT_START).unwrap_or(0);
+    if fragment[..before_match].contains(SLIDE_MARK) {
+        return None;
+    }
+    let plain = fragment.replace(HIGHLIGHT_START, "").replace(HIGHLIGHT_END, "");
+    let start = content.find(&plain)?;
+    slide_at(content, start + before_match)
+}
+
+/// Échappe le contenu pour un rendu HTML, puis remplace les marqueurs par `<mark>` / `</mark>`
+fn render_marks(fragment: &str) -> String {
+    let mut rendered = String::with_capacity(fragment.len() + 16);
+    for c in fragment.chars() {
+        match c {
+            '&' => rendered.push_str("&amp;"),
+            '<' => rendered.push_str("&lt;"),
+            '>' => rendered.push_str("&gt;"),
+            '"' => rendered.push_str("&quot;"),
+            '\'' => rendered.push_str("&#39;"),
+            _ => rendered.push(c),
+        }
+    }
+    rendered.replace(HIGHLIGHT_START, "<mark>").replace(HIGHLIGHT_END, "</mark>")
+}
+
+/// Vecteur stocké en BLOB : f32 little-endian bout à bout
+fn vector_to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn blob_to_vector(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+/// Bornes `[début, fin)` couvrant tous les descendants d'un dossier dans l'ordre lexicographique
+fn descendant_bounds(path: &str) -> (String, String) {
+    let separator = std::path::MAIN_SEPARATOR;
+    let path = path.trim_end_matches(separator);
+    let next_char = (separator as u8 + 1) as char;
+    (format!("{}{}", path, separator), format!("{}{}", path, next_char))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::domain::ports::repository::FileRepository;
     use crate::domain::entities::file::File;
-    use crate::domain::entities::search::SearchQuery;
+    use crate::domain::entities::content::MailHeaders;
+    use crate::domain::entities::search::{SearchFilters, SearchQuery};
     use std::path::PathBuf;
-    use std::time::SystemTime;
+    use std::time::{Duration, SystemTime};
     use tempfile::TempDir;
 
     fn create_test_db() -> (Db, TempDir) {
@@ -686,7 +1400,7 @@ mod tests {
         let file = create_test_file("/test/path.txt");
         
         // Tenter une opération qui échouera (fichier non existant dans la DB)
-        let result = db.update_file_index_status(&file, "hash".to_string(), true);
+        let result = db.update_file_index_status(&file, ExtractedContent::from_text("hash".to_string()), true);
         
         // Devrait retourner une erreur NotFound
         assert!(result.is_err());
@@ -759,6 +1473,75 @@ mod tests {
         assert!(elapsed < std::time::Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_upsert_resets_content_on_change() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let file = create_test_file("/test/doc.txt");
+        db.insert(vec![file.clone()]).unwrap();
+        db.update_file_index_status(&file, ExtractedContent::from_text("ancien contenu".to_string()), true).unwrap();
+        db.update_file_index_status(&file, ExtractedContent::from_text("nouveau contenu".to_string()), true).unwrap();
+
+        let fts_rows: i64 = db.conn
+            .query_row("SELECT COUNT(*) FROM fts_content", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(fts_rows, 1);
+
+        // Même taille et même date : le contenu indexé est conservé
+        db.upsert(vec![file.clone()]).unwrap();
+        assert!(db.get_uncontent_indexed_files().unwrap().is_empty());
+
+        // Taille modifiée : le fichier doit être réindexé
+        let mut modified = file.clone();
+        modified.size = Some(200);
+        db.upsert(vec![modified]).unwrap();
+        let pending = db.get_uncontent_indexed_files().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].size, Some(200));
+    }
+
+    #[test]
+    fn test_delete_files_removes_children_and_fts() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let mut folder = create_test_file("/test/folder");
+        folder.is_dir = true;
+        let child = create_test_file("/test/folder/child.txt");
+        let sibling = create_test_file("/test/folder-bis.txt");
+        db.insert(vec![folder, child.clone(), sibling]).unwrap();
+        db.update_file_index_status(&child, ExtractedContent::from_text("contenu".to_string()), true).unwrap();
+
+        let deleted = db.delete_files(&["/test/folder".to_string()]).unwrap();
+        assert_eq!(deleted, 2);
+
+        let remaining = db.search(&SearchQuery { limit: 10, ..Default::default() }).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].path, PathBuf::from("/test/folder-bis.txt"));
+
+        let fts_rows: i64 = db.conn
+            .query_row("SELECT COUNT(*) FROM fts_content", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(fts_rows, 0);
+    }
+
+    #[test]
+    fn test_get_file_signatures_scoped_to_root() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let inside = create_test_file("/test/root/a.txt");
+        let outside = create_test_file("/test/rootless/b.txt");
+        db.insert(vec![inside.clone(), outside]).unwrap();
+
+        let signatures = db.get_file_signatures("/test/root").unwrap();
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].path, "/test/root/a.txt");
+        assert_eq!(signatures[0].size, Some(100));
+        assert_eq!(signatures[0].last_modified, to_unix_secs(inside.last_modified));
+
+        // Séparateur final : mêmes bornes que sans
+        assert_eq!(db.get_file_signatures("/test/root/").unwrap().len(), 1);
+    }
+
     #[test]
     fn test_insert_paths_transaction_rollback() {
         let (mut db, _temp_dir) = create_test_db();
@@ -772,4 +1555,408 @@ mod tests {
         let all_paths = db.get_all_paths().unwrap();
         assert_eq!(all_paths.len(), 2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_search_hits_returns_highlighted_snippets() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let file = create_test_file("/test/facture.pdf");
+        db.insert(vec![file.clone()]).unwrap();
+        let content = format!("{} montant <b>de</b> la facture {} échéance de la facture", "début ".repeat(80), "milieu ".repeat(20));
+        db.update_file_index_status(&file, ExtractedContent::from_text(content), true).unwrap();
+
+        let query = SearchQuery {
+            text: "facture".to_string(),
+            search_in_content: true,
+            ..Default::default()
+        };
+        let hits = db.search_hits(&query).unwrap();
+
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].score.is_some());
+        assert_eq!(hits[0].snippets.len(), 2);
+        assert!(hits[0].snippets.iter().all(|s| s.contains("<mark>facture</mark>")));
+        assert!(hits[0].snippets[0].starts_with('…'));
+        // Le contenu est échappé, seuls les marqueurs restent du HTML
+        assert!(hits[0].snippets[0].contains("&lt;b&gt;de&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn test_init_migrates_fts_content_without_positions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("legacy.db");
+        let db = Db::new(db_path.to_str().unwrap()).unwrap();
+        db.conn.execute_batch(
+            "CREATE VIRTUAL TABLE fts_content USING fts5(
+                content, file_id UNINDEXED,
+                tokenize = 'unicode61 remove_diacritics 2', prefix = '3 4', detail = 'none'
+            );
+            INSERT INTO fts_content (content, file_id) VALUES ('rapport annuel', 1);"
+        ).unwrap();
+
+        db.init().unwrap();
+
+        let schema: String = db.conn
+            .query_row("SELECT sql FROM sqlite_master WHERE name = 'fts_content'", [], |row| row.get(0))
+            .unwrap();
+        assert!(!schema.contains("detail"));
+
+        let snippet: String = db.conn
+            .query_row(
+                "SELECT snippet(fts_content, 0, '[', ']', '…', 8) FROM fts_content WHERE fts_content MATCH 'annuel'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(snippet, "rapport [annuel]");
+    }
+
+    #[test]
+    fn test_indexing_rules_roundtrip_and_root_cleanup() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        // Exclusions par défaut posées par la migration
+        let defaults = db.get_indexing_rules().unwrap();
+        assert!(defaults.rules.iter().any(|rule| rule.pattern == "node_modules" && rule.root.is_none()));
+        assert!(!defaults.respect_ignore_files);
+
+        db.insert_paths(vec!["/projets".to_string()]).unwrap();
+        let rules = IndexingRules {
+            rules: vec![
+                PathRule { pattern: "*.log".to_string(), kind: RuleKind::Exclude, root: None },
+                PathRule { pattern: "src/**".to_string(), kind: RuleKind::Include, root: Some("/projets".to_string()) },
+            ],
+            respect_ignore_files: true,
+        };
+        db.save_indexing_rules(&rules).unwrap();
+        assert_eq!(db.get_indexing_rules().unwrap(), rules);
+
+        // Retirer la racine supprime ses règles, pas les règles globales
+        db.insert_paths(vec!["/autre".to_string()]).unwrap();
+        let remaining = db.get_indexing_rules().unwrap();
+        assert_eq!(remaining.rules, vec![rules.rules[0].clone()]);
+    }
+
+    #[test]
+    fn test_duplicate_candidates_and_checksum_invalidation() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let mut unique = create_test_file("/test/root/unique.txt");
+        unique.size = Some(42);
+        let mut outside = create_test_file("/test/other/copy.txt");
+        outside.size = Some(500);
+        let mut small = create_test_file("/test/root/small.txt");
+        small.size = Some(500);
+        let mut big = create_test_file("/test/root/big.txt");
+        big.size = Some(500);
+        db.insert(vec![create_test_file("/test/root/a.txt"), create_test_file("/test/root/b.txt"), unique, outside, small, big.clone()]).unwrap();
+
+        let all = db.get_duplicate_candidates(&DuplicateQuery::default()).unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(all.iter().all(|c| c.path != "/test/root/unique.txt"));
+
+        // "/test/other/copy.txt" est hors racine : "big" et "small" restent candidats entre eux
+        let scoped = db.get_duplicate_candidates(&DuplicateQuery { root: Some("/test/root".to_string()), min_size: 200 }).unwrap();
+        let paths: Vec<&str> = scoped.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["/test/root/big.txt", "/test/root/small.txt"]);
+
+        db.update_checksums(&[("/test/root/big.txt".to_string(), "abc".to_string())]).unwrap();
+        db.upsert(vec![big.clone()]).unwrap();
+        let checksum = |db: &Db| db.get_duplicate_candidates(&DuplicateQuery::default()).unwrap()
+            .into_iter().find(|c| c.path == "/test/root/big.txt").unwrap().checksum;
+        assert_eq!(checksum(&db), Some("abc".to_string()));
+
+        // Fichier modifié : l'empreinte est invalidée
+        big.last_modified = SystemTime::now() + Duration::from_secs(60);
+        db.upsert(vec![big]).unwrap();
+        assert_eq!(checksum(&db), None);
+    }
+
+    #[test]
+    fn test_content_metadata_is_stored_and_filterable() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let mut notes = create_test_file("/test/notes.txt");
+        notes.owner = Some("alice".to_string());
+        let mut readme = create_test_file("/test/readme.txt");
+        readme.owner = Some("bob".to_string());
+        db.insert(vec![notes.clone(), readme.clone()]).unwrap();
+
+        let source = "première ligne\ndeuxième ligne avec plus de mots\n";
+        db.update_file_index_status(&notes, ExtractedContent::from_source(source.to_string(), source, Some("UTF-8".to_string())), true).unwrap();
+        db.update_file_index_status(&readme, ExtractedContent::from_text("court".to_string()), true).unwrap();
+
+        let search = |filters: SearchFilters| db.search(&SearchQuery { filters, limit: 10, ..Default::default() }).unwrap();
+
+        let results = search(SearchFilters { encoding: Some("utf-8".to_string()), ..Default::default() });
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_count, Some(2));
+        assert_eq!(results[0].word_count, Some(8));
+
+        assert_eq!(search(SearchFilters { word_count: [2, 0], ..Default::default() }).len(), 1);
+        let by_owner = search(SearchFilters { owner: Some("bob".to_string()), ..Default::default() });
+        assert_eq!(by_owner[0].path, PathBuf::from("/test/readme.txt"));
+    }
+
+    #[test]
+    fn test_mailbox_entries_are_searchable_and_replaced() {
+        let (mut db, _temp_dir) = create_test_db();
+        let mailbox = create_test_file("/test/archives.mbox");
+        db.insert(vec![mailbox.clone()]).unwrap();
+
+        let message = |path: &str, subject: &str, from: &str, body: &str| ExtractedEntry {
+            path: path.to_string(),
+            name: subject.to_string(),
+            file_type: Some("eml".to_string()),
+            size: 100,
+            modified: Some(1_772_445_600),
+            content: ExtractedContent {
+                mail: Some(MailHeaders { subject: Some(subject.to_string()), from: Some(from.to_string()), ..Default::default() }),
+                ..ExtractedContent::from_text(body.to_string())
+            },
+        };
+        let extract = |entries: Vec<ExtractedEntry>| ExtractedContent { entries, ..Default::default() };
+        db.update_file_index_status(&mailbox, extract(vec![
+            message("00001.eml", "Budget 2026", "Alice <alice@exemple.fr>", "prévisions trimestrielles"),
+            message("00002.eml", "Congés", "Bob <bob@exemple.fr>", "planning estival"),
+        ]), true).unwrap();
+
+        let search = |db: &Db, text: &str, filters: SearchFilters| -> Vec<PathBuf> {
+            db.search(&SearchQuery { text: text.to_string(), filters, search_in_content: true, limit: 10, ..Default::default() })
+                .unwrap().into_iter().map(|file| file.path).collect()
+        };
+        assert_eq!(search(&db, "trimestrielles", SearchFilters::default()), vec![PathBuf::from("/test/archives.mbox!/00001.eml")]);
+        assert_eq!(search(&db, "", SearchFilters { sender: Some("BOB@".to_string()), ..Default::default() }), vec![PathBuf::from("/test/archives.mbox!/00002.eml")]);
+        assert_eq!(search(&db, "", SearchFilters { subject: Some("budget".to_string()), ..Default::default() }).len(), 1);
+
+        // Les éléments virtuels n'existent pas sur le disque : la resynchronisation les ignore
+        let signatures = db.get_file_signatures("/test").unwrap();
+        assert_eq!(signatures.iter().map(|signature| signature.path.as_str()).collect::<Vec<_>>(), vec!["/test/archives.mbox"]);
+        // ... et les statistiques ne comptent que la boîte, en nombre comme en taille
+        let stat = db.get_stat().unwrap();
+        assert_eq!((stat.nb_files, stat.total_size), (1, 100));
+
+        db.update_file_index_status(&mailbox, extract(vec![message("00001.eml", "Relance", "Alice <alice@exemple.fr>", "rappel")]), true).unwrap();
+        assert!(search(&db, "trimestrielles", SearchFilters::default()).is_empty());
+        assert_eq!(search(&db, "rappel", SearchFilters::default()).len(), 1);
+
+        db.delete_files(&["/test/archives.mbox".to_string()]).unwrap();
+        assert!(search(&db, "rappel", SearchFilters::default()).is_empty());
+    }
+
+    #[test]
+    fn test_structured_query_language() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let file = |path: &str, size: u64, age_days: u64| {
+            let mut file = create_test_file(path);
+            file.name = path.rsplit('/').next().unwrap().to_string();
+            file.file_type = file.name.rsplit('.').next().map(str::to_string);
+            file.size = Some(size);
+            file.last_modified = SystemTime::now() - Duration::from_secs(age_days * 86_400);
+            file
+        };
+        let report = file("/docs/annual_report.pdf", 8 * 1024 * 1024, 3);
+        let draft = file("/docs/annual_report_draft.pdf", 9 * 1024 * 1024, 3);
+        let old = file("/archives/annual_report_2019.pdf", 6 * 1024 * 1024, 400);
+        let small = file("/docs/notes.txt", 1024, 1);
+        db.insert(vec![report.clone(), draft, old, small.clone()]).unwrap();
+        db.update_file_index_status(&report, ExtractedContent::from_text("the annual report of the company".to_string()), true).unwrap();
+        db.update_file_index_status(&small, ExtractedContent::from_text("report annual pending".to_string()), true).unwrap();
+
+        let names = |text: &str, search_in_content: bool| -> Vec<String> {
+            let query = SearchQuery { text: text.to_string(), search_in_content, limit: 10, ..Default::default() };
+            let mut names: Vec<String> = db.search(&query).unwrap().into_iter().map(|f| f.name).collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(names(r#"ext:pdf size:>5MB modified:<2w -draft"#, false), vec!["annual_report.pdf"]);
+        assert_eq!(names("(ext:txt OR path:archives) report", false), vec!["annual_report_2019.pdf"]);
+        assert_eq!(names("notes OR 2019", false), vec!["annual_report_2019.pdf", "notes.txt"]);
+
+        // Contenu : l'expression exacte distingue l'ordre des mots
+        assert_eq!(names(r#""annual report""#, true), vec!["annual_report.pdf"]);
+        assert_eq!(names("annual report", true), vec!["annual_report.pdf", "notes.txt"]);
+        assert_eq!(names("content:pending OR name:draft", false), vec!["annual_report_draft.pdf", "notes.txt"]);
+        assert_eq!(names("ext:pdf -content:company", false), vec!["annual_report_2019.pdf", "annual_report_draft.pdf"]);
+
+        let error = db.search(&SearchQuery { text: "size:>beaucoup".to_string(), ..Default::default() }).unwrap_err();
+        assert!(matches!(error, AppError::Validation(message) if message.contains("position 6")));
+    }
+
+    #[test]
+    fn test_relevance_sort_falls_back_to_fuzzy_names() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let file = |path: &str, size: u64| {
+            let mut file = create_test_file(path);
+            file.name = path.rsplit('/').next().unwrap().to_string();
+            file.file_type = file.name.rsplit('.').next().map(str::to_string);
+            file.size = Some(size);
+            file
+        };
+        db.insert(vec![
+            file("/docs/misreported.txt", 10),
+            file("/docs/annual_report.pdf", 10),
+            file("/docs/report.pdf", 10),
+            file("/docs/report/notes.txt", 10),
+            file("/docs/photo.jpg", 10),
+            file("/docs/big_report.pdf", 5000),
+        ]).unwrap();
+
+        let names = |text: &str, filters: SearchFilters| -> Vec<String> {
+            let query = SearchQuery { text: text.to_string(), filters, sort_by: Some(SortBy::Relevance), limit: 10, ..Default::default() };
+            db.search(&query).unwrap().into_iter().map(|f| f.name).collect()
+        };
+
+        assert_eq!(names("report", SearchFilters::default()), vec![
+            "report.pdf", "annual_report.pdf", "big_report.pdf", "misreported.txt",
+        ]);
+        // Faute de frappe, combinée aux filtres et à la syntaxe de requête
+        let filters = SearchFilters { file_types: vec!["pdf".to_string()], ..Default::default() };
+        assert_eq!(names("reprot size:<1KB", filters), vec!["annual_report.pdf", "report.pdf"]);
+        assert!(names("zzz", SearchFilters::default()).is_empty());
+
+        // Les hits portent aussi le classement
+        let query = SearchQuery { text: "anual".to_string(), sort_by: Some(SortBy::Relevance), limit: 10, ..Default::default() };
+        let hits = db.search_hits(&query).unwrap();
+        assert_eq!(hits.iter().map(|hit| hit.file.name.as_str()).collect::<Vec<_>>(), vec!["annual_report.pdf"]);
+    }
+
+    #[test]
+    fn test_hybrid_relevance_ranking() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let day = Duration::from_secs(86_400);
+        let file = |path: &str, age_days: u32| {
+            let mut file = create_test_file(path);
+            file.name = path.rsplit('/').next().unwrap().to_string();
+            file.last_modified = SystemTime::now() - day * age_days;
+            file
+        };
+        let old = file("/docs/budget_2021.xlsx", 900);
+        let recent = file("/docs/budget_2024.xlsx", 1);
+        let notes = file("/docs/notes.txt", 900);
+        db.insert(vec![old.clone(), recent.clone(), notes.clone()]).unwrap();
+        db.update_file_index_status(&notes, ExtractedContent::from_text("budget budget budget prévisionnel".to_string()), true).unwrap();
+        let long_text = format!("budget {}", "ligne de tableur ".repeat(50));
+        db.update_file_index_status(&recent, ExtractedContent::from_text(long_text), true).unwrap();
+        // Sans autres documents, le terme n'aurait aucun poids BM25
+        for i in 0..20 {
+            let other = file(&format!("/docs/autre_{}.txt", i), 900);
+            db.insert(vec![other.clone()]).unwrap();
+            db.update_file_index_status(&other, ExtractedContent::from_text("compte rendu de réunion".to_string()), true).unwrap();
+        }
+
+        let names = |db: &Db, search_in_content: bool| -> Vec<String> {
+            let query = SearchQuery { text: "budget".to_string(), search_in_content, sort_by: Some(SortBy::Relevance), limit: 10, ..Default::default() };
+            db.search(&query).unwrap().into_iter().map(|f| f.name).collect()
+        };
+
+        // À correspondance de nom égale, le plus récent l'emporte
+        assert_eq!(names(&db, false), vec!["budget_2024.xlsx", "budget_2021.xlsx"]);
+        // Les ouvertures fréquentes font remonter l'ancien fichier
+        for _ in 0..5 {
+            db.record_open(old.path.to_str().unwrap()).unwrap();
+        }
+        assert_eq!(names(&db, false), vec!["budget_2021.xlsx", "budget_2024.xlsx"]);
+
+        // Contenu : le BM25 l'emporte avec les poids par défaut...
+        assert_eq!(names(&db, true), vec!["notes.txt", "budget_2024.xlsx"]);
+        // ...la fraîcheur avec des poids personnalisés
+        let weights = RankingWeights { content: 0.1, recency: 5.0, ..Default::default() };
+        db.set_setting(RANKING_WEIGHTS, Some(&serde_json::to_string(&weights).unwrap())).unwrap();
+        assert_eq!(names(&db, true), vec!["budget_2024.xlsx", "notes.txt"]);
+
+        // Des poids invalides sont ignorés
+        db.set_setting(RANKING_WEIGHTS, Some("{\"content\": -1}")).unwrap();
+        assert_eq!(names(&db, true), vec!["notes.txt", "budget_2024.xlsx"]);
+    }
+
+    #[test]
+    fn test_default_sort_follows_search_text() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let file = |path: &str| {
+            let mut file = create_test_file(path);
+            file.name = path.rsplit('/').next().unwrap().to_string();
+            file
+        };
+        db.insert(vec![file("/docs/annual_report.pdf"), file("/docs/report.pdf"), file("/docs/zeta.txt")]).unwrap();
+
+        let names = |text: &str, sort_by: Option<SortBy>| -> Vec<String> {
+            let query = SearchQuery { text: text.to_string(), sort_by, limit: 10, ..Default::default() };
+            db.search(&query).unwrap().into_iter().map(|f| f.name).collect()
+        };
+
+        // Listage par nom, recherche textuelle par pertinence, repli approximatif compris
+        assert_eq!(names("", None), vec!["annual_report.pdf", "report.pdf", "zeta.txt"]);
+        assert_eq!(names("report", None), vec!["report.pdf", "annual_report.pdf"]);
+        assert_eq!(names("reprot", None), vec!["annual_report.pdf", "report.pdf"]);
+        // Un tri demandé est respecté, sans classement ni repli
+        assert_eq!(names("report", Some(SortBy::Name)), vec!["annual_report.pdf", "report.pdf"]);
+        assert!(names("reprot", Some(SortBy::Name)).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_fallback_considers_every_file() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        for chunk in 0..50 {
+            db.insert((0..1000).map(|i| {
+                let mut file = create_test_file(&format!("/docs/note_{}_{}.txt", chunk, i));
+                file.name = format!("note_{}_{}.txt", chunk, i);
+                file
+            }).collect()).unwrap();
+        }
+        let mut report = create_test_file("/docs/annual_report.pdf");
+        report.name = "annual_report.pdf".to_string();
+        db.insert(vec![report]).unwrap();
+
+        // Le seul fichier correspondant est inséré en dernier
+        let query = SearchQuery { text: "reprot".to_string(), limit: 10, ..Default::default() };
+        let names: Vec<String> = db.search(&query).unwrap().into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["annual_report.pdf"]);
+    }
+
+    #[test]
+    fn test_name_index_follows_inserts_renames_and_deletes() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let file = |path: &str| {
+            let mut file = create_test_file(path);
+            file.name = path.rsplit('/').next().unwrap().to_string();
+            file
+        };
+        db.insert(vec![file("/docs/Rapport_Annuel.pdf"), file("/docs/notes.txt"), file("/archives/vieux.txt")]).unwrap();
+
+        let names = |db: &Db, text: &str, path_pattern: Option<&str>| -> Vec<String> {
+            let query = SearchQuery { text: text.to_string(), path_pattern: path_pattern.map(str::to_string), limit: 10, ..Default::default() };
+            let mut names: Vec<String> = db.search(&query).unwrap().into_iter().map(|f| f.name).collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(names(&db, "port_ann", None), vec!["Rapport_Annuel.pdf"]);
+        assert_eq!(names(&db, "rap*uel", None), vec!["Rapport_Annuel.pdf"]);
+        assert_eq!(names(&db, "t", None), vec!["Rapport_Annuel.pdf", "notes.txt", "vieux.txt"]);
+        assert_eq!(names(&db, "path:chives", None), vec!["vieux.txt"]);
+        assert_eq!(names(&db, "", Some("archiv")), vec!["vieux.txt"]);
+        // `_` et `%` sont des caractères ordinaires
+        assert!(names(&db, "", Some("doc%")).is_empty());
+
+        let mut renamed = file("/docs/notes.txt");
+        renamed.name = "brouillon.txt".to_string();
+        db.upsert(vec![renamed]).unwrap();
+        db.delete_files(&["/archives/vieux.txt".to_string()]).unwrap();
+
+        // Le chemin contient encore « notes », que le repli approximatif retrouverait : seul le nom est interrogé
+        assert!(names(&db, "name:notes", None).is_empty());
+        assert_eq!(names(&db, "brouillon", None), vec!["brouillon.txt"]);
+        assert!(names(&db, "vieux", None).is_empty());
+
+        let integrity = db.conn.execute("INSERT INTO fts_names (fts_names) VALUES ('integrity-check')", []);
+        assert!(integrity.is_ok(), "{:?}", integrity);
+    }
+}
@@ -1,32 +1,44 @@
 use crate::application::events::emitters::{
     emit_event, emit_error_event, emit_started_event, emit_finished_event,
     EVENT_WATCHER_STARTED, EVENT_WATCHER_STOPPED, EVENT_WATCHER_ERROR,
-    EVENT_FILE_CREATED, EVENT_FILE_MODIFIED, EVENT_FILE_DELETED
+    EVENT_FILE_CREATED, EVENT_FILE_MODIFIED, EVENT_FILE_DELETED, EVENT_STAT_UPDATED
 };
+use crate::application::use_cases::apply_file_changes::apply_file_changes;
+use crate::domain::entities::file_event::{FileChange, FileChangeKind};
+use crate::infrastructure::repository::pool::RepositoryPool;
 use crate::shared::errors::{AppError, AppResult};
 use notify::{Watcher, RecursiveMode, Config, PollWatcher, Event, EventKind};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::time::Duration;
 use std::path::Path;
 use tokio::task::JoinHandle;
 use tauri::WebviewWindow;
 use std::sync::{Arc, Mutex};
 
+// Fenêtre de regroupement des événements avant application en base
+const EVENT_BATCH_WINDOW: Duration = Duration::from_millis(500);
+const MAX_EVENTS_PER_BATCH: usize = 1000;
+
+type NotifyResult = notify::Result<Event>;
+
 pub struct AsyncFileWatcher {
     watcher: Option<PollWatcher>,
     is_watching: bool,
     task_handle: Option<JoinHandle<()>>,
     window: WebviewWindow,
     watched_paths: Vec<String>,
+    service_repository: Arc<RepositoryPool>,
 }
 
 impl AsyncFileWatcher {
-    pub fn new(window: WebviewWindow) -> Self {
+    pub fn new(window: WebviewWindow, service_repository: Arc<RepositoryPool>) -> Self {
         Self {
             watcher: None,
             is_watching: false,
             task_handle: None,
             window,
             watched_paths: Vec::new(),
+            service_repository,
         }
     }
 
@@ -76,25 +88,21 @@ impl AsyncFileWatcher {
 
         let window_clone = self.window.clone();
         let window_error_clone = self.window.clone();
+        let service_repository = self.service_repository.clone();
 
-        // Tâche pour écouter les événements de fichiers
+        // Tâche pour écouter les événements de fichiers et maintenir l'index
         let task_handle = tokio::task::spawn(async move {
             tokio::task::spawn_blocking(move || {
                 loop {
-                    match notify_rx.recv() {
-                        Ok(Ok(event)) => {
-                            if let Some(file_event) = convert_notify_event(event) {
-                                emit_file_event(&window_clone, &file_event);
-                            }
-                        },
-                        Ok(Err(e)) => {
-                            let error_msg = format!("File watcher error: {:?}", e);
-                            emit_error_event(&window_clone, EVENT_WATCHER_ERROR, error_msg);
-                        },
-                        Err(_) => {
-                            // Canal fermé, on sort de la boucle
-                            break;
-                        }
+                    let (batch, disconnected) = receive_batch(&notify_rx, &window_clone);
+
+                    if !batch.is_empty() {
+                        process_batch(batch, &service_repository, &window_clone);
+                    }
+
+                    if disconnected {
+                        // Canal fermé, on sort de la boucle
+                        break;
                     }
                 }
             }).await.unwrap_or_else(|e| {
@@ -156,31 +164,83 @@ impl AsyncFileWatcher {
     }
 }
 
-fn convert_notify_event(event: Event) -> Option<(String, String)> {
-    if let Some(path) = event.paths.first() {
-        let path_str = path.to_string_lossy().to_string();
+/// Attend un premier événement puis regroupe ceux qui suivent dans la fenêtre de batch
+fn receive_batch(notify_rx: &Receiver<NotifyResult>, window: &WebviewWindow) -> (Vec<FileChange>, bool) {
+    let mut batch = Vec::new();
+
+    match notify_rx.recv() {
+        Ok(result) => handle_notify_result(result, &mut batch, window),
+        Err(_) => return (batch, true),
+    }
 
-        match event.kind {
-            EventKind::Create(_) => Some((EVENT_FILE_CREATED.to_string(), path_str)),
-            EventKind::Modify(_) => Some((EVENT_FILE_MODIFIED.to_string(), path_str)),
-            EventKind::Remove(_) => Some((EVENT_FILE_DELETED.to_string(), path_str)),
-            _ => None,
+    while batch.len() < MAX_EVENTS_PER_BATCH {
+        match notify_rx.recv_timeout(EVENT_BATCH_WINDOW) {
+            Ok(result) => handle_notify_result(result, &mut batch, window),
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(RecvTimeoutError::Disconnected) => return (batch, true),
+        }
+    }
+
+    (batch, false)
+}
+
+fn handle_notify_result(result: NotifyResult, batch: &mut Vec<FileChange>, window: &WebviewWindow) {
+    match result {
+        Ok(event) => batch.extend(convert_notify_event(event)),
+        Err(e) => {
+            let error_msg = format!("File watcher error: {:?}", e);
+            emit_error_event(window, EVENT_WATCHER_ERROR, error_msg);
         }
-    } else {
-        None
     }
 }
 
-fn emit_file_event(window: &WebviewWindow, event: &(String, String)) {
-    let (event_type, path) = event;
+/// Met à jour l'index puis notifie l'UI, uniquement une fois la base cohérente
+fn process_batch(batch: Vec<FileChange>, service_repository: &Arc<RepositoryPool>, window: &WebviewWindow) {
+    match apply_file_changes(batch, service_repository) {
+        Ok(applied) => {
+            if applied.is_empty() {
+                return;
+            }
+
+            for change in &applied {
+                emit_file_event(window, change);
+            }
+
+            if let Ok(stat) = service_repository.read(|repo| repo.get_stat()) {
+                emit_event(window, EVENT_STAT_UPDATED, stat);
+            }
+        }
+        Err(e) => {
+            tracing::error!("Failed to apply file changes: {}", e);
+            emit_error_event(window, EVENT_WATCHER_ERROR, format!("Index update failed: {}", e));
+        }
+    }
+}
+
+fn convert_notify_event(event: Event) -> Vec<FileChange> {
+    let kind = match event.kind {
+        EventKind::Create(_) => FileChangeKind::Created,
+        EventKind::Modify(_) => FileChangeKind::Modified,
+        EventKind::Remove(_) => FileChangeKind::Deleted,
+        _ => return Vec::new(),
+    };
+
+    event.paths
+        .into_iter()
+        .map(|path| FileChange { path, kind })
+        .collect()
+}
+
+fn emit_file_event(window: &WebviewWindow, change: &FileChange) {
+    let (event_type, label) = match change.kind {
+        FileChangeKind::Created => (EVENT_FILE_CREATED, "created"),
+        FileChangeKind::Modified => (EVENT_FILE_MODIFIED, "modified"),
+        FileChangeKind::Deleted => (EVENT_FILE_DELETED, "deleted"),
+    };
+
     let payload = serde_json::json!({
-        "path": path,
-        "event_type": match event_type.as_str() {
-            EVENT_FILE_CREATED => "created",
-            EVENT_FILE_MODIFIED => "modified",
-            EVENT_FILE_DELETED => "deleted",
-            _ => "unknown"
-        },
+        "path": change.path.to_string_lossy(),
+        "event_type": label,
         "timestamp": chrono::Utc::now().to_rfc3339()
     });
 
@@ -196,12 +256,14 @@ impl Drop for AsyncFileWatcher {
 // Gestionnaire global pour un seul watcher
 pub struct FileWatcherManager {
     watcher: Arc<Mutex<Option<AsyncFileWatcher>>>,
+    service_repository: Arc<RepositoryPool>,
 }
 
 impl FileWatcherManager {
-    pub fn new() -> Self {
+    pub fn new(service_repository: Arc<RepositoryPool>) -> Self {
         Self {
             watcher: Arc::new(Mutex::new(None)),
+            service_repository,
         }
     }
 
@@ -216,7 +278,7 @@ impl FileWatcherManager {
             },
             None => {
                 // Créer un nouveau watcher
-                let mut new_watcher = AsyncFileWatcher::new(window);
+                let mut new_watcher = AsyncFileWatcher::new(window, self.service_repository.clone());
                 new_watcher.start_watching(paths)?;
                 *watcher_guard = Some(new_watcher);
                 Ok(())
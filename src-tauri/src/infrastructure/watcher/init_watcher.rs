@@ -1,41 +1,34 @@
-use crate::application::factories::service_factory::get_service_repository;
 use crate::shared::config::AppState;
 use tauri::Manager;
 
 pub fn start_file_watcher_on_startup(app: &tauri::App, window: tauri::WebviewWindow) {
-    match get_service_repository() {
-        Ok(service_repo) => {
-            match service_repo.get_all_paths() {
-                Ok(paths) => {
-                    if !paths.is_empty() {
-                        tracing::info!("Starting file watcher on startup with {} paths", paths.len());
+    let app_state = app.state::<AppState>();
 
-                        let app_state = app.state::<AppState>();
-                        let watcher_manager = app_state.file_watcher_manager.clone();
+    match app_state.service_repository.read(|repo| repo.get_all_paths()) {
+        Ok(paths) => {
+            if !paths.is_empty() {
+                tracing::info!("Starting file watcher on startup with {} paths", paths.len());
 
-                        tauri::async_runtime::spawn(async move {
-                            match watcher_manager.start_watching(window.clone(), paths.clone()) {
-                                Ok(()) => {
-                                    tracing::info!("File watcher started successfully on startup for {} paths", paths.len());
-                                },
-                                Err(e) => {
-                                    tracing::error!("Failed to start file watcher on startup: {}", e);
-                                    use crate::application::events::emitters::{emit_error_event, EVENT_WATCHER_ERROR};
-                                    emit_error_event(&window, EVENT_WATCHER_ERROR, format!("Auto-start failed: {}", e));
-                                }
-                            }
-                        });
-                    } else {
-                        tracing::info!("No paths configured, file watcher not started");
+                let watcher_manager = app_state.file_watcher_manager.clone();
+
+                tauri::async_runtime::spawn(async move {
+                    match watcher_manager.start_watching(window.clone(), paths.clone()) {
+                        Ok(()) => {
+                            tracing::info!("File watcher started successfully on startup for {} paths", paths.len());
+                        },
+                        Err(e) => {
+                            tracing::error!("Failed to start file watcher on startup: {}", e);
+                            use crate::application::events::emitters::{emit_error_event, EVENT_WATCHER_ERROR};
+                            emit_error_event(&window, EVENT_WATCHER_ERROR, format!("Auto-start failed: {}", e));
+                        }
                     }
-                },
-                Err(e) => {
-                    tracing::error!("Failed to get paths for file watcher: {}", e);
-                }
+                });
+            } else {
+                tracing::info!("No paths configured, file watcher not started");
             }
         },
         Err(e) => {
-            tracing::error!("Failed to get service repository for file watcher: {}", e);
+            tracing::error!("Failed to get paths for file watcher: {}", e);
         }
     }
-}
\ No newline at end of file
+}
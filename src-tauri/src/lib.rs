@@ -50,6 +50,16 @@ pub fn run() {
         // Indexing
         indexing_commands::sync_files_and_folders,
         indexing_commands::start_content_indexing,
+        indexing_commands::get_indexing_rules,
+        indexing_commands::save_indexing_rules,
+        indexing_commands::get_reader_settings,
+        indexing_commands::save_reader_settings,
+
+        // Jobs
+        job_commands::list_jobs,
+        job_commands::cancel_job,
+        job_commands::pause_job,
+        job_commands::resume_job,
 
         // Watcher
         watch_commands::start_file_watcher,
@@ -61,15 +71,26 @@ pub fn run() {
         file_commands::save_paths,
         file_commands::get_all_types,
         file_commands::search_files,
+        file_commands::search_files_with_snippets,
+        file_commands::find_duplicates,
         file_commands::reset_data,
         file_commands::open_file,
+        file_commands::get_ranking_weights,
+        file_commands::save_ranking_weights,
         file_commands::get_all_folders,
         file_commands::get_all_paths,
 
         //AI
         ai_commands::ai_search,
         ai_commands::ai_health_check,
-        ai_commands::ai_list_models
+        ai_commands::ai_list_models,
+        ai_commands::list_ai_providers,
+        ai_commands::get_ai_settings,
+        ai_commands::save_ai_settings,
+        ai_commands::get_embedding_settings,
+        ai_commands::save_embedding_settings,
+        ai_commands::semantic_search,
+        ai_commands::ask_files
     ])
     .setup(|app| {
         if let Some(window) = app.get_webview_window("main") {
@@ -1,25 +1,23 @@
 use std::sync::Arc;
-use std::sync::Mutex;
+use crate::application::jobs::JobManager;
 use crate::infrastructure::watcher::file_watcher::FileWatcherManager;
-use crate::domain::services::file_service::FileService;
-use crate::infrastructure::repository::sqlite::Db;
+use crate::infrastructure::repository::pool::RepositoryPool;
 
 pub struct AppState {
     pub file_watcher_manager: Arc<FileWatcherManager>,
-    pub service_repository: Arc<Mutex<FileService<Db>>>,
+    pub service_repository: Arc<RepositoryPool>,
+    pub job_manager: Arc<JobManager>,
 }
 
 impl AppState {
     pub fn new() -> Result<Self, String> {
-        let service_repository = crate::application::factories::service_factory::get_service_repository()?;
-        
-        // Initialiser la base de données
-        service_repository.init()
-            .map_err(|e| format!("Failed to initialize database: {}", e))?;
-        
+        // Ouvre et initialise la base de données (migrations, connexions en lecture, thread d'écriture)
+        let service_repository = Arc::new(crate::application::factories::service_factory::get_service_repository()?);
+
         Ok(Self {
-            file_watcher_manager: Arc::new(FileWatcherManager::new()),
-            service_repository: Arc::new(Mutex::new(service_repository)),
+            file_watcher_manager: Arc::new(FileWatcherManager::new(service_repository.clone())),
+            service_repository,
+            job_manager: Arc::new(JobManager::new()),
         })
     }
 }
\ No newline at end of file
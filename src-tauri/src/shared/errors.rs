@@ -17,6 +17,15 @@ pub enum AppError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Database schema version {found} is newer than supported version {supported}, please update the application")]
+    SchemaTooNew { found: u32, supported: u32 },
+
+    #[error("Unsupported content: {0}")]
+    Unsupported(String),
+
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -1,6 +1,8 @@
 import {defineStore} from 'pinia';
 import {invoke} from '@tauri-apps/api/core';
-import type {SearchQuery} from '../../types';
+import {listen} from '@tauri-apps/api/event';
+import {AiProvider, JobKind} from '../../types';
+import type {AiProviderInfo, AiReset, AiSettings, AiToken, JobInfo, SearchQuery} from '../../types';
 
 interface AiState {
     isConnected: boolean;
@@ -12,6 +14,12 @@ interface AiState {
     connectionStatus: 'connected' | 'connecting' | 'disconnected' | 'error';
     lastError: string | null;
     apiUrl: string;
+    provider: AiProvider;
+    apiKey: string;
+    hasApiKey: boolean;
+    providers: AiProviderInfo[];
+    streamedResponse: string;
+    currentJobId: number | null;
 }
 
 export const useAiStore = defineStore('ai', {
@@ -24,10 +32,30 @@ export const useAiStore = defineStore('ai', {
         selectedModel: 'llama3.2',
         connectionStatus: 'disconnected',
         lastError: null,
-        apiUrl: 'http://localhost:11434',
+        apiUrl: 'http://localhost:1234',
+        provider: AiProvider.LM_STUDIO,
+        apiKey: '',
+        hasApiKey: false,
+        providers: [],
+        streamedResponse: '',
+        currentJobId: null,
     }),
 
     getters: {
+        settings(): AiSettings {
+            return {
+                provider: this.provider,
+                url: this.apiUrl,
+                model: this.selectedModel,
+                api_key: this.apiKey || null,
+                has_api_key: this.hasApiKey,
+            };
+        },
+
+        usesApiKey(): boolean {
+            return this.providers.some(info => info.provider === this.provider && info.uses_api_key);
+        },
+
         isOperational(): boolean {
             return this.isConnected && this.connectionStatus === 'connected';
         },
@@ -47,7 +75,7 @@ export const useAiStore = defineStore('ai', {
             this.lastError = null;
 
             try {
-                const result = await invoke<boolean>('ai_health_check', { aiUrl: this.apiUrl});
+                const result = await invoke<boolean>('ai_health_check', {settings: this.settings});
                 
                 this.isConnected = result;
                 this.connectionStatus = result ? 'connected' : 'disconnected';
@@ -62,10 +90,10 @@ export const useAiStore = defineStore('ai', {
         },
         async loadModels(): Promise<void> {
             try {
-                const models = await invoke<string[]>('ai_list_models', {aiUrl: this.apiUrl});
+                const models = await invoke<string[]>('ai_list_models', {settings: this.settings});
                 this.availableModels = models;
 
-                if (models.length > 0) {
+                if (models.length > 0 && !models.includes(this.selectedModel)) {
                     this.selectedModel = models[0] as string
                 }
             } catch (error) {
@@ -77,20 +105,69 @@ export const useAiStore = defineStore('ai', {
         async aiSearch(): Promise<SearchQuery | undefined> {
             this.inLoading = true;
             this.isLoaded = false;
+            this.streamedResponse = '';
+            const unlistenStarted = await listen<JobInfo>('job_started', (event) => {
+                if (event.payload.kind === JobKind.AI_QUERY) {
+                    this.currentJobId = event.payload.id;
+                }
+            });
+            const unlistenToken = await listen<AiToken>('ai_token', (event) => {
+                if (event.payload.job_id === this.currentJobId) {
+                    this.streamedResponse += event.payload.token;
+                }
+            });
+            const unlistenReset = await listen<AiReset>('ai_reset', (event) => {
+                if (event.payload.job_id === this.currentJobId) {
+                    this.streamedResponse = '';
+                }
+            });
             try {
+                await this.saveSettings();
                 return await invoke<SearchQuery>('ai_search', {
-                    aiUrl: this.apiUrl,
                     naturalQuery: this.naturalSearch,
-                    model: this.selectedModel
                 });
             } catch (error) {
                 console.error(error);
             } finally {
+                unlistenStarted();
+                unlistenToken();
+                unlistenReset();
+                this.currentJobId = null;
                 this.inLoading = false;
             }
         },
 
+        async cancelAiSearch(): Promise<void> {
+            if (this.currentJobId !== null) {
+                await invoke('cancel_job', {id: this.currentJobId});
+            }
+        },
+
+        async loadSettings(): Promise<void> {
+            const settings = await invoke<AiSettings>('get_ai_settings');
+            this.provider = settings.provider;
+            this.apiUrl = settings.url;
+            this.selectedModel = settings.model;
+            this.apiKey = '';
+            this.hasApiKey = settings.has_api_key ?? false;
+            this.providers = await invoke<AiProviderInfo[]>('list_ai_providers');
+        },
+
+        async saveSettings(): Promise<void> {
+            await invoke('save_ai_settings', {settings: this.settings});
+        },
+
+        /** Change de fournisseur, avec son URL par défaut */
+        async selectProvider(provider: AiProvider): Promise<void> {
+            this.provider = provider;
+            this.apiUrl = this.providers.find(info => info.provider === provider)?.default_url ?? this.apiUrl;
+            await this.init();
+        },
+
         async init() {
+            if (this.providers.length === 0) {
+                await this.loadSettings();
+            }
             await this.checkConnection();
             await this.loadModels();
         }
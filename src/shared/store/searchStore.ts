@@ -1,7 +1,7 @@
 import {defineStore} from 'pinia';
 import {invoke} from '@tauri-apps/api/core';
 import type {File} from '../../types';
-import {DateMode, type SearchQuery, SortBy, SortOrder} from '../../types';
+import {DateMode, type SearchQuery, SortOrder} from '../../types';
 import {DateTime} from 'luxon';
 
 type SearchState = {
@@ -26,7 +26,7 @@ export const useSearchStore = defineStore('search', {
                 date_mode: DateMode.MODIFY,
                 search_in_content: false
             },
-            sort_by: SortBy.NAME,
+            sort_by: null,
             sort_order: SortOrder.ASC,
             limit: 1000,
             offset: 0,
@@ -94,7 +94,7 @@ export const useSearchStore = defineStore('search', {
                     date_mode: DateMode.MODIFY,
                     search_in_content: false
                 },
-                sort_by: SortBy.NAME,
+                sort_by: null,
                 sort_order: SortOrder.ASC,
                 limit: 1000, // Cohérence avec l'état initial
                 offset: 0,
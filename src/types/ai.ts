@@ -0,0 +1,33 @@
+export enum AiProvider {
+    LM_STUDIO = 'LmStudio',
+    LLAMA_CPP = 'LlamaCpp',
+    OLLAMA = 'Ollama',
+    OPENAI_COMPATIBLE = 'OpenAiCompatible',
+}
+
+export interface AiSettings {
+    provider: AiProvider;
+    url: string;
+    model: string;
+    api_key?: string | null;
+    /** Une clé est enregistrée ; elle n'est jamais renvoyée à l'interface */
+    has_api_key?: boolean;
+}
+
+export interface AiProviderInfo {
+    provider: AiProvider;
+    label: string;
+    default_url: string;
+    uses_api_key: boolean;
+}
+
+/** Fragment d'une réponse du modèle en cours de génération (événement `ai_token`) */
+export interface AiToken {
+    job_id: number;
+    token: string;
+}
+
+/** Nouvel essai de la génération : les fragments déjà reçus sont remplacés (événement `ai_reset`) */
+export interface AiReset {
+    job_id: number;
+}
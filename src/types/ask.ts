@@ -0,0 +1,17 @@
+export interface AskQuery {
+    question: string;
+    max_files?: number;
+    token_budget?: number;
+}
+
+/** Passage cité dans la réponse par `[index]` */
+export interface AskSource {
+    index: number;
+    path: string;
+    passage: string;
+}
+
+export interface AskAnswer {
+    answer: string;
+    sources: AskSource[];
+}
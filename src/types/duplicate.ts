@@ -0,0 +1,17 @@
+export interface DuplicateQuery {
+    root: string | null;
+    min_size: number;
+}
+
+export interface DuplicateGroup {
+    checksum: string;
+    size: number;
+    paths: string[];
+    wasted_space: number;
+}
+
+export interface DuplicateReport {
+    groups: DuplicateGroup[];
+    duplicate_files: number;
+    wasted_space: number;
+}
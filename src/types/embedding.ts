@@ -0,0 +1,18 @@
+import type { File } from './file';
+
+export interface EmbeddingSettings {
+    enabled: boolean;
+    url: string;
+    model: string;
+}
+
+export interface SemanticQuery {
+    text: string;
+    limit?: number;
+}
+
+export interface SemanticHit {
+    file: File;
+    score: number;
+    passage: string;
+}
@@ -2,4 +2,10 @@ export * from './setting';
 export * from './stat';
 export * from "./file";
 export * from "./scan";
-export * from "./search";
\ No newline at end of file
+export * from "./search";
+export * from "./rules";
+export * from "./job";
+export * from "./duplicate";
+export * from "./embedding";
+export * from "./ask";
+export * from "./ai";
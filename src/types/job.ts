@@ -0,0 +1,25 @@
+export enum JobKind {
+    SCAN = 'Scan',
+    CONTENT_INDEXING = 'ContentIndexing',
+    CHECKSUMS = 'Checksums',
+    AI_QUERY = 'AiQuery',
+}
+
+export enum JobState {
+    RUNNING = 'Running',
+    PAUSED = 'Paused',
+    CANCELLED = 'Cancelled',
+}
+
+export interface JobInfo {
+    id: number;
+    kind: JobKind;
+    state: JobState;
+    started_at: number;
+}
+
+export interface JobCancelled {
+    id: number;
+    kind: JobKind;
+    message: string;
+}
@@ -0,0 +1,21 @@
+export enum RuleKind {
+    INCLUDE = 'Include',
+    EXCLUDE = 'Exclude',
+}
+
+export interface PathRule {
+    pattern: string;
+    kind: RuleKind;
+    root: string | null;
+}
+
+export interface IndexingRules {
+    rules: PathRule[];
+    respect_ignore_files: boolean;
+}
+
+export interface ReaderSettings {
+    spreadsheet_max_cells: number;
+    archive_max_depth: number;
+    archive_max_size_mb: number;
+}
@@ -21,6 +21,9 @@ export interface InsertProgressPayload {
 export interface ScanFinishedPayload {
     total: number;
     message: string;
+    added: number;
+    updated: number;
+    removed: number;
 }
 
 export interface IndexProgressPayload {
@@ -1,3 +1,5 @@
+import type { File } from './file';
+
 export interface SearchFilters {
     is_dir: boolean;
     folders: string[];
@@ -6,6 +8,13 @@ export interface SearchFilters {
     date_range: [number, number];
     date_mode: DateMode;
     search_in_content: boolean;
+    owner?: string | null;
+    group?: string | null;
+    encoding?: string | null;
+    line_count?: [number, number];
+    word_count?: [number, number];
+    sender?: string | null;
+    subject?: string | null;
 }
 
 export enum SortBy {
@@ -14,6 +23,7 @@ export enum SortBy {
     LAST_MODIFIED = 'LastModified',
     CREATED_AT = 'CreatedAt',
     ACCESSED_AT = 'AccessedAt',
+    RELEVANCE = 'Relevance',
 }
 
 export enum SortOrder {
@@ -29,11 +39,26 @@ export enum DateMode {
 export interface SearchQuery {
     text: string;
     filters: SearchFilters;
-    sort_by: SortBy;
+    /** null : pertinence pour une recherche textuelle, nom pour un simple listage */
+    sort_by: SortBy | null;
     sort_order: SortOrder;
     limit: number;
     offset: number;
     search_in_content: boolean;
     path_pattern: string | null;
     cursor?: number | null;
-}
\ No newline at end of file
+}
+
+export interface SearchHit {
+    file: File;
+    score: number | null;
+    snippets: string[];
+}
+
+export interface RankingWeights {
+    content: number;
+    name: number;
+    recency: number;
+    frecency: number;
+    recency_half_life_days: number;
+}